@@ -44,6 +44,20 @@ pub struct Withdraw<'info> {
     pub lend: Lend<'info>,
 }
 
+#[derive(Accounts, Clone)]
+pub struct UpdateUserAssetConfig<'info> {
+    /// CHECK: OK
+    #[account(signer)]
+    pub user_wallet: AccountInfo<'info>,
+    /// CHECK: OK
+    #[account(mut)]
+    pub user_info: AccountInfo<'info>,
+    /// CHECK: OK
+    pub pool_summaries: AccountInfo<'info>,
+    /// CHECK: OK
+    pub price_summaries: AccountInfo<'info>,
+}
+
 #[derive(Accounts, Clone)]
 pub struct Lend<'info> {
     /// CHECK: OK
@@ -7,19 +7,22 @@ pub fn add_user_and_deposit<'a, 'b, 'c, 'info>(
     pool_id: u8,
     page_id: u16,
 ) -> Result<()> {
+    let accounts = apricot_client::accounts::AddUserAndDepositAccounts {
+        user_wallet: *ctx.accounts.user_wallet.key,
+        user_spl: *ctx.accounts.lend.user_spl.key,
+        user_pages_stats: *ctx.accounts.user_pages_stats.key,
+        users_page: *ctx.accounts.users_page.key,
+        user_info: *ctx.accounts.lend.user_info.key,
+        asset_pool: *ctx.accounts.lend.asset_pool.key,
+        asset_pool_spl: *ctx.accounts.lend.asset_pool_spl.key,
+        pool_summaries: *ctx.accounts.lend.pool_summaries.key,
+        price_summaries: *ctx.accounts.price_summaries.key,
+        system_program: *ctx.accounts.system_program.key,
+        token_program: *ctx.accounts.lend.token_program.key,
+    };
     let ix = apricot_client::instructions::add_user_and_deposit_full(
-        &ctx.accounts.user_wallet.key,
-        &ctx.accounts.lend.user_spl.key,
-        &ctx.accounts.user_pages_stats.key,
-        &ctx.accounts.users_page.key,
-        &ctx.accounts.lend.user_info.key,
-        &ctx.accounts.lend.asset_pool.key,
-        &ctx.accounts.lend.asset_pool_spl.key,
-        &ctx.accounts.lend.pool_summaries.key,
-        &ctx.accounts.price_summaries.key,
-        &ctx.accounts.system_program.key,
-        &ctx.accounts.lend.token_program.key,
-        &ctx.program.key,
+        &accounts,
+        ctx.program.key,
         amount,
         pool_id,
         page_id,
@@ -50,19 +53,17 @@ pub fn deposit<'a, 'b, 'c, 'info>(
     amount: u64,
     pool_id: u8,
 ) -> Result<()> {
-    let ix = apricot_client::instructions::deposit_full(
-        &ctx.accounts.user_wallet.key,
-        &ctx.accounts.lend.user_spl.key,
-        &ctx.accounts.lend.user_info.key,
-        &ctx.accounts.lend.asset_pool.key,
-        &ctx.accounts.lend.asset_pool_spl.key,
-        &ctx.accounts.lend.pool_summaries.key,
-        &ctx.accounts.price_summaries.key,
-        &ctx.accounts.lend.token_program.key,
-        &ctx.program.key,
-        amount,
-        pool_id,
-    );
+    let accounts = apricot_client::accounts::DepositAccounts {
+        user_wallet: *ctx.accounts.user_wallet.key,
+        user_spl: *ctx.accounts.lend.user_spl.key,
+        user_info: *ctx.accounts.lend.user_info.key,
+        asset_pool: *ctx.accounts.lend.asset_pool.key,
+        asset_pool_spl: *ctx.accounts.lend.asset_pool_spl.key,
+        pool_summaries: *ctx.accounts.lend.pool_summaries.key,
+        price_summaries: *ctx.accounts.price_summaries.key,
+        token_program: *ctx.accounts.lend.token_program.key,
+    };
+    let ix = apricot_client::instructions::deposit_full(&accounts, ctx.program.key, amount, pool_id);
     program::invoke_signed(
         &ix,
         &[
@@ -88,21 +89,25 @@ pub fn withdraw<'a, 'b, 'c, 'info>(
     pool_id: u8,
     page_id: u16,
 ) -> Result<()> {
+    let accounts = apricot_client::accounts::WithdrawAccounts {
+        user_wallet: *ctx.accounts.user_wallet.key,
+        user_spl: *ctx.accounts.lend.user_spl.key,
+        user_pages_stats: if withdraw_all { Some(apricot_client::consts::get_user_pages_stats_k()) } else { None },
+        users_page: if withdraw_all { Some(apricot_client::consts::get_users_page_k(page_id)) } else { None },
+        user_info: *ctx.accounts.lend.user_info.key,
+        asset_pool: *ctx.accounts.lend.asset_pool.key,
+        asset_pool_spl: *ctx.accounts.lend.asset_pool_spl.key,
+        pool_summaries: *ctx.accounts.lend.pool_summaries.key,
+        price_summaries: *ctx.accounts.price_summaries.key,
+        base_pda: *ctx.accounts.base_pda.key,
+        token_program: *ctx.accounts.lend.token_program.key,
+    };
     let ix = apricot_client::instructions::withdraw_full(
-        &ctx.accounts.user_wallet.key,
-        &ctx.accounts.lend.user_spl.key,
-        &ctx.accounts.lend.user_info.key,
-        &ctx.accounts.lend.asset_pool.key,
-        &ctx.accounts.lend.asset_pool_spl.key,
-        &ctx.accounts.lend.pool_summaries.key,
-        &ctx.accounts.price_summaries.key,
-        &ctx.accounts.base_pda.key,
-        &ctx.accounts.lend.token_program.key,
-        &ctx.program.key,
+        &accounts,
+        ctx.program.key,
         withdraw_all,
         amount,
         pool_id,
-        page_id,
     );
 
     program::invoke_signed(
@@ -124,6 +129,37 @@ pub fn withdraw<'a, 'b, 'c, 'info>(
     .map_err(Into::into)
 }
 
+pub fn update_user_asset_config<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, UpdateUserAssetConfig<'info>>,
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Result<()> {
+    let accounts = apricot_client::accounts::UpdateUserAssetConfigAccounts {
+        user_wallet: *ctx.accounts.user_wallet.key,
+        user_info: *ctx.accounts.user_info.key,
+        pool_summaries: *ctx.accounts.pool_summaries.key,
+        price_summaries: *ctx.accounts.price_summaries.key,
+    };
+    let ix = apricot_client::instructions::update_user_asset_config_full(
+        &accounts,
+        ctx.program.key,
+        use_as_collateral,
+        pool_id,
+    );
+    program::invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.user_wallet.clone(),
+            ctx.accounts.user_info.clone(),
+            ctx.accounts.pool_summaries.clone(),
+            ctx.accounts.price_summaries.clone(),
+            ctx.program.clone(),
+        ],
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
 pub fn claim_apt_rewards<'a, 'b, 'c, 'info>(
     ctx: CpiContext<'a, 'b, 'c, 'info, ClaimAptRewards<'info>>,
 ) -> Result<()> {
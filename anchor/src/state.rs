@@ -8,7 +8,7 @@ pub struct ApricotUser(apricot_client::state::UserInfo);
 impl anchor_lang::AccountDeserialize for ApricotUser {
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
         let user = if buf.len() > 0 {
-            Ok(*apricot_client::state::UserInfo::from_bytes(buf))
+            Ok(apricot_client::state::UserInfo::from_bytes(buf))
         } else {
             Err(ProgramError::UninitializedAccount)
         };
@@ -39,7 +39,7 @@ pub struct ApricotPool(apricot_client::state::AssetPool);
 impl anchor_lang::AccountDeserialize for ApricotPool {
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
         let pool = if buf.len() > 0 {
-            Ok(*apricot_client::state::AssetPool::from_bytes(buf))
+            Ok(apricot_client::state::AssetPool::from_bytes(buf))
         } else {
             Err(ProgramError::UninitializedAccount)
         };
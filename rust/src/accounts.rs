@@ -0,0 +1,870 @@
+use solana_program::{pubkey::Pubkey, system_program, sysvar};
+use spl_token;
+
+use crate::config::{self, ConfigError};
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::lp::LpRoute;
+use crate::registry::PoolRegistry;
+
+// Accounts of each Apricot instruction, in the order the program expects them. resolve() derives
+// all of them for env, user_spl defaults to the wallet's associated token account of the pool's
+// mint and fails with ConfigError::UnknownPoolId when the pool isn't in config.
+// resolve_with_registry() does the same for the env and pools of a PoolRegistry, so pools loaded
+// from a file work too. from_user_spl() takes the token accounts instead and never fails. Any
+// field can be overridden before passing the struct to the *_full builder.
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RefreshUserAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub pool_summaries: Pubkey,
+}
+
+impl RefreshUserAccounts {
+    pub fn resolve(user_wallet: &Pubkey, env: &ApricotEnv) -> Self {
+        RefreshUserAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            pool_summaries: env.pool_summaries,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AddUserAndDepositAccounts {
+    pub user_wallet: Pubkey,
+    pub user_spl: Pubkey,
+    pub user_pages_stats: Pubkey,
+    pub users_page: Pubkey,
+    pub user_info: Pubkey,
+    pub asset_pool: Pubkey,
+    pub asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl AddUserAndDepositAccounts {
+    // page_id is the users page the user is added to, see utils::get_best_page_id
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        page_id: u16,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = consts::try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            page_id,
+            env,
+        ))
+    }
+
+    pub fn resolve_with_registry(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        page_id: u16,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = registry.try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            page_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        user_wallet: &Pubkey,
+        user_spl: &Pubkey,
+        pool_id: u8,
+        page_id: u16,
+        env: &ApricotEnv,
+    ) -> Self {
+        AddUserAndDepositAccounts {
+            user_wallet: *user_wallet,
+            user_spl: *user_spl,
+            user_pages_stats: env.user_stats,
+            users_page: env.users_page_k(page_id),
+            user_info: env.user_info_k(user_wallet),
+            asset_pool: env.asset_pool_k(pool_id),
+            asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DepositAccounts {
+    pub user_wallet: Pubkey,
+    pub user_spl: Pubkey,
+    pub user_info: Pubkey,
+    pub asset_pool: Pubkey,
+    pub asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl DepositAccounts {
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = consts::try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(user_wallet, &user_spl, pool_id, env))
+    }
+    pub fn resolve_with_registry(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = registry.try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        user_wallet: &Pubkey,
+        user_spl: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        DepositAccounts {
+            user_wallet: *user_wallet,
+            user_spl: *user_spl,
+            user_info: env.user_info_k(user_wallet),
+            asset_pool: env.asset_pool_k(pool_id),
+            asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+// user_pages_stats and users_page are only sent with CMD_WITHDRAW_AND_REMOVE_USER
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WithdrawAccounts {
+    pub user_wallet: Pubkey,
+    pub user_spl: Pubkey,
+    pub user_pages_stats: Option<Pubkey>,
+    pub users_page: Option<Pubkey>,
+    pub user_info: Pubkey,
+    pub asset_pool: Pubkey,
+    pub asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub base_pda: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl WithdrawAccounts {
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = consts::try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(user_wallet, &user_spl, pool_id, env))
+    }
+    pub fn resolve_with_registry(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = registry.try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        user_wallet: &Pubkey,
+        user_spl: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        WithdrawAccounts {
+            user_wallet: *user_wallet,
+            user_spl: *user_spl,
+            user_pages_stats: None,
+            users_page: None,
+            user_info: env.user_info_k(user_wallet),
+            asset_pool: env.asset_pool_k(pool_id),
+            asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            base_pda: env.base_pda,
+            token_program: spl_token::ID,
+        }
+    }
+
+    // withdraws and frees the user's slot in page_id (UserInfo.page_id)
+    pub fn resolve_remove_user(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        page_id: u16,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        Ok(Self::resolve(user_wallet, pool_id, env)?.removing_user(page_id, env))
+    }
+
+    pub fn removing_user(self, page_id: u16, env: &ApricotEnv) -> Self {
+        WithdrawAccounts {
+            user_pages_stats: Some(env.user_stats),
+            users_page: Some(env.users_page_k(page_id)),
+            ..self
+        }
+    }
+
+    pub fn removes_user(&self) -> bool {
+        self.user_pages_stats.is_some() && self.users_page.is_some()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BorrowAccounts {
+    pub user_wallet: Pubkey,
+    pub user_spl: Pubkey,
+    pub user_info: Pubkey,
+    pub asset_pool: Pubkey,
+    pub asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub base_pda: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl BorrowAccounts {
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = consts::try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(user_wallet, &user_spl, pool_id, env))
+    }
+    pub fn resolve_with_registry(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = registry.try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        user_wallet: &Pubkey,
+        user_spl: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        BorrowAccounts {
+            user_wallet: *user_wallet,
+            user_spl: *user_spl,
+            user_info: env.user_info_k(user_wallet),
+            asset_pool: env.asset_pool_k(pool_id),
+            asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            base_pda: env.base_pda,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RepayAccounts {
+    pub user_wallet: Pubkey,
+    pub user_spl: Pubkey,
+    pub user_info: Pubkey,
+    pub asset_pool: Pubkey,
+    pub asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl RepayAccounts {
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = consts::try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(user_wallet, &user_spl, pool_id, env))
+    }
+    pub fn resolve_with_registry(
+        user_wallet: &Pubkey,
+        pool_id: u8,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let user_spl = registry.try_get_user_spl_k(user_wallet, pool_id)?;
+        Ok(Self::from_user_spl(
+            user_wallet,
+            &user_spl,
+            pool_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        user_wallet: &Pubkey,
+        user_spl: &Pubkey,
+        pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        RepayAccounts {
+            user_wallet: *user_wallet,
+            user_spl: *user_spl,
+            user_info: env.user_info_k(user_wallet),
+            asset_pool: env.asset_pool_k(pool_id),
+            asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, pool_id),
+            pool_summaries: env.pool_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExternLiquidateAccounts {
+    pub liquidated_wallet: Pubkey,
+    pub liquidator_wallet: Pubkey,
+    pub user_info: Pubkey, // of liquidated_wallet
+    pub base_pda: Pubkey,
+    pub liquidator_collateral_spl: Pubkey,
+    pub liquidator_borrowed_spl: Pubkey,
+    pub collateral_asset_pool: Pubkey,
+    pub collateral_asset_pool_spl: Pubkey,
+    pub borrowed_asset_pool: Pubkey,
+    pub borrowed_asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl ExternLiquidateAccounts {
+    pub fn resolve(
+        liquidated_wallet: &Pubkey,
+        liquidator_wallet: &Pubkey,
+        collateral_pool_id: u8,
+        borrowed_pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Result<Self, ConfigError> {
+        let collateral_spl = consts::try_get_user_spl_k(liquidator_wallet, collateral_pool_id)?;
+        let borrowed_spl = consts::try_get_user_spl_k(liquidator_wallet, borrowed_pool_id)?;
+        Ok(Self::from_user_spl(
+            liquidated_wallet,
+            liquidator_wallet,
+            &collateral_spl,
+            &borrowed_spl,
+            collateral_pool_id,
+            borrowed_pool_id,
+            env,
+        ))
+    }
+
+    pub fn resolve_with_registry(
+        liquidated_wallet: &Pubkey,
+        liquidator_wallet: &Pubkey,
+        collateral_pool_id: u8,
+        borrowed_pool_id: u8,
+        registry: &PoolRegistry,
+    ) -> Result<Self, ConfigError> {
+        let collateral_spl = registry.try_get_user_spl_k(liquidator_wallet, collateral_pool_id)?;
+        let borrowed_spl = registry.try_get_user_spl_k(liquidator_wallet, borrowed_pool_id)?;
+        Ok(Self::from_user_spl(
+            liquidated_wallet,
+            liquidator_wallet,
+            &collateral_spl,
+            &borrowed_spl,
+            collateral_pool_id,
+            borrowed_pool_id,
+            &registry.env,
+        ))
+    }
+
+    pub fn from_user_spl(
+        liquidated_wallet: &Pubkey,
+        liquidator_wallet: &Pubkey,
+        liquidator_collateral_spl: &Pubkey,
+        liquidator_borrowed_spl: &Pubkey,
+        collateral_pool_id: u8,
+        borrowed_pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        ExternLiquidateAccounts {
+            liquidated_wallet: *liquidated_wallet,
+            liquidator_wallet: *liquidator_wallet,
+            user_info: env.user_info_k(liquidated_wallet),
+            base_pda: env.base_pda,
+            liquidator_collateral_spl: *liquidator_collateral_spl,
+            liquidator_borrowed_spl: *liquidator_borrowed_spl,
+            collateral_asset_pool: env.asset_pool_k(collateral_pool_id),
+            collateral_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, collateral_pool_id),
+            borrowed_asset_pool: env.asset_pool_k(borrowed_pool_id),
+            borrowed_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, borrowed_pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UpdateUserConfigAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+}
+
+impl UpdateUserConfigAccounts {
+    pub fn resolve(user_wallet: &Pubkey, env: &ApricotEnv) -> Self {
+        UpdateUserConfigAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UpdateUserAssetConfigAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+}
+
+impl UpdateUserAssetConfigAccounts {
+    pub fn resolve(user_wallet: &Pubkey, env: &ApricotEnv) -> Self {
+        UpdateUserAssetConfigAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+        }
+    }
+}
+
+// followed by the swap keys of the market, see SwapRoute::swap_keys()
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MarginSwapAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub base_pda: Pubkey,
+    pub sell_asset_pool: Pubkey,
+    pub sell_asset_pool_spl: Pubkey,
+    pub buy_asset_pool: Pubkey,
+    pub buy_asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl MarginSwapAccounts {
+    pub fn resolve(
+        user_wallet: &Pubkey,
+        sell_pool_id: u8,
+        buy_pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        MarginSwapAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            base_pda: env.base_pda,
+            sell_asset_pool: env.asset_pool_k(sell_pool_id),
+            sell_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, sell_pool_id),
+            buy_asset_pool: env.asset_pool_k(buy_pool_id),
+            buy_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, buy_pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+// shared by lp create and lp redeem, followed by LpRoute::deposit_keys()/withdraw_keys() and
+// the lp pool's stake table when the pool needs second stake
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpOpAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub base_pda: Pubkey,
+    pub left_asset_pool: Pubkey,
+    pub left_asset_pool_spl: Pubkey,
+    pub right_asset_pool: Pubkey,
+    pub right_asset_pool_spl: Pubkey,
+    pub lp_asset_pool: Pubkey,
+    pub lp_asset_pool_spl: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl LpOpAccounts {
+    pub fn resolve(user_wallet: &Pubkey, route: &LpRoute, env: &ApricotEnv) -> Self {
+        LpOpAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            base_pda: env.base_pda,
+            left_asset_pool: env.asset_pool_k(route.left_pool_id),
+            left_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, route.left_pool_id),
+            right_asset_pool: env.asset_pool_k(route.right_pool_id),
+            right_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, route.right_pool_id),
+            lp_asset_pool: env.asset_pool_k(route.lp_pool_id),
+            lp_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, route.lp_pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpOpCheckAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub left_asset_pool: Pubkey,
+    pub left_asset_pool_spl: Pubkey,
+    pub right_asset_pool: Pubkey,
+    pub right_asset_pool_spl: Pubkey,
+    pub lp_asset_pool: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub instructions_sysvar: Pubkey,
+}
+
+impl LpOpCheckAccounts {
+    pub fn resolve(user_wallet: &Pubkey, route: &LpRoute, env: &ApricotEnv) -> Self {
+        LpOpCheckAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            left_asset_pool: env.asset_pool_k(route.left_pool_id),
+            left_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, route.left_pool_id),
+            right_asset_pool: env.asset_pool_k(route.right_pool_id),
+            right_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, route.right_pool_id),
+            lp_asset_pool: env.asset_pool_k(route.lp_pool_id),
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            instructions_sysvar: sysvar::instructions::ID,
+        }
+    }
+
+    // the check accounts are a subset of the accounts of the bracketed lp_create/lp_redeem
+    pub fn from_lp_op(op: &LpOpAccounts) -> Self {
+        LpOpCheckAccounts {
+            user_wallet: op.user_wallet,
+            user_info: op.user_info,
+            left_asset_pool: op.left_asset_pool,
+            left_asset_pool_spl: op.left_asset_pool_spl,
+            right_asset_pool: op.right_asset_pool,
+            right_asset_pool_spl: op.right_asset_pool_spl,
+            lp_asset_pool: op.lp_asset_pool,
+            pool_summaries: op.pool_summaries,
+            price_summaries: op.price_summaries,
+            instructions_sysvar: sysvar::instructions::ID,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpOpEndcheckAccounts {
+    pub pool_summaries: Pubkey,
+}
+
+impl LpOpEndcheckAccounts {
+    pub fn resolve(env: &ApricotEnv) -> Self {
+        LpOpEndcheckAccounts {
+            pool_summaries: env.pool_summaries,
+        }
+    }
+}
+
+// shared by lp stake and lp unstake, followed by the farm's stake keys
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpStakeAccounts {
+    pub pool_summaries: Pubkey,
+    pub lp_asset_pool_spl: Pubkey,
+    pub base_pda: Pubkey,
+}
+
+impl LpStakeAccounts {
+    pub fn resolve(lp_pool_id: u8, env: &ApricotEnv) -> Self {
+        LpStakeAccounts {
+            pool_summaries: env.pool_summaries,
+            lp_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, lp_pool_id),
+            base_pda: env.base_pda,
+        }
+    }
+}
+
+// signer is the apricot refresher for orca, a placeholder (clock sysvar) for raydium. Followed by
+// floating_lp and base_pda for orca, base_pda for raydium, then the farm's stake keys.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpStakeSecondAccounts {
+    pub signer: Pubkey,
+    pub lp_asset_pool: Pubkey,
+    pub lp_asset_pool_spl: Pubkey,
+    pub stake_table: Pubkey,
+}
+
+impl LpStakeSecondAccounts {
+    pub fn resolve(signer: &Pubkey, lp_pool_id: u8, env: &ApricotEnv) -> Self {
+        LpStakeSecondAccounts {
+            signer: *signer,
+            lp_asset_pool: env.asset_pool_k(lp_pool_id),
+            lp_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, lp_pool_id),
+            stake_table: env.asset_pool_stake_table_k(lp_pool_id),
+        }
+    }
+}
+
+// followed by floating_lp and base_pda for orca, base_pda, left/right asset pools and pool
+// summaries for raydium, then the farm's stake keys.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpUnstakeSecondAccounts {
+    pub unstake_identity: Pubkey,
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub lp_asset_pool: Pubkey,
+    pub lp_asset_pool_spl: Pubkey,
+    pub stake_table: Pubkey,
+}
+
+impl LpUnstakeSecondAccounts {
+    pub fn resolve(
+        unstake_identity: &Pubkey,
+        user_wallet: &Pubkey,
+        lp_pool_id: u8,
+        env: &ApricotEnv,
+    ) -> Self {
+        LpUnstakeSecondAccounts {
+            unstake_identity: *unstake_identity,
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            lp_asset_pool: env.asset_pool_k(lp_pool_id),
+            lp_asset_pool_spl: env.asset_pool_spl_k(&spl_token::ID, lp_pool_id),
+            stake_table: env.asset_pool_stake_table_k(lp_pool_id),
+        }
+    }
+}
+
+// the program takes user_wallet twice, once as signer
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MakeLmRewardClaimableAccounts {
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub pool_summaries: Pubkey,
+}
+
+impl MakeLmRewardClaimableAccounts {
+    pub fn resolve(user_wallet: &Pubkey, env: &ApricotEnv) -> Self {
+        MakeLmRewardClaimableAccounts {
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            pool_summaries: env.pool_summaries,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClaimAptLmRewardAccounts {
+    pub base_pda: Pubkey,
+    pub user_wallet: Pubkey,
+    pub user_info: Pubkey,
+    pub user_apt_spl: Pubkey,
+    pub lm_apt_vault: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl ClaimAptLmRewardAccounts {
+    pub fn resolve(user_wallet: &Pubkey, env: &ApricotEnv) -> Self {
+        ClaimAptLmRewardAccounts {
+            base_pda: env.base_pda,
+            user_wallet: *user_wallet,
+            user_info: env.user_info_k(user_wallet),
+            user_apt_spl: consts::get_user_spl_k(user_wallet, config::apt::POOL_ID),
+            lm_apt_vault: env.lm_apt_vault,
+            pool_summaries: env.pool_summaries,
+            price_summaries: env.price_summaries,
+            token_program: spl_token::ID,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod accounts_test {
+    use super::*;
+    use crate::decoder::ApricotInstruction;
+    use crate::instructions;
+    use solana_program::instruction::Instruction;
+
+    fn decode(ix: &Instruction) -> ApricotInstruction {
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        ApricotInstruction::decode(&ix.program_id, &keys, &ix.data).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_matches_consts() {
+        let wallet = Pubkey::new_unique();
+        let accounts = DepositAccounts::resolve(&wallet, 3, &ApricotEnv::mainnet()).unwrap();
+        assert_eq!(accounts.user_info, consts::get_user_info_k(&wallet));
+        assert_eq!(accounts.user_spl, consts::get_user_spl_k(&wallet, 3));
+        assert_eq!(accounts.asset_pool, consts::get_asset_pool_k(3));
+        assert_eq!(
+            accounts.asset_pool_spl,
+            consts::get_asset_pool_spl_k(&spl_token::ID, 3)
+        );
+        assert_eq!(accounts.pool_summaries, consts::get_pool_summaries_k());
+        assert_eq!(accounts.price_summaries, consts::get_price_summaries_k());
+    }
+
+    #[test]
+    fn test_resolve_unknown_pool() {
+        let env = ApricotEnv::mainnet();
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+        assert!(config::pool_by_id(7).is_none());
+
+        assert_eq!(
+            DepositAccounts::resolve(&wallet, 7, &env),
+            Err(ConfigError::UnknownPoolId(7))
+        );
+        assert_eq!(
+            WithdrawAccounts::resolve_remove_user(&wallet, 7, 0, &env),
+            Err(ConfigError::UnknownPoolId(7))
+        );
+        assert_eq!(
+            ExternLiquidateAccounts::resolve(&wallet, &spl, 3, 7, &env),
+            Err(ConfigError::UnknownPoolId(7))
+        );
+
+        // builders that are given the token account don't look up the mint
+        assert_eq!(
+            DepositAccounts::from_user_spl(&wallet, &spl, 7, &env).user_spl,
+            spl
+        );
+        let ix = instructions::deposit(&wallet, &spl, 100, 7);
+        assert_eq!(ix.accounts[1].pubkey, spl);
+        let ix = instructions::extern_liquidate(&wallet, &wallet, &spl, &spl, 1, 1, 7, 3);
+        assert_eq!(ix.accounts[4].pubkey, spl);
+    }
+
+    #[test]
+    fn test_resolve_with_registry() {
+        let wallet = Pubkey::new_unique();
+        let liquidator = Pubkey::new_unique();
+        let mut registry = PoolRegistry::builtin();
+        registry.env = ApricotEnv::alpha();
+        let mut pool = config::pool_by_id(3).unwrap().clone();
+        pool.pool_id = 7;
+        pool.name = "NEW".into();
+        pool.mint = Pubkey::new_unique();
+        registry.pools.push(pool);
+        assert_eq!(registry.validate(), Ok(()));
+
+        let accounts = DepositAccounts::resolve_with_registry(&wallet, 7, &registry).unwrap();
+        let user_spl = consts::get_associated_token_k(&wallet, &registry.by_id(7).unwrap().mint);
+        assert_eq!(accounts.user_spl, user_spl);
+        assert_eq!(
+            accounts,
+            DepositAccounts::from_user_spl(&wallet, &user_spl, 7, &registry.env)
+        );
+        assert_eq!(accounts.asset_pool, registry.env.asset_pool_k(7));
+        let accounts =
+            AddUserAndDepositAccounts::resolve_with_registry(&wallet, 7, 5, &registry).unwrap();
+        assert_eq!(accounts.users_page, registry.env.users_page_k(5));
+        assert_eq!(accounts.user_spl, user_spl);
+        let accounts = WithdrawAccounts::resolve_with_registry(&wallet, 7, &registry).unwrap();
+        assert_eq!(accounts.user_spl, user_spl);
+        let accounts = BorrowAccounts::resolve_with_registry(&wallet, 7, &registry).unwrap();
+        assert_eq!(accounts.user_spl, user_spl);
+        let accounts = RepayAccounts::resolve_with_registry(&wallet, 7, &registry).unwrap();
+        assert_eq!(accounts.user_spl, user_spl);
+        let accounts =
+            ExternLiquidateAccounts::resolve_with_registry(&wallet, &liquidator, 3, 7, &registry)
+                .unwrap();
+        let borrowed_spl = registry.try_get_user_spl_k(&liquidator, 7).unwrap();
+        assert_eq!(accounts.liquidator_borrowed_spl, borrowed_spl);
+        assert_eq!(accounts.user_info, registry.env.user_info_k(&wallet));
+
+        registry.pools.retain(|pool| pool.pool_id != 7);
+        assert_eq!(
+            DepositAccounts::resolve_with_registry(&wallet, 7, &registry),
+            Err(ConfigError::UnknownPoolId(7))
+        );
+    }
+
+    #[test]
+    fn test_full_builders_round_trip() {
+        let env = ApricotEnv::mainnet();
+        let wallet = Pubkey::new_unique();
+
+        let accounts = DepositAccounts::resolve(&wallet, 3, &env).unwrap();
+        match decode(&instructions::deposit_full(
+            &accounts,
+            &env.program_id,
+            100,
+            3,
+        )) {
+            ApricotInstruction::Deposit {
+                accounts: decoded, ..
+            } => assert_eq!(decoded, accounts),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let accounts = WithdrawAccounts::resolve(&wallet, 3, &env).unwrap();
+        match decode(&instructions::withdraw_full(
+            &accounts,
+            &env.program_id,
+            false,
+            100,
+            3,
+        )) {
+            ApricotInstruction::Withdraw {
+                accounts: decoded, ..
+            } => assert_eq!(decoded, accounts),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let accounts = WithdrawAccounts::resolve_remove_user(&wallet, 3, 321, &env).unwrap();
+        assert!(accounts.removes_user());
+        match decode(&instructions::withdraw_full(
+            &accounts,
+            &env.program_id,
+            true,
+            0,
+            3,
+        )) {
+            ApricotInstruction::WithdrawAndRemoveUser {
+                accounts: decoded, ..
+            } => {
+                assert_eq!(decoded, accounts)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::state::RawAmt;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/**
+ * How a conversion to an integer amount treats the fractional part. Down is what the contract does
+ * when it turns a RawAmt into a token transfer, Up is the safe side for amounts owed (e.g. repays).
+*/
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+    Nearest,
+}
+
+impl Rounding {
+    pub(crate) fn round(self, value: f64) -> f64 {
+        match self {
+            Rounding::Down => value.floor(),
+            Rounding::Up => value.ceil(),
+            Rounding::Nearest => value.round(),
+        }
+    }
+}
+
+/**
+ * Amount in the smallest unit of a mint (lamports for SOL), what instructions take and SPL token
+ * accounts hold.
+*/
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct NativeAmount(pub u64);
+
+/**
+ * Amount in whole tokens as shown in a UI, e.g. 1.5 SOL for NativeAmount(1_500_000_000).
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct TokenAmount(pub f64);
+
+/**
+ * Value in USD.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct UsdAmount(pub f64);
+
+fn decimal_multiplier(decimals: u8) -> f64 {
+    10f64.powi(decimals as i32)
+}
+
+impl NativeAmount {
+    pub fn to_token_amount(self, decimals: u8) -> TokenAmount {
+        TokenAmount(self.0 as f64 / decimal_multiplier(decimals))
+    }
+
+    // price_in_usd is per whole token, as in PriceSource and sdk-ts
+    pub fn to_usd_amount(self, decimals: u8, price_in_usd: f64) -> UsdAmount {
+        self.to_token_amount(decimals).to_usd_amount(price_in_usd)
+    }
+
+    pub fn to_raw_amount(self) -> RawAmt {
+        RawAmt::from_native_amount(self.0)
+    }
+
+    pub fn checked_add(self, other: NativeAmount) -> Option<NativeAmount> {
+        self.0.checked_add(other.0).map(NativeAmount)
+    }
+
+    pub fn checked_sub(self, other: NativeAmount) -> Option<NativeAmount> {
+        self.0.checked_sub(other.0).map(NativeAmount)
+    }
+
+    pub fn saturating_sub(self, other: NativeAmount) -> NativeAmount {
+        NativeAmount(self.0.saturating_sub(other.0))
+    }
+}
+
+impl TokenAmount {
+    /**
+     * None for negative, NaN or out of range amounts rather than the silent clamping of an `as`
+     * cast.
+     */
+    pub fn to_native_amount(self, decimals: u8, rounding: Rounding) -> Option<NativeAmount> {
+        let native = rounding.round(self.0 * decimal_multiplier(decimals));
+        if native.is_nan() || native < 0.0 || native >= u64::MAX as f64 {
+            return None;
+        }
+        Some(NativeAmount(native as u64))
+    }
+
+    pub fn to_usd_amount(self, price_in_usd: f64) -> UsdAmount {
+        UsdAmount(self.0 * price_in_usd)
+    }
+}
+
+impl UsdAmount {
+    // None for a non-positive price
+    pub fn to_token_amount(self, price_in_usd: f64) -> Option<TokenAmount> {
+        if price_in_usd > 0.0 {
+            Some(TokenAmount(self.0 / price_in_usd))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<u64> for NativeAmount {
+    fn from(amount: u64) -> Self {
+        NativeAmount(amount)
+    }
+}
+
+impl From<NativeAmount> for u64 {
+    fn from(amount: NativeAmount) -> Self {
+        amount.0
+    }
+}
+
+impl From<NativeAmount> for RawAmt {
+    fn from(amount: NativeAmount) -> Self {
+        amount.to_raw_amount()
+    }
+}
+
+impl Display for NativeAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for TokenAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for UsdAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "${:.2}", self.0)
+    }
+}
+
+#[cfg(test)]
+pub mod amount_test {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let sol = NativeAmount(1_500_000_000);
+        assert_eq!(sol.to_token_amount(9), TokenAmount(1.5));
+        assert_eq!(sol.to_usd_amount(9, 100.0), UsdAmount(150.0));
+        assert_eq!(
+            TokenAmount(1.5).to_native_amount(9, Rounding::Down),
+            Some(sol)
+        );
+        assert_eq!(
+            UsdAmount(150.0).to_token_amount(100.0),
+            Some(TokenAmount(1.5))
+        );
+        assert_eq!(UsdAmount(150.0).to_token_amount(0.0), None);
+
+        // same token amount, different decimals
+        assert_eq!(
+            TokenAmount(1.5).to_native_amount(6, Rounding::Down),
+            Some(NativeAmount(1_500_000))
+        );
+        assert_eq!(RawAmt::from(NativeAmount(7)).to_native_amount(), 7);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let amount = TokenAmount(0.00000175);
+        assert_eq!(
+            amount.to_native_amount(6, Rounding::Down),
+            Some(NativeAmount(1))
+        );
+        assert_eq!(
+            amount.to_native_amount(6, Rounding::Up),
+            Some(NativeAmount(2))
+        );
+        assert_eq!(
+            amount.to_native_amount(6, Rounding::Nearest),
+            Some(NativeAmount(2))
+        );
+        assert_eq!(TokenAmount(-1.0).to_native_amount(6, Rounding::Down), None);
+        assert_eq!(
+            TokenAmount(f64::NAN).to_native_amount(6, Rounding::Down),
+            None
+        );
+        assert_eq!(TokenAmount(1e30).to_native_amount(6, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(NativeAmount(1).checked_sub(NativeAmount(2)), None);
+        assert_eq!(NativeAmount(u64::MAX).checked_add(NativeAmount(1)), None);
+        assert_eq!(
+            NativeAmount(1).saturating_sub(NativeAmount(2)),
+            NativeAmount(0)
+        );
+        assert_eq!(format!("{}", UsdAmount(1.234)), "$1.23");
+    }
+
+    #[test]
+    fn test_raw_amt() {
+        use crate::state::NATIVE_RAW_SHIFT;
+        let half = 1u128 << (NATIVE_RAW_SHIFT - 1);
+        let amount = RawAmt::from_raw((5u128 << NATIVE_RAW_SHIFT) + half);
+        assert_eq!(amount.to_native_amount(), 5);
+        assert_eq!(
+            amount.to_native_amount_rounded(Rounding::Down),
+            Some(NativeAmount(5))
+        );
+        assert_eq!(
+            amount.to_native_amount_rounded(Rounding::Up),
+            Some(NativeAmount(6))
+        );
+        assert_eq!(
+            amount.to_native_amount_rounded(Rounding::Nearest),
+            Some(NativeAmount(6))
+        );
+        let whole = RawAmt::from_native_amount(5);
+        assert_eq!(
+            whole.to_native_amount_rounded(Rounding::Up),
+            Some(NativeAmount(5))
+        );
+        assert_eq!(
+            RawAmt::from_raw(u128::MAX).to_native_amount_rounded(Rounding::Down),
+            None
+        );
+        assert_eq!(format!("{}", amount), "5.50000000");
+
+        let one = RawAmt::from_native_amount(1);
+        assert_eq!(one.checked_sub(amount), None);
+        assert_eq!(RawAmt::from_raw(u128::MAX).checked_add(one), None);
+        let rest = (4u128 << NATIVE_RAW_SHIFT) + half;
+        assert_eq!(amount.checked_sub(one), Some(RawAmt::from_raw(rest)));
+        assert_eq!(one.saturating_sub(amount), RawAmt::ZERO);
+    }
+}
@@ -1,4 +1,9 @@
+use crate::amount::{NativeAmount, Rounding, TokenAmount, UsdAmount};
+use crate::env::ApricotEnv;
+use crate::swap::SwapVenue;
 use solana_program::pubkey::Pubkey;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Result as FormatResult};
 
 pub mod apt {
     use solana_program::declare_id;
@@ -97,6 +102,38 @@ pub mod usdt_usdc_saber {
         use solana_program::declare_id;
         declare_id!("GP1U66jGiiscj4HotJP7JTj76jpygdUaTUJT6HPbkoKn");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("YAkoNb6HKmSxQN9L8hiBE5tPJRsniSSMzND1boHmZxe");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("5C1k9yV7y4CjMnKv8eGYDgWND8P89Pdfj79Trk2qmfGo");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("CfWX7o2TswwbxusJ4hCaPobu2jLCb1hfXuXJQjVq3jQF");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("EnTrdMMpdhugeH6Ban6gYZWXughWxKtVGfCwFn78ZmY3");
+    }
+    pub mod fees_a {
+        use solana_program::declare_id;
+        declare_id!("XZuQG7CQrAA6y6tHM9CLrDjDUWwuUU2SBoV7pLaGDQT");
+    }
+    pub mod fees_b {
+        use solana_program::declare_id;
+        declare_id!("63aJYYuZddSnCGyE8FNrCVQWnXhjh6CQSRwcDeSMhdVC");
+    }
+    pub mod quarry {
+        use solana_program::declare_id;
+        declare_id!("Hs1X5YtXwZACueUtS9azZyXFDWVxAMLvm3tttubpK7ph");
+    }
+    pub mod rewarder {
+        use solana_program::declare_id;
+        declare_id!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk");
+    }
 }
 pub mod msol_sol_saber {
     use solana_program::declare_id;
@@ -107,6 +144,38 @@ pub mod msol_sol_saber {
         use solana_program::declare_id;
         declare_id!("73asEXQWZZqUUG58gY8vovh9wNQxUsKT7tKq8eZzPJhT");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("Lee1XZJfJ9Hm2K1qTyeCz1LXNc1YBZaKZszvNY4KCDw");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("2Sj4MZvmLhud4uRmGHJvDxq612nmF4JJsU1R4ZjNNGMS");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("9DgFSWkPDGijNKcLGbr3p5xoJbHsPgXUTr6QvGBJ5vGN");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("2hNHZg7XBhuhHVZ3JDEi4buq2fPQwuWBdQ9xkH7t1GQX");
+    }
+    pub mod fees_a {
+        use solana_program::declare_id;
+        declare_id!("HzZRDMiJSqS5oxzfu17c35DChnkx58LZtas16Pgmuunn");
+    }
+    pub mod fees_b {
+        use solana_program::declare_id;
+        declare_id!("3oebZVvPqba2egfdcbNXa1uS13SfSebxMaNVE82FMk7R");
+    }
+    pub mod quarry {
+        use solana_program::declare_id;
+        declare_id!("7193EeecxsPPv9TMoQATTN8i1eTqEUSNU8aDLuFCQy68");
+    }
+    pub mod rewarder {
+        use solana_program::declare_id;
+        declare_id!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk");
+    }
 }
 pub mod stsol_sol_saber {
     use solana_program::declare_id;
@@ -117,6 +186,38 @@ pub mod stsol_sol_saber {
         use solana_program::declare_id;
         declare_id!("AE3BisWAMqs695qU7Y2L6s52v7N79MMTNQRbvqytXNJU");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("Lid8SLUxQ9RmF7XMqUA8c24RitTwzja8VSKngJxRcUa");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("8eyi347MTDeH5F6eVv2qjPxVnU685FFZLDGcj5QWHZ6y");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("4PgzyzLtds9bKZ2to9PMnKqJzKEUpjvNUaeN23phegax");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("AtymwxoVN9peZo7EXTcDz9jKVc4vRmisJKKrNfe3ewBa");
+    }
+    pub mod fees_a {
+        use solana_program::declare_id;
+        declare_id!("2AbLYRQa7PV6gG6XgMjaey18RtPh85sXFmMmP4HsDdQK");
+    }
+    pub mod fees_b {
+        use solana_program::declare_id;
+        declare_id!("Cv3YNq8iY1ttMS3iDgwBxd7QxnMC2pwcXUomtR7CTD8W");
+    }
+    pub mod quarry {
+        use solana_program::declare_id;
+        declare_id!("2isVhcqVVpsAeZ94d1DGVErCQ7uZQNSJ85xikN8awHeC");
+    }
+    pub mod rewarder {
+        use solana_program::declare_id;
+        declare_id!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk");
+    }
 }
 pub mod ust_usdc_saber {
     use solana_program::declare_id;
@@ -127,6 +228,38 @@ pub mod ust_usdc_saber {
         use solana_program::declare_id;
         declare_id!("HgkCE5MPdDRKaYAY51smAJTToYsjUn2oLy5K9ZQYL4WP");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("KwnjUuZhTMTSGAaavkLEmSyfobY16JNH4poL9oeeEvE");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("9osV5a7FXEjuMujxZJGBRXVAyQ5fJfBFNkyAf6fSz9kw");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("J63v6qEZmQpDqCD8bd4PXu2Pq5ZbyXrFcSa3Xt1HdAPQ");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("BnKQtTdLw9qPCDgZkWX3sURkBAoKCUYL1yahh6Mw7mRK");
+    }
+    pub mod fees_a {
+        use solana_program::declare_id;
+        declare_id!("BYgyVxdrGa3XNj1cx1XHAVyRG8qYhBnv1DS59Bsvmg5h");
+    }
+    pub mod fees_b {
+        use solana_program::declare_id;
+        declare_id!("G9nt2GazsDj3Ey3KdA49Sfaq9K95Dc72Ejps4NKTP2SR");
+    }
+    pub mod quarry {
+        use solana_program::declare_id;
+        declare_id!("BYEUtsLjYAVHRiRR3Avjqnd2RQLRL8n933N52p9kSX2y");
+    }
+    pub mod rewarder {
+        use solana_program::declare_id;
+        declare_id!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk");
+    }
 }
 
 pub mod usdc_usdt_orca {
@@ -142,6 +275,46 @@ pub mod usdc_usdt_orca {
         use solana_program::declare_id;
         declare_id!("FSQWYCVXiGXRfKd1NmchusEa9wADez9eQGt5RY5eDjiy");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("F13xvvx45jVGd84ynK3c8T89UejQVxjCLtmHfPmAXAHP");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("3cGHDS8uWhdxQj14vTmFtYHX3NMouPpE4o9MjQ43Bbf4");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("6uUn2okWk5v4x9Gc4n2LLGHtWoa9tmizHq1363dW7t9W");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("AiwmnLy7xPT28dqZpkRm6i1ZGwELUCzCsuN92v4JkSeU");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("B4RNxMJGRzKFQyTq2Uwkmpyjtew13n7KtdqZy6qgENTu");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("9hPRfmQmZYiL4ZtuvGBk5SjMzmFCQ2h9a4GKoM82BR84");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("GjpXgKwn4VW4J2pZdS3dovM58hiXWLJtopTfqG83zY2f");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("5psKJrxWnPmoAbCxk3An2CGh7wHAX2cWddf5vZuYbbVw");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("AYbtHmuJxXpo91m988UdyTtzC6J72WvMAW7XkXqFhAbz");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("5YGvg6mfuvJtHdVWDXTs4sYy6GwQAUduK8qurDcL111S");
+    }
 }
 pub mod sol_usdc_orca {
     use solana_program::declare_id;
@@ -156,6 +329,46 @@ pub mod sol_usdc_orca {
         use solana_program::declare_id;
         declare_id!("Hr5yQGW35HBP8fJLKfranRbbKzfSPHrhKFf1ZP68LmVp");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("EGZ7tiLeH62TPV1gL8WwbXGzEPa9zmcpVnnkPKKnrE2U");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("JU8kmKzDHF9sXWsnoznaFDFezLsE5uomX2JkRMbmsQP");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("ANP74VNsHwSrq9uUSjiSNyNWvf6ZPrKTmE4gHoNd13Lg");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("75HgnSvXbWKZBpZHveX68ZzAhDqMzNDS29X6BGLtxMo1");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("8JnSiuvQq3BVuCU3n4DrSTw9chBSPvEMswrhtifVkr1o");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("7ipefo5V3QEJWeuT2PohFSEUaranZxMSeWQo2rcNigr3");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("FFdjrSvNALfdgxANNpt3x85WpeVMdQSH5SEP2poM8fcK");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("85HrPbJtrN82aeB74WTwoFxcNgmf5aDNP2ENngbDpd5G");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("kjjFC8RAF7GuBQ9iYgyTcPmvsRafJ2Ec2AmoS6DjakJ");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("MDcWkwPqr5HrA91g4GGax7bVP1NDDetnR12nGhoAdYj");
+    }
 }
 pub mod msol_sol_orca {
     use solana_program::declare_id;
@@ -182,6 +395,66 @@ pub mod msol_sol_orca {
         use solana_program::declare_id;
         declare_id!("5QpEXnCWMyUHNwE1jFUZq4gzNzmke9XJ2qCZjeBvMqz3");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("9EQMEzJdE2LDAY1hw1RytpufdwAXzatYfQ3M2UuT9b88");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("6cwehd4xhKkJ2s7iGh4CaDb7KhMgqczSBnyNJieUYbHn");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("6xmki5RtGNHrfhTiHFfp9k3RQ9t8qgL1cYP2YCG2h179");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("Ew2coQtVGLeca31vqB2ssHntjzZgUy1ad9VuuAX8yw7p");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("6j2tt2UVYMQwqG3hRtyydW3odzBFwy3pN33tyB3xCKQ6");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("DuTZUmTRydVc3EN78brdYFUfskn6s93zH4WhY3Fo53AJ");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("3RTGL7gPF4V1ns1AeGFApT7cBEGVDfmJ77DqQi9AC6uG");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("JADWjBW1Xs8WhW8kj3GTCRQn3LR4gwvbFTEMwv9ZNxQh");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("7dpUACKvEiuq5kyoGtgiA131hYwdxfFhEeD5TMT4mnzG");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("CtXKDXJ4wzgto48QQFANestEgtov5dJRrs9qpRw7BV1h");
+    }
+    pub mod global_lp_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("AEZpFdJ5hA7MwVS7AReBbS9pMhoYRhLXgDyc1GWbSoXc");
+    }
+    pub mod farm_token_mint_2nd {
+        use solana_program::declare_id;
+        declare_id!("576ABEdvLG1iFU3bLC8AMJ3mo5LhfgPPhMtTeVAGG6u7");
+    }
+    pub mod global_farm_state_2nd {
+        use solana_program::declare_id;
+        declare_id!("2SciNw7cEsKJc1PMRDzWCcEzvuScmEaUgmrJXCi9UFxY");
+    }
+    pub mod global_reward_token_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("DCHpFt1bCk9mTudj6VsKbADvUPT3tAJvJ2rcBZQry8Wz");
+    }
+    pub mod reward_token_authority_2nd {
+        use solana_program::declare_id;
+        declare_id!("5uk8F4MaFSu1pF9Q7k8xcyWgqyo9q2dqr3Kb4Esvd1n3");
+    }
 }
 pub mod orca_usdc_orca {
     use solana_program::declare_id;
@@ -196,6 +469,46 @@ pub mod orca_usdc_orca {
         use solana_program::declare_id;
         declare_id!("G8cPgn6tiQQAQcTQupEi8fTBfo1RpqTii1hW65L4poTY");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("2p7nYbtPBgtmY69NsE8DAW6szpRJn7tQvDnqvoEWQvjY");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("3fr1AhdiAmWLeNrS24CMoAu9pPgbzVhwLtJ6QUPmw2ob");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("9vYWHBPz817wJdQpE8u3h8UoY3sZ16ZXdCcvLB7jY4Dj");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("6UczejMUv1tzdvUzKpULKHxrK9sqLm8edR1v9jinVWm9");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("7CXZED4jfRp3qdHB9Py3up6v1C4UhHofFvfT6RXbJLRN");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("45BAAQCZYd2kP3Z3WvRwdtfUhvuW4FvpqVK4m8qrR5x1");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("Gc7W5U66iuHQcC1cQyeX9hxkPF2QUVJPTf1NWbW8fNrt");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("9S1BsxbDNQXQccjFamVEGgxiYQHTeudvhEYwFr4oWeaf");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("DEiqe2Ta9TRMRtWdBqiFV13dhVrqCeG8MMmVwywvXvJo");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("66xaEjFoYfRcspc18oDj61mXDyznr9zam6tFNeqvs2jK");
+    }
 }
 pub mod orca_sol_orca {
     use solana_program::declare_id;
@@ -210,6 +523,46 @@ pub mod orca_sol_orca {
         use solana_program::declare_id;
         declare_id!("2G7ZWG9z6WtKJ5k5B32RTmLFB7hLVEnC5RmYD7gvCpG3");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("2ZnVuidTHpi5WWKUwFXauYGhvdT9jRKYv5MDahtbwtYr");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("2PH1quJj9MHQXATCmNZ6qQ2gZqM8R236DpKaz99ggVpm");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("AioST8HKQJRqjE1mknk4Rydc8wVADhdQwRJmAAYX1T6Z");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("73zdy95DynZP4exdpuXTDsexcrWbDJX9TFi2E6CDzXh4");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("4Zc4kQZhRQeGztihvcGSWezJE1k44kKEgPCAkdeBfras");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("7N7zxoDMMV1sCDiVEzinTyQxS2GoN388QprMCQX38BeT");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("B5waaKnsmtqFawPspUwcuy1cRjAC7u2LrHSwxPSxK4sZ");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("F6pi7SyXWx56fP96mYQ4Yfh4yZ7oGNtDjwSYHT5Mz7Ld");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("CSbYA7Cd65Vis2oqX797zmnWmpgENmqrPdmPbTbRPykd");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("98RAHBKRTTC87nNwug1GEAnLVgouk9nRaa3u14jrp6Zz");
+    }
 }
 pub mod eth_usdc_orca {
     use solana_program::declare_id;
@@ -224,6 +577,46 @@ pub mod eth_usdc_orca {
         use solana_program::declare_id;
         declare_id!("CtVJtQHSAcSQ3b4FD3A3Zk8vb2PaC4wn1oTnHtUMS8rf");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("FgZut2qVQEyPBibaTJbbX2PxaMZvT1vjDebiVaDp5BWP");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("4dfCZR32xXhoTgMRhnViNaTFwiKP9A34TDjHCR3xM5rg");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("H9h5yTBfCHcb4eRP87fXczzXgNaMzKihr7bf1sjw7iuZ");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("JA98RXv2VdxQD8pRQq4dzJ1Bp4nH8nokCGmxvPWKJ3hx");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("DLWewB12jzGn4wXJmFCddWDeof1Ma4cZYNRv9CP5hTvX");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("6zoYTvgLd4UAhKSPwirEU9VNNNkpezwq8AM4jXW1Qop9");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("HDP2AYFmvLz6sWpoSuNS62JjvW4HjMKp7doXucqpWN56");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("FpezTR76RRjgpBb9HhR6ap8BgQfkHyNMQSqJDcoXpjAb");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("9MWJmWVAGQ9C9SxwWKidStAA8HjDHpnZ7KfKgVJdrNtj");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("DFTLJrgsn7cLNX9hbqiUwM8C1y6f7AfyvEmbsFSkjQNR");
+    }
 }
 pub mod sol_usdt_orca {
     use solana_program::declare_id;
@@ -238,6 +631,46 @@ pub mod sol_usdt_orca {
         use solana_program::declare_id;
         declare_id!("9AfsnfPwRrJLjcCAasUcaYeVunpmxgev6yCVa6HiLkp7");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("Dqk7mHQBx2ZWExmyrR2S8X6UG75CrbbpK2FSBZsNYsw6");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("2sxKY7hxVFrY5oNE2DgaPAJFamMzsmFLM2DgVcjK5yTy");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("DTb8NKsfhEJGY1TrA7RXN6MBiTrjnkdMAfjPEjtmTT3M");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("E8erPjPEorykpPjFV9yUYMYigEWKQUxuGfL2rJKLJ3KU");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("BBKgw75FivTYXj85D2AWyVdaTdTWuSuHVXRm1Xu7fipb");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("EXxH5tKDHLy68nWXS8w1BRUsiDEHMbKACLUmFWv8Q9tu");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("71vZ7Jvu8fTyFzpX399dmoSovoz24rVbipLrRn2wBNzW");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("4RRRJkscV2DmwJUxTQgRdYock75GfwYJn7LTxy9rGTmY");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("H3ozvCeEwnsqnM2naCnXVxLLwH2XPC5kU8BH97XDpDwS");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("EavNUagNtD7DEdV4atcm3dEBXafARKCNJyNkyfz426m6");
+    }
 }
 pub mod eth_sol_orca {
     use solana_program::declare_id;
@@ -252,6 +685,46 @@ pub mod eth_sol_orca {
         use solana_program::declare_id;
         declare_id!("2NYnAKhCwCMoe5unHuaEQEYL1ugLypK8Hrx4Qp5ugSUf");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("EuK3xDa4rWuHeMQCBsHf1ETZNiEQb5C476oE9u9kp8Ji");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("DffrDbzPiswDJaiicBBo9CjqztKgFLrqXGwNJH4XQefZ");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("7F2cLdio3i6CCJaypj9VfNDPW2DwT3vkDmZJDEfmxu6A");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("5pUTGvN2AA2BEzBDU4CNDh3LHER15WS6J8oJf5XeZFD8");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("unxKgWEc71ZiHwMqZs3VLqjcjmZhfTZEg94ZLGvjdMP");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("6ckhPnn6tCr88aq9SxhWaAA5G7izuXNKhVk1Xa62zhFD");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("CGFTRh4jKLPbS9r4hZtbDfaRuC7qcA8rZpbLnVTzJBer");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("3ARgavt1NhqLmJWj3wAJy6XBarG6pJbEKRv1wzzRbbaN");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("FYTTVMqWPzbnhTsukgiWmPiNJam4yLTxHM9mpzdan2zo");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("HXY2Vvj2XyqiPNXV3PhM9YYKgfjqzXUX4tUFRnvqihdY");
+    }
 }
 pub mod apt_usdc_orca {
     use solana_program::declare_id;
@@ -266,6 +739,46 @@ pub mod apt_usdc_orca {
         use solana_program::declare_id;
         declare_id!("EgFva9mEFCV31AkhoZb6rN6zvbNGE1xdaRYAkKTtdNjN");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("Fg3UabVqnfycMtkiTVoaia9eNafehtT9Y4TicH2iBtvK");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("JDEYn1JsacdxoB4v4mbctFSVrSUPttacX3gxWphFHJKZ");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("636crNdZTf46gFUKuedaBCZDBMLahf7KGud2LyTMskU5");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("DGEYFkEHyiuHWtHeCGiQGn1JbkGHqYrNwaP44miRbgxu");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("41H5mWwsZKewJeV4wWiNjQ3U4VYBnwqCpzvAWt86baHd");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("Ha7NSMkfjQt2pWF8JY5p89T38NpKdm5da4FR3sYednin");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("Dx7DYSuaBufhXyQG7155ePkLmHyn6w7WeKKtQB9zscZV");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("3YZ5GYL625vWibn7d8hMdrMBawy9HGUyeTe4AoXoME1Q");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("HyCJbQkccvMwC5FHAYBMjQCKXEjDo9fbhBa5pj8sc2v5");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("53y344S5Cv32ViwajrHxnsgcmam7Mw2nydcRgJEkqdGd");
+    }
 }
 pub mod btc_msol_orca {
     use solana_program::declare_id;
@@ -292,8 +805,68 @@ pub mod btc_msol_orca {
         use solana_program::declare_id;
         declare_id!("ESCvrGSqm2ykHBetb7bXAY8j1zWFLCSMeoFwYAQeJ3u3");
     }
-}
-pub mod msol_usdc_orca {
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("8DRw5wQE1pyg6RB1UwypGNFgb2Pzp2hpyDDNwo76Lcc8");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("3X1aLdyvcQNc8TvBMPiucMsRCnGMBnGsjJHpZEyCf3pn");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("6D3sxC6yEe84FUnF5Kpbgx6gN57N9poJCKAtrCeCWdJo");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("EPoVJLhi9QtVPVo8n31M5k5Knvb48j8zbYyRrUbrHwC5");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("AqiLHbUAy4UWWKGVVgbHsaUVCMg1zemNkgsYBPSirT92");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("75gpvckCXk49zTUwG8QrzUSP4NpWh3JXdyELBrnAhimL");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("DzpLz78wuwyFsQToin8iDv6YK6aBEymRqQq82swiFh7r");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("GBrpFtiTabs14mc4Hi1RX9YiQY7res6JxrVfMTADfcQV");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("CNe5S831UP4YkumU7UsusTkf7uxJnAVdmPe6jhF51k4y");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("8sVCTztvytajkdczYEZVkSmuoRLjnMezwpT46L5w4RWR");
+    }
+    pub mod global_lp_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("DuyHVLzsqg6SZeFNbpUWfJf67kvAXPWUdUGJYWJK5vTu");
+    }
+    pub mod farm_token_mint_2nd {
+        use solana_program::declare_id;
+        declare_id!("6uA1ADUJbvwYJZpzUn9z9LuyKoRVngBKcQTKdXsSivA8");
+    }
+    pub mod global_farm_state_2nd {
+        use solana_program::declare_id;
+        declare_id!("Cn7QNyosNQ8DyKEeMDPmtg66R7vKMXigcQ561kTkFD8E");
+    }
+    pub mod global_reward_token_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("Ea3FYh9RMJxwsyu3xS7BesLMtpX32DURohiEigG2iJCx");
+    }
+    pub mod reward_token_authority_2nd {
+        use solana_program::declare_id;
+        declare_id!("9Lg5wBjcYDgY8S2ZAEqjtXAQ4UdHuw65aP1WmmWss4QX");
+    }
+}
+pub mod msol_usdc_orca {
     use solana_program::declare_id;
     declare_id!("8PSfyiTVwPb6Rr2iZ8F3kNpbg65BCfJM9v8LfB916r44");
     pub const POOL_ID: u8 = 25;
@@ -318,6 +891,66 @@ pub mod msol_usdc_orca {
         use solana_program::declare_id;
         declare_id!("FUBad9ZBZmegSugRt7qY4uM1yRxzhBRpywwkn4mRQAeQ");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("Hme4Jnqhdz2jAPUMnS7jGE5zv6Y1ynqrUEhmUAWkXmzn");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("9Z7E42k46kxnBjAh8YGXDw3rRGwwxQUBYM7Ccrmwg6ZP");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("GBa7G5f1FqAXEgByuHXsqsEdpyMjRgT9SNxZwmmnEJAY");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("7hFgNawzzmpDM8TTVCKm8jykBrym8C3TQdb8TDAfAVkD");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("3W3Skj2vQsNEMhGRQprFXQy3Q8ZbM6ojdgiDCokVPWno");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("8F6NCo1PiakW7m3eeEZvdxsjXF5bkLD3QZsTxaNg9jvv");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("5r3vDsNTGXXb9cGQfqyNuYD2bjhRPymGJBfDmKosR9Ev");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("EvtMzreDMq1U8ytV5fEmfoWNfPhrjZ87za835GuRvZCc");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("A1enLcj9XmuVeYCQScEruwnfAz7ksQhbuGFUgvgeS1a6");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("9czgZkSxLFtxmvWSb1PEHmUyBuNpAUxj9XAcHKikYnzt");
+    }
+    pub mod global_lp_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("CdbgqE5B9oADrSAWc51Mgw6c3B6nvYJ4c431rftpoVqZ");
+    }
+    pub mod farm_token_mint_2nd {
+        use solana_program::declare_id;
+        declare_id!("9y3QYM5mcaB8tU7oXRzAQnzHVa75P8riDuPievLp64cY");
+    }
+    pub mod global_farm_state_2nd {
+        use solana_program::declare_id;
+        declare_id!("5fhDMuGKRDPWVWXf7BBEwifRFrp6XwXctDQoG7UHGVt6");
+    }
+    pub mod global_reward_token_vault_2nd {
+        use solana_program::declare_id;
+        declare_id!("XbkV9HZpLdv3CjMUfoq4t8nkxR6UguHb4oP8aAKBGV2");
+    }
+    pub mod reward_token_authority_2nd {
+        use solana_program::declare_id;
+        declare_id!("FvXa954NiCqE2jAthxV5oVcuuPAJCggwYtAihYDRhVUw");
+    }
 }
 pub mod stsol_ust_orca {
     use solana_program::declare_id;
@@ -332,6 +965,46 @@ pub mod stsol_ust_orca {
         use solana_program::declare_id;
         declare_id!("6P4frmXufUBsT2s39zbQ5k5UDrzSpFcNKmZAgWZLHgMv");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("9F3J6RY7PTkDb3SUUpg725uXyCceBGCpZrtmYGJwgMwF");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("wJydc21tAMxYDif8uvy5rWNGWDFNZnPPmqCvegyZRod");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("GFso9SAGakm8ZFa3rmuonuerbcQ8ZbACNZN7idkKR5nw");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("EZ7pJskN2a4pDknrdkLzGDHpzjbfgdBj3Tt594K9HZbL");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("5rCbmppxMBHwBjCkLUP6fireQ12cL8LRa26QRUimoxN6");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("EQBy5YqWkLxr1xx1CZh2dDdX57XR9Ata4jNJebtuR61h");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("3Q44iV4URXdbS4Tk1PGs5VdWQoCxHB7zdcdMnemo8jfH");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("GxhewC22S6wsXT156yC9SARvDnijoc3YEYyLVcQFDUCx");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("FFjuVr4FYdRGkPwaQREVQWHsBFGwQ1LH6VkmVSJL5Vva");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("8za7LwVuYbP2PivS3KbeohpUkSmRqLdTDxhrVzsHyFZf");
+    }
 }
 pub mod orca_wheth_orca {
     use solana_program::declare_id;
@@ -346,6 +1019,46 @@ pub mod orca_wheth_orca {
         use solana_program::declare_id;
         declare_id!("9jJVuB2UhzcGED6h476kieHwTVPmhY7EJsQyud38Q4r1");
     }
+    pub mod swap {
+        use solana_program::declare_id;
+        declare_id!("D67u6UEuFp9Q4Fu2gnu5thxBoVj2eRgDDDirdWsMVsgm");
+    }
+    pub mod swap_authority {
+        use solana_program::declare_id;
+        declare_id!("4kwchkCnCaZPkBdnk85j9bJ9F1P883rwjbvsXGV7H5rM");
+    }
+    pub mod vault_a {
+        use solana_program::declare_id;
+        declare_id!("EMxDw1NjdddQjPk9gCUN4iDMy8HJ5siKYMVjGbjuUCrh");
+    }
+    pub mod vault_b {
+        use solana_program::declare_id;
+        declare_id!("DQyhcuEvE1K9DSd51agcNLg4CDrgthnKMVhU7q6R55LV");
+    }
+    pub mod fees {
+        use solana_program::declare_id;
+        declare_id!("GdHXLai5M8FKedXUsECSzrV72nb1RTCCooFyTWT8WYmT");
+    }
+    pub mod global_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("CYSwKVGEaPazRXUcCo2Ve9wyovK44E9ftLNSsvVqRuf1");
+    }
+    pub mod farm_token_mint {
+        use solana_program::declare_id;
+        declare_id!("A7EAHvFEWzW8qSTFNLrEHN894GAZWZesNiebttEwtUGJ");
+    }
+    pub mod global_farm_state {
+        use solana_program::declare_id;
+        declare_id!("AgWH92f8cPAuURyqehF6A9wFgupEsnDX3VP5eHyytabA");
+    }
+    pub mod global_reward_token_vault {
+        use solana_program::declare_id;
+        declare_id!("DoaerVHonB3FYpzKtqADug9UtrPLzgY1gbzZm6cRuymi");
+    }
+    pub mod reward_token_authority {
+        use solana_program::declare_id;
+        declare_id!("B1pahxFpN3GX4TKsw7fEDLw5MuuzmHNUbVcPZ6bp7KBM");
+    }
 }
 
 pub mod sol_usdc_raydium {
@@ -365,6 +1078,90 @@ pub mod sol_usdc_raydium {
         use solana_program::declare_id;
         declare_id!("4vbQX6ycybRe6KMeb6VT9pEpG1284QvTrZv2ph3Yh2Nd");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("HmiHHzq4Fym9e1D4qzLS6LDDM3tNsCTBPDWHTLZ763jY");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("G7xeGGLevkRwB5f44QNgQtrPKBdMfkT6ZZwpS9xcC97n");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("Awpt6N7ZYPBa4vG4BQNFhFxDj4sxExAA9rpBAoBw2uok");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("CKxTHwM9fPMRRvZmFnFoqKNd9pQR21c5Aq9bh5h9oghX");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("6A5NHCj1yF6urc9wZNe6Bcjj4LVszQNj5DwAWG97yzMu");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("CTz5UMLQm2SRWHzQnU62Pi4yJqbNGjgRBHqqp6oDHfF7");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("8CvwxZ9Db6XbLD46NZwwmVDZZRDy7eydFcAGkXKh9axa");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("5jWUncPNBMZJ3sTHKmMLszypVkoRK6bfEQMQUHweeQnh");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("EaXdHx7x3mdGA38j5RSmKYSXMzAFzzUXCLNBEDXDn1d5");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("GUzaohfNuFbBqQTnPgPSNciv3aUvriXYjQduRE3ZkqFw");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("DgbCWnbXg43nmeiAveMCkUUPEpAr3rZo3iop3TyP6S63");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("J6ECnRDZEXcxuruvErXDWsPZn9czowKynUr9eDSQ4QeN");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("38YS2N7VUb856QDsXHS1h8zv5556YgEy9zKbbL2mefjf");
+    }
+    pub mod reward_second_vault {
+        use solana_program::declare_id;
+        declare_id!("ANDJUfDryy3jY6DngwGRXVyxCJBT5JfojLDXwZYSpnEL");
+    }
 }
 pub mod ray_usdc_raydium {
     use solana_program::declare_id;
@@ -383,6 +1180,86 @@ pub mod ray_usdc_raydium {
         use solana_program::declare_id;
         declare_id!("FicrKCCcXaZxgLAS6hymbcaHxNNjtCUmJFofsJ1fjdxj");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("6UmmUiYoBjSrhakAobJw8BvkmJtDVxaeBtbt7rxWo1mg");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("CSCS9J8eVQ4vnWfWCx59Dz8oLGtcdQ5R53ea4V9o2eUp");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("3cji8XW5uhtsA757vELVFAeJpskyHwbnTSceMFY5GjVT");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("FdmKUE4UMiJYFK5ogCngHzShuVKrFXBamPWcewDr31th");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("Eqrhxd7bDUCH3MepKmdVkgwazXRzY6iHhEoBpY7yAohk");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("ERiPLHrxvjsoMuaWDWSTLdCMzRkQSo8SkLBLYEmSokyr");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("D1V5GMf3N26owUFcbz2qR5N4G81qPKQvS2Vc4SM73XGB");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("DZjbn4XC8qoHKikZqzmhemykVzmossoayV9ffbsUqxVj");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("7ssdQJxVAEBSigoJovgHcchwcEQFPPtYbyzLHDHEewKM");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("EBGFfeQ5dVwW4HxtShVbh8aCh2fKJ1r2qXBoa6teUve6");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("HYfri5vWyYiDziQeprFErUTbrWdUnkfAFnAAGApZjdGv");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("EkKZwBeKWPvhraYERfUNr2fdh1eazrbTrQXYkRZs24XB");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("CXMRrGEseppLPmzYJsx5vYwTkaDEag4A9LJvgrAeNpF");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("27BrDDYtv9NDQCALCNnDqe3BqjYkgiaQwKBbyqCA8p8B");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("CHYrUBX2RKX8iBg7gYTkccoGNBzP44LdaazMHCLcdEgS");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("5KQFnDd33J5NaMC9hQ64P5XzaaSz8Pt7NBCkZFYn1po");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("BNnXLFGva3K8ACruAc1gaP49NCbLkyE6xWhGV4G2HLrs");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("DpRueBHHhrQNvrjZX7CwGitJDJ8eZc3AHcyFMG4LqCQR");
+    }
 }
 pub mod sol_usdt_raydium {
     use solana_program::declare_id;
@@ -401,12 +1278,148 @@ pub mod sol_usdt_raydium {
         use solana_program::declare_id;
         declare_id!("C4GrteYviDR4xfMGwdGD5gzVgy3iVmTHGhV2XoiJZfLw");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("7XawhbbxtsRcQA8KTkHT9f9nc6d69UwqCDh6U5EEbEmX");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("3oWQRLewGsUMA2pebcpGPPGrzyRNfbs7fQEMUxPAGgff");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("9x4knb3nuNAzxsV7YFuGLgnYqKArGemY54r2vFExM1dp");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("876Z9waBygfzUrwwKFfnRcc7cfY4EQf6Kz1w7GRgbVYW");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("CB86HtaqpXbNWbq67L18y5x2RhqoJ6smb7xHUcyWdQAQ");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("52AfgxYPTGruUA9XyE8eF46hdR6gMQiA6ShVoMMsC6jQ");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("2JKZRQc92TaH3fgTcUZyxfD7k7V7BMqhF24eussPtkwh");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("2AdaV97p6SfkuMQJdu8DHhBhmJe7oWdvbm52MJfYQmfA");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("4zVFCGJVQhSvsJ625qTH4WKgvfPQpNpAVUfjpgCxbKh8");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("9aoqhYjXBqWsTVCEjwtxrotx6sVPGVLmbpVSpSRzTv54");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("n8meSpYX5n3oRoToN21PFQ5SSYBDf675eub3WMoJJoA");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("9zw6ztEpHfcKccahzTKgPkQNYhJMPwL4iJJc8BAztNYY");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("F4LnU7SarP7nLmGPnDHxnCqZ8gRwiFRgbo5seifyicfo");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("BKgZNz8tqJFoZ9gEHKR6k33wBMeXKAaSWpW5zMhSRhr3");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("5r878BSWPtoXgnqaeFJi7BCycKZ5CodBB2vS9SeiV8q");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("DimG1WK9N7NdbhddweGTDDBRaBdCmcbPtoWZJ4Fi4rn4");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("jfhZy3B6sqeu95z71GukkxpkDtfHXJiFAMULM6STWxb");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("Bgj3meVYds8ficJc9xntbjmMBPVUuyn6CvDUm1AD39yq");
+    }
+    pub mod reward_second_vault {
+        use solana_program::declare_id;
+        declare_id!("DJifNDjNt7iHbkNHs9V6Wm5pdiuddtF9w3o4WEiraKrP");
+    }
 }
 pub mod msol_sol_raydium {
     use solana_program::declare_id;
     declare_id!("5ijRoAHVgd5T5CNtK5KDRUBZ7Bffb69nktMj5n6ks6m4");
     pub const POOL_ID: u8 = 22;
     pub const NAME: &str = "mSOL_SOL_RAYDIUM";
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("EGyhb2uLAsRUbRx9dNFBjMVYnFaASWMvD6RE1aEf2LxL");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("6c1u1cNEELKPmuH352WPNNEPdfTyVPHsei39DUPemC42");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("CLuMpSesLPqdxewQTxfiLdifQfDfRsxkFhPgiChmdGfk");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("85SxT7AdDQvJg6pZLoDf7vPiuXLj5UYZLVVNWD1NjnFK");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("BtGUR6y7uwJ6UGXNMcY3gCLm7dM3WaBdmgtKVgGnE1TJ");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("7vvoHxA6di9EvzJKL6bmojbZnH3YaRXu2LitufrQhM21");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("ACn8TZ27fQ85kgdPKUfkETB4dS5JPFoq53z7uCgtHDai");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("5cLrMai1DsLRYc1Nio9qMTicsWtvzjzZfJPXyAoF4t1Z");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("2qmHPJn3URkrboLiJkQ5tBB4bmYWdb6MyhQzZ6ms7wf9");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("A6eEM36Vpyti2PoHK8h8Dqk5zu7YTaSRTQb7XXL8tcrV");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("EHMK3DdPiPBd9aBjeRU4aZjD7z568rmwHCSAAxRooPq6");
+    }
 }
 pub mod ray_usdt_raydium {
     use solana_program::declare_id;
@@ -425,6 +1438,86 @@ pub mod ray_usdt_raydium {
         use solana_program::declare_id;
         declare_id!("HqoBjwSWTWvSUvWAAG1ScWJDXb9ZgUJhhvcFUxKZf7Pz");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("DVa7Qmb5ct9RCpaU7UTpSaf3GVMYz17vNVU67XpdCRut");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("8MSPLj4c2hi1fZGDARvxLXQp1ooDQ8iGnWXbGdwvZxUQ");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("3K2uLkKwVVPvZuMhcQAPLF8hw95somMeNwJS7vgWYrsJ");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("3wqhzSB9avepM9xMteiZnbJw75zmTBDVmPFLTQAGcSMN");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("5GtSbKJEPaoumrDzNj4kGkgZtfDyUceKaHrPziazALC1");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("8VuvrSWfQP8vdbuMAP9AkfgLxU9hbRR6BmTJ8Gfas9aK");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("FBzqDD1cBgkZ1h6tiZNFpkh4sZyg6AG8K5P9DSuJoS5F");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("GpHbiJJ9VHiuHVXeoet121Utrbm1CSNNzYrBKB8Xz2oz");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("kh2FSFa3JQn858KwM4NFjJVokQgtEpnqUyrz62QTMN6");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("JBnZwmn4V8wVHLWzHGeiKZ41icPf6d64cdfHBBaba1w3");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("6tooYdTzzBm6dPqwDz29addE2YHzvDVDcAt7CbgXkxCN");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("9VWPNiJu7ui3xucUHtKmGCHENWhohVmZxujP4uCB8Fzm");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("EY9SKG9EyNFuy1JfQrmaUdHUiTqaVfX3zG9gGfWQywTV");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("GZ2DN58shX6igfaCquq8BLWE1ytkT5uKZfnAGSiu8mR4");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("AvbVWpBi2e4C9HPmZgShGdPoNydG4Yw8GJvG9HUcLgce");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("8JYVFy3pYsPSpPRsqf43KSJFnJzn83nnRLQgG88XKB8q");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("4u4AnMBHXehdpP5tbD6qzB5Q4iZmvKKR5aUr2gavG7aw");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("HCHNuGzkqSnw9TbwpPv1gTnoqnqYepcojHw9DAToBrUj");
+    }
 }
 pub mod ray_eth_raydium {
     use solana_program::declare_id;
@@ -443,6 +1536,86 @@ pub mod ray_eth_raydium {
         use solana_program::declare_id;
         declare_id!("BTPJFX8vWYExEAD7C9J9hpsUU8bUfcxDfc8da2pskzX1");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("8iQFhWyceGREsWnLM8NkG9GC8DvZunGZyMzuyUScgkMK");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("7iztHknuo7FAXVrrpAjsHBEEjRTaNH4b3hecVApQnSwN");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("JChSqhn6yyEWqD95t8UR5DaZZtEZ1RGGjdwgMc8S6UUt");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("G3Szi8fUqxfZjZoNx17kQbxeMTyXt2ieRvju4f3eJt9j");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("7MgaPPNa7ySdu5XV7ik29Xoav4qcDk4wznXZ2Muq9MnT");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("C9aijsE3tLbVyYaXXHi45qneDL5jfyN8befuJh8zzpou");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("3CDnyBsNnexdvfvo6ASde5Q4e72jzMQFHRRkSQr49vEG");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("6jx6aoNFbmorwyncVP5V5ESKfuFc9oUYebob1iF6tgN4");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("EVVtYo4AeCbmn2dYS1UnhtfjpzCXCcN26G1HmuHwMo7w");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("6ZT6KwvjLnJLpFdVfiRD9ifVUo4gv4MUie7VvPTuk69v");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("HXbRDLcX2FyqWJY95apnsTgBoRHyp7SWYXcMYod6EBrQ");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("EgcugBBSwM2FxqLQx5S6zAiU9x9qRS8qMVRMDFFU4Zty");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("Hdvh4ZGL9MkiQApNqfZtdmd4jM6Sz8e9akCUuxxkYhb8");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("7vWmTv9Mh8XbAxcduEqed2dLtro4N7hFroqch6mMxYKM");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("B6fbnZZ7sbKHR18ffEDD5Nncgp54iKN1GbCgjTRdqhS1");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("6amoZ7YBbsz3uUUbkeEH4vDTNwjvgjxTiu6nGi9z1JGe");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("BjAfXpHTHz2kipraNddS6WwQvGGtbvyobn7MxLEEYfrH");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("7YfTgYQFGEJ4kb8jCF8cBrrUwEFskLin3EbvE1crqiQh");
+    }
 }
 pub mod ray_sol_raydium {
     use solana_program::declare_id;
@@ -461,6 +1634,86 @@ pub mod ray_sol_raydium {
         use solana_program::declare_id;
         declare_id!("5wGcTvJVumeXy7YBjcf1RqPwQ8GciFVvUJeCvgGmwLxp");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("AVs9TA4nWDzfPJE9gGVNJMVhcQy3V9PGazuz33BfG2RA");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("6Su6Ea97dBxecd5W92KcVvv6SzCurE2BXGgFe9LNGMpE");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("5hATcCfvhVwAjNExvrg8rRkXmYyksHhVajWLa46iRsmE");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("Em6rHi68trYgBFyJ5261A2nhwuQWfLcirgzZZYoRcrkX");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("3mEFzHsJyu2Cpjrz6zPmTzP7uoLFj9SbbecGVzzkL1mJ");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("FSHqX232PHE4ev9Dpdzrg9h2Tn1byChnX4tuoPUyjjdV");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("87CCkBfthmyqwPuCDwFmyqKWJfjYqPFhm5btkNyoALYZ");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("C6tp2RVZnxBPFbnAsfTjis8BN9tycESAT4SgDQgbbrsA");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("6U6U59zmFWrPSzm9sLX7kVkaK78Kz7XJYkrhP1DjF3uF");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("4YEx21yeUAZxUL9Fs7YU9Gm3u45GWoPFs8vcJiHga2eQ");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("7SdieGqwPJo5rMmSQM9JmntSEMoimM4dQn7NkGbNFcrd");
+    }
+    pub mod serum_event_queue {
+        use solana_program::declare_id;
+        declare_id!("4HGvdannxvmAhszVVig9auH6HsqVH17qoavDiNcnm9nj");
+    }
+    pub mod serum_bids {
+        use solana_program::declare_id;
+        declare_id!("C1nEbACFaHMUiKAUsXVYPWZsuxunJeBkqXHPFr8QgSj9");
+    }
+    pub mod serum_asks {
+        use solana_program::declare_id;
+        declare_id!("4DNBdnTw6wmrK4NmdSTTxs1kEz47yjqLGuoqsMeHvkMF");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("HUDr9BDaAGqi37xbQHzxCyXvfMCKPTPNF8g9c9bPu1Fu");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("9VbmvaaPeNAke2MAL3h2Fw82VubH1tBCzwBzaWybGKiG");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("A4xQv2BQPB1WxsjiCC7tcMH7zUq255uCBkevFj8qSCyJ");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("6zA5RAQYgazm4dniS8AigjGFtRi4xneqjL7ehrSqCmhr");
+    }
 }
 pub mod srm_usdc_raydium {
     use solana_program::declare_id;
@@ -479,94 +1732,650 @@ pub mod srm_usdc_raydium {
         use solana_program::declare_id;
         declare_id!("AMpGacKHMNbQvKXGCkCGq4tNeXokHZwvPiYSeyHS6PvG");
     }
+    pub mod amm {
+        use solana_program::declare_id;
+        declare_id!("8tzS7SkUZyHPQY7gLqsMCXZ5EDCgjESUHcB17tiR1h3Z");
+    }
+    pub mod amm_authority {
+        use solana_program::declare_id;
+        declare_id!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    }
+    pub mod open_orders {
+        use solana_program::declare_id;
+        declare_id!("GJwrRrNeeQKY2eGzuXGc3KBrBftYbidCYhmA6AZj2Zur");
+    }
+    pub mod target_orders {
+        use solana_program::declare_id;
+        declare_id!("26LLpo8rscCpMxyAnJsqhqESPnzjMGiFdmXA4eF2Jrk5");
+    }
+    pub mod vault_coin {
+        use solana_program::declare_id;
+        declare_id!("zuLDJ5SEe76L3bpFp2Sm9qTTe5vpJL3gdQFT5At5xXG");
+    }
+    pub mod vault_pc {
+        use solana_program::declare_id;
+        declare_id!("4usvfgPDwXBX2ySX11ubTvJ3pvJHbGEW2ytpDGCSv5cw");
+    }
+    pub mod withdraw_queue {
+        use solana_program::declare_id;
+        declare_id!("7c1VbXTB7Xqx5eQQeUxAu5o6GHPq3P1ByhDsnRRUWYxB");
+    }
+    pub mod temp_lp_token_account {
+        use solana_program::declare_id;
+        declare_id!("2sozAi6zXDUCCkpgG3usphzeCDm4e2jTFngbm5atSdC9");
+    }
+    pub mod serum_program {
+        use solana_program::declare_id;
+        declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+    }
+    pub mod serum_market {
+        use solana_program::declare_id;
+        declare_id!("ByRys5tuUWDgL73G8JBAEfkdFf8JWBzPBDHsBVQ5vbQA");
+    }
+    pub mod serum_vault_coin {
+        use solana_program::declare_id;
+        declare_id!("Ecfy8et9Mft9Dkavnuh4mzHMa2KWYUbBTA5oDZNoWu84");
+    }
+    pub mod serum_vault_pc {
+        use solana_program::declare_id;
+        declare_id!("hUgoKy5wjeFbZrXDW4ecr42T4F5Z1Tos31g68s5EHbP");
+    }
+    pub mod serum_vault_signer {
+        use solana_program::declare_id;
+        declare_id!("GVV4ZT9pccwy9d17STafFDuiSqFbXuRTdvKQ1zJX6ttX");
+    }
+    pub mod farm {
+        use solana_program::declare_id;
+        declare_id!("27bysJaX5eu5Urb5kftR66otiVc6DKK7TnifKwnpNzYu");
+    }
+    pub mod farm_authority {
+        use solana_program::declare_id;
+        declare_id!("HAWwtFc4MFNSXFyQbUZd2GefSwZLntCiumt1D6XM8jfk");
+    }
+    pub mod farm_lp_vault {
+        use solana_program::declare_id;
+        declare_id!("HVEm5BG4jMHtwgrUtuiC9K17bjp9CjFpgqmzVABmzLxr");
+    }
+    pub mod reward_ray_vault {
+        use solana_program::declare_id;
+        declare_id!("9gs6XnKs3RMMSSQAZm3VCbRpoNmPMrGaQQGMmRKjPeSU");
+    }
+    pub mod reward_second_vault {
+        use solana_program::declare_id;
+        declare_id!("BsuQ3XCCapopam8byEzHzazyxcRn5dCT3UX9kUzozhw");
+    }
+}
+
+// swap programs used for margin swaps and LP operations
+pub mod serum_program {
+    use solana_program::declare_id;
+    declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+}
+pub mod raydium_amm_program {
+    use solana_program::declare_id;
+    declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
 }
+pub mod saber_swap_program {
+    use solana_program::declare_id;
+    declare_id!("SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ");
+}
+pub mod orca_swap_program {
+    use solana_program::declare_id;
+    declare_id!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+}
+
+pub mod associated_token_program {
+    use solana_program::declare_id;
+    declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+// farm programs LP is staked into
+pub mod saber_quarry_program {
+    use solana_program::declare_id;
+    declare_id!("QMNeHCGYnLVDn1icRAfQZpjPLBNkfGbSKRB83G5d8KB");
+}
+pub mod orca_farm_program {
+    use solana_program::declare_id;
+    declare_id!("82yxjeMsvaURa4MbZZ7WZZHfobirZYkH1zF8fmeGtyaQ");
+}
+pub mod raydium_stake_program {
+    use solana_program::declare_id;
+    declare_id!("EhhTKczWMGQt46ynNeRX1WfeagwwJd7ufHvCDjRxjo5Q");
+}
+pub mod raydium_stake_v5_program {
+    use solana_program::declare_id;
+    declare_id!("9KEPoZmtHUrBbhWN1v1KWLMkkvwY6WLtAVUCPRtRjP4z");
+}
+
+// TokenCategory in sdk-ts
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TokenCategory {
+    Volatile,
+    Stable,
+    Lp,
+}
+
+// second farm of orca double-dip LP pools
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OrcaDoubleDipAccounts {
+    pub user_farm_state: Pubkey,
+    pub reward_dd_account: Pubkey,
+    pub floating_lp: Pubkey,
+}
+
+// farm accounts of an LP pool, the config::<pool> submodules
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LpAccounts {
+    Saber {
+        miner: Pubkey,
+    },
+    Orca {
+        user_farm_state: Pubkey,
+        reward_orca_account: Pubkey,
+        double_dip: Option<OrcaDoubleDipAccounts>,
+    },
+    Raydium {
+        reward_ray_account: Pubkey,
+        reward_second_account: Pubkey,
+        user_ledger_account: Pubkey,
+    },
+}
+
+/**
+ * Static description of an Apricot pool. dex is the venue LP pools are created and redeemed on,
+ * lp_accounts their farm accounts (None for LP pools that aren't staked).
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolConfig {
+    pub pool_id: u8,
+    pub name: Cow<'static, str>,   // TokenID in sdk-ts, unique
+    pub symbol: Cow<'static, str>, // for display, e.g. SOL-USDC for both SOL_USDC_ORCA and _RAYDIUM
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub category: TokenCategory,
+    pub dex: Option<SwapVenue>,
+    pub lp_accounts: Option<LpAccounts>,
+    pub lp_components: Option<(u8, u8)>, // (left, right) pool ids of an LP pool, LP_TO_LR in sdk-ts
+    // extra collateral value a liquidator receives per repaid value when seizing this pool's
+    // deposits, LIQUIDATION_DISCOUNT in sdk-ts. 0 means the pool isn't seized (APT, LP pools)
+    pub liquidation_discount: f64,
+}
+
+impl PoolConfig {
+    pub fn is_lp(&self) -> bool {
+        self.category == TokenCategory::Lp
+    }
+
+    pub fn asset_pool_k(&self, env: &ApricotEnv) -> Pubkey {
+        env.asset_pool_k(self.pool_id)
+    }
+
+    pub fn to_token_amount(&self, amount: NativeAmount) -> TokenAmount {
+        amount.to_token_amount(self.decimals)
+    }
+
+    pub fn to_native_amount(
+        &self,
+        amount: TokenAmount,
+        rounding: Rounding,
+    ) -> Option<NativeAmount> {
+        amount.to_native_amount(self.decimals, rounding)
+    }
 
+    // price_in_usd is per whole token
+    pub fn to_usd_amount(&self, amount: NativeAmount, price_in_usd: f64) -> UsdAmount {
+        amount.to_usd_amount(self.decimals, price_in_usd)
+    }
+
+    // exact decimal representation with the symbol, e.g. "1.5 SOL" for 1_500_000_000
+    pub fn format_amount(&self, amount: NativeAmount) -> String {
+        // with 20 decimals or more any u64 amount is below one token
+        let (whole, fraction) = match 10u64.checked_pow(self.decimals as u32) {
+            Some(multiplier) => (amount.0 / multiplier, amount.0 % multiplier),
+            None => (0, amount.0),
+        };
+        if fraction == 0 {
+            return format!("{} {}", whole, self.symbol);
+        }
+        let fraction = format!("{:0width$}", fraction, width = self.decimals as usize);
+        format!(
+            "{}.{} {}",
+            whole,
+            fraction.trim_end_matches('0'),
+            self.symbol
+        )
+    }
+}
+
+const fn token(
+    pool_id: u8,
+    name: &'static str,
+    mint: Pubkey,
+    decimals: u8,
+    category: TokenCategory,
+    liquidation_discount: f64,
+) -> PoolConfig {
+    PoolConfig {
+        pool_id,
+        name: Cow::Borrowed(name),
+        symbol: Cow::Borrowed(name),
+        mint,
+        decimals,
+        category,
+        dex: None,
+        lp_accounts: None,
+        lp_components: None,
+        liquidation_discount,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn lp(
+    pool_id: u8,
+    name: &'static str,
+    symbol: &'static str,
+    mint: Pubkey,
+    decimals: u8,
+    lp_components: (u8, u8),
+    dex: SwapVenue,
+    lp_accounts: Option<LpAccounts>,
+) -> PoolConfig {
+    PoolConfig {
+        pool_id,
+        name: Cow::Borrowed(name),
+        symbol: Cow::Borrowed(symbol),
+        mint,
+        decimals,
+        category: TokenCategory::Lp,
+        dex: Some(dex),
+        lp_accounts,
+        lp_components: Some(lp_components),
+        liquidation_discount: 0.0,
+    }
+}
+
+// $left and $right are the component pools in LP_TO_LR order
+macro_rules! saber_lp {
+    ($pool:ident, $decimals:expr, $symbol:expr, $left:ident, $right:ident) => {
+        lp(
+            $pool::POOL_ID,
+            $pool::NAME,
+            $symbol,
+            $pool::ID,
+            $decimals,
+            ($left::POOL_ID, $right::POOL_ID),
+            SwapVenue::Saber,
+            Some(LpAccounts::Saber {
+                miner: $pool::miner::ID,
+            }),
+        )
+    };
+}
+
+macro_rules! orca_lp {
+    ($pool:ident, $decimals:expr, $symbol:expr, $left:ident, $right:ident) => {
+        orca_lp!($pool, $decimals, $symbol, $left, $right, None)
+    };
+    ($pool:ident, $decimals:expr, $symbol:expr, $left:ident, $right:ident, double_dip) => {
+        orca_lp!(
+            $pool,
+            $decimals,
+            $symbol,
+            $left,
+            $right,
+            Some(OrcaDoubleDipAccounts {
+                user_farm_state: $pool::user_farm_state_2nd::ID,
+                reward_dd_account: $pool::reward_dd_account_2nd::ID,
+                floating_lp: $pool::floating_lp_2nd::ID,
+            })
+        )
+    };
+    ($pool:ident, $decimals:expr, $symbol:expr, $left:ident, $right:ident, $double_dip:expr) => {
+        lp(
+            $pool::POOL_ID,
+            $pool::NAME,
+            $symbol,
+            $pool::ID,
+            $decimals,
+            ($left::POOL_ID, $right::POOL_ID),
+            SwapVenue::Orca,
+            Some(LpAccounts::Orca {
+                user_farm_state: $pool::user_farm_state::ID,
+                reward_orca_account: $pool::reward_orca_account::ID,
+                double_dip: $double_dip,
+            }),
+        )
+    };
+}
+
+macro_rules! raydium_lp {
+    ($pool:ident, $decimals:expr, $symbol:expr, $left:ident, $right:ident) => {
+        lp(
+            $pool::POOL_ID,
+            $pool::NAME,
+            $symbol,
+            $pool::ID,
+            $decimals,
+            ($left::POOL_ID, $right::POOL_ID),
+            SwapVenue::Raydium,
+            Some(LpAccounts::Raydium {
+                reward_ray_account: $pool::reward_ray_account::ID,
+                reward_second_account: $pool::reward_second_account::ID,
+                user_ledger_account: $pool::user_ledger_account::ID,
+            }),
+        )
+    };
+}
+
+use TokenCategory::{Stable, Volatile};
+
+// every pool of the modules above, decimals as DECIMAL_MULT and liquidation discounts as
+// LIQUIDATION_DISCOUNT in sdk-ts
+pub static POOLS: &[PoolConfig] = &[
+    token(apt::POOL_ID, apt::NAME, apt::ID, 6, Volatile, 0.0),
+    token(btc::POOL_ID, btc::NAME, btc::ID, 6, Volatile, 0.04),
+    token(eth::POOL_ID, eth::NAME, eth::ID, 6, Volatile, 0.04),
+    token(wheth::POOL_ID, wheth::NAME, wheth::ID, 8, Volatile, 0.04),
+    token(sol::POOL_ID, sol::NAME, sol::ID, 9, Volatile, 0.04),
+    token(msol::POOL_ID, msol::NAME, msol::ID, 9, Volatile, 0.04),
+    token(stsol::POOL_ID, stsol::NAME, stsol::ID, 9, Volatile, 0.04),
+    token(scnsol::POOL_ID, scnsol::NAME, scnsol::ID, 9, Volatile, 0.04),
+    token(ray::POOL_ID, ray::NAME, ray::ID, 6, Volatile, 0.04),
+    token(orca::POOL_ID, orca::NAME, orca::ID, 6, Volatile, 0.04),
+    token(srm::POOL_ID, srm::NAME, srm::ID, 6, Volatile, 0.04),
+    token(usdt::POOL_ID, usdt::NAME, usdt::ID, 6, Stable, 0.04),
+    token(usdc::POOL_ID, usdc::NAME, usdc::ID, 6, Stable, 0.04),
+    token(ust::POOL_ID, ust::NAME, ust::ID, 6, Stable, 0.04),
+    saber_lp!(usdt_usdc_saber, 6, "USDT-USDC", usdt, usdc),
+    saber_lp!(msol_sol_saber, 9, "mSOL-SOL", msol, sol),
+    saber_lp!(stsol_sol_saber, 9, "stSOL-SOL", stsol, sol),
+    saber_lp!(ust_usdc_saber, 6, "UST-USDC", ust, usdc),
+    orca_lp!(usdc_usdt_orca, 6, "USDC-USDT", usdc, usdt),
+    orca_lp!(sol_usdc_orca, 6, "SOL-USDC", sol, usdc),
+    orca_lp!(msol_sol_orca, 6, "mSOL-SOL", msol, sol, double_dip),
+    orca_lp!(orca_usdc_orca, 6, "ORCA-USDC", orca, usdc),
+    orca_lp!(orca_sol_orca, 6, "ORCA-SOL", orca, sol),
+    orca_lp!(eth_usdc_orca, 6, "ETH-USDC", eth, usdc),
+    orca_lp!(sol_usdt_orca, 6, "SOL-USDT", sol, usdt),
+    orca_lp!(eth_sol_orca, 6, "ETH-SOL", eth, sol),
+    orca_lp!(apt_usdc_orca, 6, "APT-USDC", apt, usdc),
+    orca_lp!(btc_msol_orca, 6, "BTC-mSOL", btc, msol, double_dip),
+    orca_lp!(msol_usdc_orca, 6, "mSOL-USDC", msol, usdc, double_dip),
+    orca_lp!(stsol_ust_orca, 6, "stSOL-UST", stsol, ust),
+    orca_lp!(orca_wheth_orca, 6, "ORCA-whETH", orca, wheth),
+    raydium_lp!(sol_usdc_raydium, 9, "SOL-USDC", sol, usdc),
+    raydium_lp!(ray_usdc_raydium, 6, "RAY-USDC", ray, usdc),
+    raydium_lp!(sol_usdt_raydium, 9, "SOL-USDT", sol, usdt),
+    lp(
+        msol_sol_raydium::POOL_ID,
+        msol_sol_raydium::NAME,
+        "mSOL-SOL",
+        msol_sol_raydium::ID,
+        9,
+        (msol::POOL_ID, sol::POOL_ID),
+        SwapVenue::Raydium,
+        None,
+    ),
+    raydium_lp!(ray_usdt_raydium, 6, "RAY-USDT", ray, usdt),
+    raydium_lp!(ray_eth_raydium, 6, "RAY-ETH", ray, eth),
+    raydium_lp!(ray_sol_raydium, 6, "RAY-SOL", ray, sol),
+    raydium_lp!(srm_usdc_raydium, 6, "SRM-USDC", srm, usdc),
+];
+
+pub fn pools() -> impl Iterator<Item = &'static PoolConfig> {
+    POOLS.iter()
+}
+
+pub fn pool_by_id(pool_id: u8) -> Option<&'static PoolConfig> {
+    pools().find(|pool| pool.pool_id == pool_id)
+}
+
+// other names pools are known by, e.g. bridge-prefixed symbols
+pub static POOL_ALIASES: &[(&str, u8)] = &[
+    ("soBTC", btc::POOL_ID),
+    ("soETH", eth::POOL_ID),
+    ("wETH", wheth::POOL_ID),
+    ("wSOL", sol::POOL_ID),
+    ("USTv2", ust::POOL_ID),
+    ("USTv2_USDC_SABER", ust_usdc_saber::POOL_ID),
+    ("stSOL_USTv2_ORCA", stsol_ust_orca::POOL_ID),
+];
+
+// case-insensitive, also accepts POOL_ALIASES
+pub fn pool_by_name(name: &str) -> Option<&'static PoolConfig> {
+    find_pool_by_name(POOLS, POOL_ALIASES.iter().copied(), name)
+}
+
+pub(crate) fn find_pool_by_name<'a, 'b>(
+    pools: &'a [PoolConfig],
+    aliases: impl IntoIterator<Item = (&'b str, u8)>,
+    name: &str,
+) -> Option<&'a PoolConfig> {
+    pools
+        .iter()
+        .find(|pool| pool.name.eq_ignore_ascii_case(name))
+        .or_else(|| {
+            let (_, pool_id) = aliases
+                .into_iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(name))?;
+            pools.iter().find(|pool| pool.pool_id == pool_id)
+        })
+}
+
+pub fn pool_by_mint(mint: &Pubkey) -> Option<&'static PoolConfig> {
+    pools().find(|pool| pool.mint == *mint)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigError {
+    UnknownPoolName(String),
+    UnknownPoolId(u8),
+    UnknownMint(Pubkey),
+    InvalidRegistry(String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            ConfigError::UnknownPoolName(name) => write!(f, "no pool for token {}", name),
+            ConfigError::UnknownPoolId(pool_id) => write!(f, "no pool with id {}", pool_id),
+            ConfigError::UnknownMint(mint) => write!(f, "no pool for mint {}", mint),
+            ConfigError::InvalidRegistry(reason) => write!(f, "invalid pool registry: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub fn try_get_pool_id_by_name(token_name: &str) -> Result<u8, ConfigError> {
+    pool_by_name(token_name)
+        .map(|pool| pool.pool_id)
+        .ok_or_else(|| ConfigError::UnknownPoolName(token_name.to_string()))
+}
+
+pub fn try_get_mint_by_pool_id(pool_id: u8) -> Result<Pubkey, ConfigError> {
+    pool_by_id(pool_id)
+        .map(|pool| pool.mint)
+        .ok_or(ConfigError::UnknownPoolId(pool_id))
+}
+
+pub fn try_get_pool_id_by_mint(mint: &Pubkey) -> Result<u8, ConfigError> {
+    pool_by_mint(mint)
+        .map(|pool| pool.pool_id)
+        .ok_or(ConfigError::UnknownMint(*mint))
+}
+
+// configs of the (left, right) component pools of an LP pool
+pub fn lp_component_pools(pool_id: u8) -> Option<(&'static PoolConfig, &'static PoolConfig)> {
+    let (left, right) = pool_by_id(pool_id)?.lp_components?;
+    Some((pool_by_id(left)?, pool_by_id(right)?))
+}
+
+// derives the asset pool address of every pool until one matches
+pub fn pool_by_asset_pool_k(asset_pool: &Pubkey, env: &ApricotEnv) -> Option<&'static PoolConfig> {
+    pools().find(|pool| pool.asset_pool_k(env) == *asset_pool)
+}
+
+// panics for unknown tokens, see try_get_pool_id_by_name
 pub fn get_pool_id_by_name(token_name: &str) -> u8 {
-    match token_name {
-        apt::NAME => apt::POOL_ID,
-        btc::NAME => btc::POOL_ID,
-        eth::NAME => eth::POOL_ID,
-        wheth::NAME => wheth::POOL_ID,
-        sol::NAME => sol::POOL_ID,
-        msol::NAME => msol::POOL_ID,
-        stsol::NAME => stsol::POOL_ID,
-        scnsol::NAME => scnsol::POOL_ID,
-        ray::NAME => ray::POOL_ID,
-        orca::NAME => orca::POOL_ID,
-        srm::NAME => srm::POOL_ID,
-        usdt::NAME => usdt::POOL_ID,
-        usdc::NAME => usdc::POOL_ID,
-        ust::NAME => ust::POOL_ID,
-        usdt_usdc_saber::NAME => usdt_usdc_saber::POOL_ID,
-        msol_sol_saber::NAME => msol_sol_saber::POOL_ID,
-        stsol_sol_saber::NAME => stsol_sol_saber::POOL_ID,
-        ust_usdc_saber::NAME => ust_usdc_saber::POOL_ID,
-        usdc_usdt_orca::NAME => usdc_usdt_orca::POOL_ID,
-        sol_usdc_orca::NAME => sol_usdc_orca::POOL_ID,
-        msol_sol_orca::NAME => msol_sol_orca::POOL_ID,
-        orca_usdc_orca::NAME => orca_usdc_orca::POOL_ID,
-        orca_sol_orca::NAME => orca_sol_orca::POOL_ID,
-        eth_usdc_orca::NAME => eth_usdc_orca::POOL_ID,
-        sol_usdt_orca::NAME => sol_usdt_orca::POOL_ID,
-        eth_sol_orca::NAME => eth_sol_orca::POOL_ID,
-        apt_usdc_orca::NAME => apt_usdc_orca::POOL_ID,
-        btc_msol_orca::NAME => btc_msol_orca::POOL_ID,
-        msol_usdc_orca::NAME => msol_usdc_orca::POOL_ID,
-        stsol_ust_orca::NAME => stsol_ust_orca::POOL_ID,
-        orca_wheth_orca::NAME => orca_wheth_orca::POOL_ID,
-        sol_usdc_raydium::NAME => sol_usdc_raydium::POOL_ID,
-        ray_usdc_raydium::NAME => ray_usdc_raydium::POOL_ID,
-        sol_usdt_raydium::NAME => sol_usdt_raydium::POOL_ID,
-        msol_sol_raydium::NAME => msol_sol_raydium::POOL_ID,
-        ray_usdt_raydium::NAME => ray_usdt_raydium::POOL_ID,
-        ray_eth_raydium::NAME => ray_eth_raydium::POOL_ID,
-        ray_sol_raydium::NAME => ray_sol_raydium::POOL_ID,
-        srm_usdc_raydium::NAME => srm_usdc_raydium::POOL_ID,
-        _ => panic!("Token doens't have a pool!"),
+    match pool_by_name(token_name) {
+        Some(pool) => pool.pool_id,
+        None => panic!("Token doens't have a pool!"),
     }
 }
 
+// panics for unknown pools, see try_get_mint_by_pool_id
 pub fn get_mint_by_pool_id(pool_id: u8) -> Pubkey {
-    match pool_id {
-        apt::POOL_ID => apt::ID,
-        btc::POOL_ID => btc::ID,
-        eth::POOL_ID => eth::ID,
-        wheth::POOL_ID => wheth::ID,
-        sol::POOL_ID => sol::ID,
-        msol::POOL_ID => msol::ID,
-        stsol::POOL_ID => stsol::ID,
-        scnsol::POOL_ID => scnsol::ID,
-        ray::POOL_ID => ray::ID,
-        orca::POOL_ID => orca::ID,
-        srm::POOL_ID => srm::ID,
-        usdt::POOL_ID => usdt::ID,
-        usdc::POOL_ID => usdc::ID,
-        ust::POOL_ID => ust::ID,
-        usdt_usdc_saber::POOL_ID => usdt_usdc_saber::ID,
-        msol_sol_saber::POOL_ID => msol_sol_saber::ID,
-        stsol_sol_saber::POOL_ID => stsol_sol_saber::ID,
-        ust_usdc_saber::POOL_ID => ust_usdc_saber::ID,
-        usdc_usdt_orca::POOL_ID => usdc_usdt_orca::ID,
-        sol_usdc_orca::POOL_ID => sol_usdc_orca::ID,
-        msol_sol_orca::POOL_ID => msol_sol_orca::ID,
-        orca_usdc_orca::POOL_ID => orca_usdc_orca::ID,
-        orca_sol_orca::POOL_ID => orca_sol_orca::ID,
-        eth_usdc_orca::POOL_ID => eth_usdc_orca::ID,
-        sol_usdt_orca::POOL_ID => sol_usdt_orca::ID,
-        eth_sol_orca::POOL_ID => eth_sol_orca::ID,
-        apt_usdc_orca::POOL_ID => apt_usdc_orca::ID,
-        btc_msol_orca::POOL_ID => btc_msol_orca::ID,
-        msol_usdc_orca::POOL_ID => msol_usdc_orca::ID,
-        stsol_ust_orca::POOL_ID => stsol_ust_orca::ID,
-        orca_wheth_orca::POOL_ID => orca_wheth_orca::ID,
-        sol_usdc_raydium::POOL_ID => sol_usdc_raydium::ID,
-        ray_usdc_raydium::POOL_ID => ray_usdc_raydium::ID,
-        sol_usdt_raydium::POOL_ID => sol_usdt_raydium::ID,
-        msol_sol_raydium::POOL_ID => msol_sol_raydium::ID,
-        ray_usdt_raydium::POOL_ID => ray_usdt_raydium::ID,
-        ray_eth_raydium::POOL_ID => ray_eth_raydium::ID,
-        ray_sol_raydium::POOL_ID => ray_sol_raydium::ID,
-        srm_usdc_raydium::POOL_ID => srm_usdc_raydium::ID,
-        _ => panic!("Token doens't have a pool!"),
+    match pool_by_id(pool_id) {
+        Some(pool) => pool.mint,
+        None => panic!("Token doens't have a pool!"),
+    }
+}
+
+#[cfg(test)]
+pub mod pool_config_test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_pool_table() {
+        assert_eq!(POOLS.len(), 39);
+        let ids: HashSet<u8> = pools().map(|pool| pool.pool_id).collect();
+        let mints: HashSet<Pubkey> = pools().map(|pool| pool.mint).collect();
+        assert_eq!(ids.len(), POOLS.len());
+        assert_eq!(mints.len(), POOLS.len());
+
+        for pool in pools() {
+            assert_eq!(pool.is_lp(), pool.dex.is_some());
+            assert_eq!(pool.is_lp(), pool.lp_components.is_some());
+            assert!(pool.lp_accounts.is_none() || pool.is_lp());
+        }
+    }
+
+    #[test]
+    fn test_lookups() {
+        let pool = pool_by_id(sol_usdc_orca::POOL_ID).unwrap();
+        assert_eq!(pool.name, "SOL_USDC_ORCA");
+        assert_eq!(pool.dex, Some(SwapVenue::Orca));
+        assert_eq!(
+            pool.lp_accounts,
+            Some(LpAccounts::Orca {
+                user_farm_state: sol_usdc_orca::user_farm_state::ID,
+                reward_orca_account: sol_usdc_orca::reward_orca_account::ID,
+                double_dip: None,
+            })
+        );
+        assert_eq!(pool_by_name("mSOL").unwrap().decimals, 9);
+        assert_eq!(
+            pool_by_mint(&usdc::ID).unwrap().category,
+            TokenCategory::Stable
+        );
+        assert!(pool_by_id(7).is_none());
+
+        let env = ApricotEnv::mainnet();
+        let asset_pool = env.asset_pool_k(btc::POOL_ID);
+        assert_eq!(
+            pool_by_asset_pool_k(&asset_pool, &env).unwrap().pool_id,
+            btc::POOL_ID
+        );
+        assert!(pool_by_asset_pool_k(&btc::ID, &env).is_none());
+
+        assert_eq!(get_pool_id_by_name("stSOL"), stsol::POOL_ID);
+        assert_eq!(get_pool_id_by_name("stsol"), stsol::POOL_ID);
+        assert_eq!(get_mint_by_pool_id(ray::POOL_ID), ray::ID);
+    }
+
+    #[test]
+    fn test_metadata() {
+        let (left, right) = lp_component_pools(sol_usdc_orca::POOL_ID).unwrap();
+        assert_eq!((left.pool_id, right.pool_id), (sol::POOL_ID, usdc::POOL_ID));
+        assert!(lp_component_pools(sol::POOL_ID).is_none());
+        for pool in pools() {
+            if let Some((left, right)) = lp_component_pools(pool.pool_id) {
+                assert_eq!(pool.symbol, format!("{}-{}", left.symbol, right.symbol));
+            }
+        }
+
+        let sol_pool = pool_by_id(sol::POOL_ID).unwrap();
+        assert_eq!(sol_pool.symbol, "SOL");
+        assert_eq!(
+            sol_pool.format_amount(NativeAmount(1_500_000_000)),
+            "1.5 SOL"
+        );
+        assert_eq!(sol_pool.format_amount(NativeAmount(2_000_000_000)), "2 SOL");
+        assert_eq!(sol_pool.format_amount(NativeAmount(1)), "0.000000001 SOL");
+        let mut many_decimals = sol_pool.clone();
+        many_decimals.decimals = 21;
+        assert_eq!(
+            many_decimals.format_amount(NativeAmount(12)),
+            "0.000000000000000000012 SOL"
+        );
+        assert_eq!(
+            sol_pool.to_token_amount(NativeAmount(500_000_000)),
+            TokenAmount(0.5)
+        );
+        assert_eq!(
+            sol_pool.to_usd_amount(NativeAmount(500_000_000), 100.0),
+            UsdAmount(50.0)
+        );
+
+        let wheth_pool = pool_by_id(wheth::POOL_ID).unwrap();
+        assert_eq!(
+            wheth_pool.to_native_amount(TokenAmount(1.5), Rounding::Down),
+            Some(NativeAmount(150_000_000))
+        );
+        assert_eq!(
+            pool_by_id(usdc::POOL_ID)
+                .unwrap()
+                .format_amount(NativeAmount(1_230_000)),
+            "1.23 USDC"
+        );
+
+        // LIQUIDATION_DISCOUNT in sdk-ts: 4% except APT and LP pools
+        for pool in pools() {
+            let expected = if pool.is_lp() || pool.pool_id == apt::POOL_ID {
+                0.0
+            } else {
+                0.04
+            };
+            assert_eq!(pool.liquidation_discount, expected, "{}", pool.name);
+        }
+    }
+
+    #[test]
+    fn test_fallible_lookups() {
+        assert_eq!(try_get_pool_id_by_name("msol"), Ok(msol::POOL_ID));
+        assert_eq!(
+            try_get_pool_id_by_name("MSOL_SOL_saber"),
+            Ok(msol_sol_saber::POOL_ID)
+        );
+        assert_eq!(try_get_pool_id_by_name("WETH"), Ok(wheth::POOL_ID));
+        assert_eq!(try_get_pool_id_by_name("whETH"), Ok(wheth::POOL_ID));
+        assert_eq!(try_get_pool_id_by_name("ustv2"), Ok(ust::POOL_ID));
+        assert_eq!(
+            try_get_pool_id_by_name("DOGE"),
+            Err(ConfigError::UnknownPoolName("DOGE".to_string()))
+        );
+        assert_eq!(try_get_mint_by_pool_id(usdc::POOL_ID), Ok(usdc::ID));
+        assert_eq!(
+            try_get_mint_by_pool_id(7),
+            Err(ConfigError::UnknownPoolId(7))
+        );
+        assert_eq!(try_get_pool_id_by_mint(&stsol::ID), Ok(stsol::POOL_ID));
+        assert_eq!(
+            try_get_pool_id_by_mint(&Pubkey::default()),
+            Err(ConfigError::UnknownMint(Pubkey::default()))
+        );
+
+        // names and aliases stay unambiguous without case
+        let mut names: Vec<String> = pools().map(|pool| pool.name.to_lowercase()).collect();
+        names.extend(POOL_ALIASES.iter().map(|(alias, _)| alias.to_lowercase()));
+        let num_names = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), num_names);
+        assert!(POOL_ALIASES
+            .iter()
+            .all(|(_, pool_id)| pool_by_id(*pool_id).is_some()));
     }
 }
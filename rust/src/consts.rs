@@ -1,4 +1,7 @@
 use solana_program::pubkey::Pubkey;
+use spl_token;
+
+use crate::config;
 
 pub mod program {
     use solana_program::declare_id;
@@ -38,6 +41,30 @@ pub mod lm_apt_vault {
     declare_id!("C1k4CehboSgUkmL3BJfw32Xj9HPs9NKTzhT5WXsYwWh4");
 }
 
+// creates user_stats and pool_summaries
+pub mod admin {
+    use solana_program::declare_id;
+    declare_id!("6L2QoTpr8WUd76eLAGnvow8i3WQzRP36C1qdUna9iwMn");
+}
+
+// alpha deployment, the other roots derive from these, see env::ApricotEnv::alpha()
+pub mod alpha {
+    pub mod program {
+        use solana_program::declare_id;
+        declare_id!("5dtKmAzoJu4qDxMjjK7gWY2pPe6NWAX6HWQk5QUHaKQZ");
+    }
+
+    pub mod admin {
+        use solana_program::declare_id;
+        declare_id!("EFo9V7mFQgxz7xPMrJ6qLyrjfGXPgsEFEfGEtVQx2xKt");
+    }
+
+    pub mod lm_apt_vault {
+        use solana_program::declare_id;
+        declare_id!("Cuf4Hbuv9RDZ1vzuUE833MKzjeX7odsBeewEjhmVwVRk");
+    }
+}
+
 // commands
 
 pub const CMD_REFRESH_USER: u8 = 0x0a;
@@ -52,8 +79,24 @@ pub const CMD_UPDATE_USER_CONFIG: u8 = 0x17;
 pub const CMD_MARGIN_SWAP: u8 = 0x18;
 pub const CMD_UPDATE_USER_ASSET_CONFIG: u8 = 0x19;
 pub const CMD_WITHDRAW_AND_REMOVE_USER: u8 = 0x1a;
+pub const CMD_LP_CREATE: u8 = 0x1d;
+pub const CMD_LP_REDEEM: u8 = 0x1e;
+pub const CMD_LP_OP_CHECK: u8 = 0x21;
+pub const CMD_LP_OP_ENDCHECK: u8 = 0x22;
+pub const CMD_LP_STAKE: u8 = 0x23;
+pub const CMD_LP_UNSTAKE: u8 = 0x24;
 pub const CMD_CLAIM_APT_LM_REWARD: u8 = 0x29;
 pub const CMD_MAKE_LM_REWARD_AVAILABLE: u8 = 0x2a;
+pub const CMD_LP_STAKE_SECOND: u8 = 0x81;
+pub const CMD_LP_UNSTAKE_SECOND: u8 = 0x82;
+
+// swap identifiers
+pub const SWAP_FAKE: u8 = 0x00;
+pub const SWAP_SERUM: u8 = 0x01;
+pub const SWAP_RAYDIUM: u8 = 0x02;
+pub const SWAP_SABER: u8 = 0x03;
+pub const SWAP_MERCURIAL: u8 = 0x04;
+pub const SWAP_ORCA: u8 = 0x05;
 
 // errors
 pub const ERR_INCORRECT_BASE_PDA: u32 = 0x1000;
@@ -127,21 +170,54 @@ pub const ERR_ASSIST_CHECK_MUST_BE_FIRST: u32 = 0x4019;
 pub const ERR_ASSIST_ACTION_NOT_ALLOWED: u32 = 0x4020;
 pub const ERR_ASSIST_INCONSISTENT_ACTION: u32 = 0x4021;
 
-pub const USER_INFO_SEED: &'static str = "UserInfo";
-pub const USER_PAGES_STATS_SEED: &'static str = "UserPagesStats";
+// assist modes, see instructions::AssistConfig
+pub const ASSIST_MODE_DISABLED: u8 = 0;
+pub const ASSIST_MODE_CUSTOM: u8 = 1;
+pub const ASSIST_MODE_STABLE_ONLY: u8 = 2;
+pub const MAX_ASSIST_ACTIONS: usize = 6;
+
+// collateral ratio (borrow value / collateral value) thresholds
+pub const SAFE_LIMIT: f64 = 0.9;
+pub const FORCE_ASSIST_LIMIT: f64 = 1.0;
+pub const LIQUIDATION_LIMIT: f64 = 1.01;
+
+// AssetPool::flags bits, PoolFlag in sdk-ts
+pub const POOL_FLAG_ALLOW_BORROW: u8 = 1;
+pub const POOL_FLAG_IS_LP: u8 = 2;
+pub const POOL_FLAG_IS_STABLE: u8 = 4;
+
+// interest rates are APRs, accrued per second
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 3600;
+
+pub const BASE_PDA_SEED: &[u8] = b"2";
+pub const PRICE_PDA_SEED: &[u8] = b"PRICE";
+pub const USER_INFO_SEED: &str = "UserInfo";
+pub const USER_PAGES_STATS_SEED: &str = "UserPagesStats";
+pub const POOL_SUMMARIES_SEED: &str = "PoolSummaries";
+pub const PRICE_SUMMARIES_SEED: &str = "PriceSummaries";
 
 // Address calculation
 #[inline(always)]
-pub fn bytes_to_str(key_bytes: &[u8]) -> &str {
-    unsafe { std::mem::transmute(key_bytes) }
+pub fn bytes_to_str(key_bytes: &[u8]) -> Result<&str, std::str::Utf8Error> {
+    std::str::from_utf8(key_bytes)
 }
 
 #[inline(always)]
-pub fn pool_id_to_seed_str<'a>(pool_id: u8, buffer: &'a mut [u8; 8]) -> &'a str {
+pub fn pool_id_to_seed_str(pool_id: u8, buffer: &mut [u8; 8]) -> &str {
     *buffer = *b"POOL____";
     buffer[6] = (pool_id / 16) + b'a';
     buffer[7] = (pool_id % 16) + b'a';
-    bytes_to_str(buffer)
+    // 'a' to 'p', the seed is always ASCII
+    bytes_to_str(buffer).expect("ASCII seed")
+}
+
+#[inline(always)]
+pub fn pool_id_to_stake_table_seed_str(pool_id: u8, buffer: &mut [u8; 8]) -> &str {
+    *buffer = *b"STAK____";
+    buffer[6] = (pool_id / 16) + b'a';
+    buffer[7] = (pool_id % 16) + b'a';
+    // 'a' to 'p', the seed is always ASCII
+    bytes_to_str(buffer).expect("ASCII seed")
 }
 
 #[inline(always)]
@@ -172,19 +248,53 @@ pub fn get_users_page_k(page_id: u16) -> Pubkey {
 
 #[inline(always)]
 pub fn get_asset_pool_k(pool_id: u8) -> Pubkey {
-    let mut mint_seed_buffer = [0 as u8; 8];
+    let mut mint_seed_buffer = [0_u8; 8];
     let pool_seed_str = pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
     Pubkey::create_with_seed(&base_pda::ID, pool_seed_str, &program::ID).unwrap()
 }
 
 #[inline(always)]
 pub fn get_asset_pool_spl_k(token_program_id: &Pubkey, pool_id: u8) -> Pubkey {
-    let mut mint_seed_buffer = [0 as u8; 8];
+    let mut mint_seed_buffer = [0_u8; 8];
     let pool_seed_str = pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
     Pubkey::create_with_seed(&base_pda::ID, pool_seed_str, token_program_id).unwrap()
 }
 
+// only LP pools that need second stake have a stake table
+#[inline(always)]
+pub fn get_asset_pool_stake_table_k(pool_id: u8) -> Pubkey {
+    let mut stake_seed_buffer = [0_u8; 8];
+    let stake_seed_str = pool_id_to_stake_table_seed_str(pool_id, &mut stake_seed_buffer);
+    Pubkey::create_with_seed(&base_pda::ID, stake_seed_str, &program::ID).unwrap()
+}
+
 #[inline(always)]
 pub fn get_user_info_k(user_wallet_key: &Pubkey) -> Pubkey {
     Pubkey::create_with_seed(user_wallet_key, USER_INFO_SEED, &program::ID).unwrap()
 }
+
+// user's associated token account of the pool's mint, panics on unknown pool_id
+#[inline(always)]
+pub fn get_user_spl_k(user_wallet_key: &Pubkey, pool_id: u8) -> Pubkey {
+    get_associated_token_k(user_wallet_key, &config::get_mint_by_pool_id(pool_id))
+}
+
+#[inline(always)]
+pub fn try_get_user_spl_k(
+    user_wallet_key: &Pubkey,
+    pool_id: u8,
+) -> Result<Pubkey, config::ConfigError> {
+    Ok(get_associated_token_k(
+        user_wallet_key,
+        &config::try_get_mint_by_pool_id(pool_id)?,
+    ))
+}
+
+#[inline(always)]
+pub fn get_associated_token_k(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::ID.as_ref(), mint.as_ref()],
+        &config::associated_token_program::ID,
+    )
+    .0
+}
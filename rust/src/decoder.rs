@@ -0,0 +1,709 @@
+use solana_program::{
+    instruction::{CompiledInstruction, Instruction},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::accounts::*;
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::instructions::{
+    AddUserAndDepositParam, AssistConfig, BorrowParam, DepositParam, ExternLiquidateParam,
+    LpCreateParam, LpOpCheckParam, LpRedeemParam, LpStakeParam, LpUnstakeSecondParam,
+    MarginSwapParam, RepayParam, UpdateUserAssetConfigParam, UpdateUserConfigParam, WithdrawParam,
+};
+
+/**
+ * A decoded Apricot instruction, one variant per CMD_* opcode. Instructions that end with
+ * venue-specific keys (swap, LP and farm accounts) keep those in `remaining`, in order.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApricotInstruction {
+    RefreshUser {
+        accounts: RefreshUserAccounts,
+    },
+    AddUserAndDeposit {
+        param: AddUserAndDepositParam,
+        accounts: AddUserAndDepositAccounts,
+    },
+    Deposit {
+        param: DepositParam,
+        accounts: DepositAccounts,
+    },
+    Withdraw {
+        param: WithdrawParam,
+        accounts: WithdrawAccounts,
+    },
+    WithdrawAndRemoveUser {
+        param: WithdrawParam,
+        accounts: WithdrawAccounts,
+    },
+    Borrow {
+        param: BorrowParam,
+        accounts: BorrowAccounts,
+    },
+    Repay {
+        param: RepayParam,
+        accounts: RepayAccounts,
+    },
+    ExternLiquidate {
+        param: ExternLiquidateParam,
+        accounts: ExternLiquidateAccounts,
+    },
+    UpdateUserConfig {
+        config: AssistConfig,
+        accounts: UpdateUserConfigAccounts,
+    },
+    MarginSwap {
+        param: MarginSwapParam,
+        accounts: MarginSwapAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    UpdateUserAssetConfig {
+        param: UpdateUserAssetConfigParam,
+        accounts: UpdateUserAssetConfigAccounts,
+    },
+    LpCreate {
+        param: LpCreateParam,
+        accounts: LpOpAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    LpRedeem {
+        param: LpRedeemParam,
+        accounts: LpOpAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    LpOpCheck {
+        param: LpOpCheckParam,
+        accounts: LpOpCheckAccounts,
+    },
+    LpOpEndcheck {
+        accounts: LpOpEndcheckAccounts,
+    },
+    LpStake {
+        param: LpStakeParam,
+        accounts: LpStakeAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    LpUnstake {
+        param: LpStakeParam,
+        accounts: LpStakeAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    LpStakeSecond {
+        accounts: LpStakeSecondAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    LpUnstakeSecond {
+        param: LpUnstakeSecondParam,
+        accounts: LpUnstakeSecondAccounts,
+        remaining: Vec<Pubkey>,
+    },
+    ClaimAptLmReward {
+        accounts: ClaimAptLmRewardAccounts,
+    },
+    MakeLmRewardClaimable {
+        accounts: MakeLmRewardClaimableAccounts,
+    },
+}
+
+// reads little-endian values off instruction data, fails instead of reading past the end
+struct DataReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DataReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        if self.data.len() < len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn param<T: Pack>(&mut self) -> Result<T, ProgramError> {
+        T::unpack_from_slice(self.take(T::LEN)?)
+    }
+
+    // trailing bytes mean we got the layout wrong
+    fn finish(&self) -> Result<(), ProgramError> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+}
+
+struct AccountReader<'a> {
+    keys: &'a [Pubkey],
+}
+
+impl<'a> AccountReader<'a> {
+    fn next(&mut self) -> Result<Pubkey, ProgramError> {
+        match self.keys.split_first() {
+            Some((key, rest)) => {
+                self.keys = rest;
+                Ok(*key)
+            }
+            None => Err(ProgramError::NotEnoughAccountKeys),
+        }
+    }
+
+    fn remaining(&mut self) -> Vec<Pubkey> {
+        let keys = self.keys.to_vec();
+        self.keys = &[];
+        keys
+    }
+}
+
+impl ApricotInstruction {
+    pub fn from_instruction(ix: &Instruction) -> Result<Self, ProgramError> {
+        Self::from_instruction_for(&consts::program::ID, ix)
+    }
+
+    // for instructions sent to env's deployment, e.g. ApricotEnv::alpha()
+    pub fn from_instruction_with_env(
+        env: &ApricotEnv,
+        ix: &Instruction,
+    ) -> Result<Self, ProgramError> {
+        Self::from_instruction_for(&env.program_id, ix)
+    }
+
+    fn from_instruction_for(apricot_id: &Pubkey, ix: &Instruction) -> Result<Self, ProgramError> {
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        Self::decode_for(apricot_id, &ix.program_id, &keys, &ix.data)
+    }
+
+    // account_keys are the message's account keys, including any loaded from lookup tables
+    pub fn from_compiled(
+        account_keys: &[Pubkey],
+        ix: &CompiledInstruction,
+    ) -> Result<Self, ProgramError> {
+        Self::from_compiled_for(&consts::program::ID, account_keys, ix)
+    }
+
+    pub fn from_compiled_with_env(
+        env: &ApricotEnv,
+        account_keys: &[Pubkey],
+        ix: &CompiledInstruction,
+    ) -> Result<Self, ProgramError> {
+        Self::from_compiled_for(&env.program_id, account_keys, ix)
+    }
+
+    fn from_compiled_for(
+        apricot_id: &Pubkey,
+        account_keys: &[Pubkey],
+        ix: &CompiledInstruction,
+    ) -> Result<Self, ProgramError> {
+        let program_id = account_keys
+            .get(ix.program_id_index as usize)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let keys = ix
+            .accounts
+            .iter()
+            .map(|index| {
+                account_keys
+                    .get(*index as usize)
+                    .copied()
+                    .ok_or(ProgramError::NotEnoughAccountKeys)
+            })
+            .collect::<Result<Vec<Pubkey>, ProgramError>>()?;
+        Self::decode_for(apricot_id, program_id, &keys, &ix.data)
+    }
+
+    // decodes every Apricot instruction of a compiled message, other programs are skipped
+    pub fn from_compiled_message(
+        account_keys: &[Pubkey],
+        instructions: &[CompiledInstruction],
+    ) -> Vec<Result<Self, ProgramError>> {
+        Self::from_compiled_message_for(&consts::program::ID, account_keys, instructions)
+    }
+
+    pub fn from_compiled_message_with_env(
+        env: &ApricotEnv,
+        account_keys: &[Pubkey],
+        instructions: &[CompiledInstruction],
+    ) -> Vec<Result<Self, ProgramError>> {
+        Self::from_compiled_message_for(&env.program_id, account_keys, instructions)
+    }
+
+    fn from_compiled_message_for(
+        apricot_id: &Pubkey,
+        account_keys: &[Pubkey],
+        instructions: &[CompiledInstruction],
+    ) -> Vec<Result<Self, ProgramError>> {
+        instructions
+            .iter()
+            .filter(|ix| account_keys.get(ix.program_id_index as usize) == Some(apricot_id))
+            .map(|ix| Self::from_compiled_for(apricot_id, account_keys, ix))
+            .collect()
+    }
+
+    pub fn decode(program_id: &Pubkey, keys: &[Pubkey], data: &[u8]) -> Result<Self, ProgramError> {
+        Self::decode_for(&consts::program::ID, program_id, keys, data)
+    }
+
+    pub fn decode_with_env(
+        env: &ApricotEnv,
+        program_id: &Pubkey,
+        keys: &[Pubkey],
+        data: &[u8],
+    ) -> Result<Self, ProgramError> {
+        Self::decode_for(&env.program_id, program_id, keys, data)
+    }
+
+    // IncorrectProgramId unless program_id is apricot_id, the deployment being decoded
+    fn decode_for(
+        apricot_id: &Pubkey,
+        program_id: &Pubkey,
+        keys: &[Pubkey],
+        data: &[u8],
+    ) -> Result<Self, ProgramError> {
+        if program_id != apricot_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (cmd, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let mut d = DataReader { data: rest };
+        let mut a = AccountReader { keys };
+
+        let decoded = match *cmd {
+            consts::CMD_REFRESH_USER => ApricotInstruction::RefreshUser {
+                accounts: RefreshUserAccounts {
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    pool_summaries: a.next()?,
+                },
+            },
+            consts::CMD_ADD_USER_AND_DEPOSIT => ApricotInstruction::AddUserAndDeposit {
+                param: d.param()?,
+                accounts: AddUserAndDepositAccounts {
+                    user_wallet: a.next()?,
+                    user_spl: a.next()?,
+                    user_pages_stats: a.next()?,
+                    users_page: a.next()?,
+                    user_info: a.next()?,
+                    asset_pool: a.next()?,
+                    asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    system_program: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_DEPOSIT => ApricotInstruction::Deposit {
+                param: d.param()?,
+                accounts: DepositAccounts {
+                    user_wallet: a.next()?,
+                    user_spl: a.next()?,
+                    user_info: a.next()?,
+                    asset_pool: a.next()?,
+                    asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_WITHDRAW | consts::CMD_WITHDRAW_AND_REMOVE_USER => {
+                let remove_user = *cmd == consts::CMD_WITHDRAW_AND_REMOVE_USER;
+                let param = d.param()?;
+                let user_wallet = a.next()?;
+                let user_spl = a.next()?;
+                let (user_pages_stats, users_page) = if remove_user {
+                    (Some(a.next()?), Some(a.next()?))
+                } else {
+                    (None, None)
+                };
+                let accounts = WithdrawAccounts {
+                    user_wallet,
+                    user_spl,
+                    user_pages_stats,
+                    users_page,
+                    user_info: a.next()?,
+                    asset_pool: a.next()?,
+                    asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    base_pda: a.next()?,
+                    token_program: a.next()?,
+                };
+                if remove_user {
+                    ApricotInstruction::WithdrawAndRemoveUser { param, accounts }
+                } else {
+                    ApricotInstruction::Withdraw { param, accounts }
+                }
+            }
+            consts::CMD_BORROW => ApricotInstruction::Borrow {
+                param: d.param()?,
+                accounts: BorrowAccounts {
+                    user_wallet: a.next()?,
+                    user_spl: a.next()?,
+                    user_info: a.next()?,
+                    asset_pool: a.next()?,
+                    asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    base_pda: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_REPAY => ApricotInstruction::Repay {
+                param: d.param()?,
+                accounts: RepayAccounts {
+                    user_wallet: a.next()?,
+                    user_spl: a.next()?,
+                    user_info: a.next()?,
+                    asset_pool: a.next()?,
+                    asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_EXTERN_LIQUIDATE => ApricotInstruction::ExternLiquidate {
+                param: d.param()?,
+                accounts: ExternLiquidateAccounts {
+                    liquidated_wallet: a.next()?,
+                    liquidator_wallet: a.next()?,
+                    user_info: a.next()?,
+                    base_pda: a.next()?,
+                    liquidator_collateral_spl: a.next()?,
+                    liquidator_borrowed_spl: a.next()?,
+                    collateral_asset_pool: a.next()?,
+                    collateral_asset_pool_spl: a.next()?,
+                    borrowed_asset_pool: a.next()?,
+                    borrowed_asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_UPDATE_USER_CONFIG => ApricotInstruction::UpdateUserConfig {
+                config: AssistConfig::from(d.param::<UpdateUserConfigParam>()?),
+                accounts: UpdateUserConfigAccounts {
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                },
+            },
+            consts::CMD_MARGIN_SWAP => ApricotInstruction::MarginSwap {
+                param: d.param()?,
+                accounts: MarginSwapAccounts {
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    base_pda: a.next()?,
+                    sell_asset_pool: a.next()?,
+                    sell_asset_pool_spl: a.next()?,
+                    buy_asset_pool: a.next()?,
+                    buy_asset_pool_spl: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    token_program: a.next()?,
+                },
+                remaining: a.remaining(),
+            },
+            consts::CMD_UPDATE_USER_ASSET_CONFIG => ApricotInstruction::UpdateUserAssetConfig {
+                param: d.param()?,
+                accounts: UpdateUserAssetConfigAccounts {
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                },
+            },
+            consts::CMD_LP_CREATE => ApricotInstruction::LpCreate {
+                param: d.param()?,
+                accounts: decode_lp_op_accounts(&mut a)?,
+                remaining: a.remaining(),
+            },
+            consts::CMD_LP_REDEEM => ApricotInstruction::LpRedeem {
+                param: d.param()?,
+                accounts: decode_lp_op_accounts(&mut a)?,
+                remaining: a.remaining(),
+            },
+            consts::CMD_LP_OP_CHECK => ApricotInstruction::LpOpCheck {
+                param: d.param()?,
+                accounts: LpOpCheckAccounts {
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    left_asset_pool: a.next()?,
+                    left_asset_pool_spl: a.next()?,
+                    right_asset_pool: a.next()?,
+                    right_asset_pool_spl: a.next()?,
+                    lp_asset_pool: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    instructions_sysvar: a.next()?,
+                },
+            },
+            consts::CMD_LP_OP_ENDCHECK => ApricotInstruction::LpOpEndcheck {
+                accounts: LpOpEndcheckAccounts {
+                    pool_summaries: a.next()?,
+                },
+            },
+            consts::CMD_LP_STAKE | consts::CMD_LP_UNSTAKE => {
+                let param = d.param()?;
+                let accounts = LpStakeAccounts {
+                    pool_summaries: a.next()?,
+                    lp_asset_pool_spl: a.next()?,
+                    base_pda: a.next()?,
+                };
+                let remaining = a.remaining();
+                if *cmd == consts::CMD_LP_STAKE {
+                    ApricotInstruction::LpStake {
+                        param,
+                        accounts,
+                        remaining,
+                    }
+                } else {
+                    ApricotInstruction::LpUnstake {
+                        param,
+                        accounts,
+                        remaining,
+                    }
+                }
+            }
+            consts::CMD_LP_STAKE_SECOND => ApricotInstruction::LpStakeSecond {
+                accounts: LpStakeSecondAccounts {
+                    signer: a.next()?,
+                    lp_asset_pool: a.next()?,
+                    lp_asset_pool_spl: a.next()?,
+                    stake_table: a.next()?,
+                },
+                remaining: a.remaining(),
+            },
+            consts::CMD_LP_UNSTAKE_SECOND => ApricotInstruction::LpUnstakeSecond {
+                param: d.param()?,
+                accounts: LpUnstakeSecondAccounts {
+                    unstake_identity: a.next()?,
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    lp_asset_pool: a.next()?,
+                    lp_asset_pool_spl: a.next()?,
+                    stake_table: a.next()?,
+                },
+                remaining: a.remaining(),
+            },
+            consts::CMD_CLAIM_APT_LM_REWARD => ApricotInstruction::ClaimAptLmReward {
+                accounts: ClaimAptLmRewardAccounts {
+                    base_pda: a.next()?,
+                    user_wallet: a.next()?,
+                    user_info: a.next()?,
+                    user_apt_spl: a.next()?,
+                    lm_apt_vault: a.next()?,
+                    pool_summaries: a.next()?,
+                    price_summaries: a.next()?,
+                    token_program: a.next()?,
+                },
+            },
+            consts::CMD_MAKE_LM_REWARD_AVAILABLE => {
+                let user_wallet = a.next()?;
+                a.next()?; // user_wallet again, not as signer
+                ApricotInstruction::MakeLmRewardClaimable {
+                    accounts: MakeLmRewardClaimableAccounts {
+                        user_wallet,
+                        user_info: a.next()?,
+                        pool_summaries: a.next()?,
+                    },
+                }
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        d.finish()?;
+        Ok(decoded)
+    }
+
+    pub fn cmd(&self) -> u8 {
+        match self {
+            ApricotInstruction::RefreshUser { .. } => consts::CMD_REFRESH_USER,
+            ApricotInstruction::AddUserAndDeposit { .. } => consts::CMD_ADD_USER_AND_DEPOSIT,
+            ApricotInstruction::Deposit { .. } => consts::CMD_DEPOSIT,
+            ApricotInstruction::Withdraw { .. } => consts::CMD_WITHDRAW,
+            ApricotInstruction::WithdrawAndRemoveUser { .. } => {
+                consts::CMD_WITHDRAW_AND_REMOVE_USER
+            }
+            ApricotInstruction::Borrow { .. } => consts::CMD_BORROW,
+            ApricotInstruction::Repay { .. } => consts::CMD_REPAY,
+            ApricotInstruction::ExternLiquidate { .. } => consts::CMD_EXTERN_LIQUIDATE,
+            ApricotInstruction::UpdateUserConfig { .. } => consts::CMD_UPDATE_USER_CONFIG,
+            ApricotInstruction::MarginSwap { .. } => consts::CMD_MARGIN_SWAP,
+            ApricotInstruction::UpdateUserAssetConfig { .. } => {
+                consts::CMD_UPDATE_USER_ASSET_CONFIG
+            }
+            ApricotInstruction::LpCreate { .. } => consts::CMD_LP_CREATE,
+            ApricotInstruction::LpRedeem { .. } => consts::CMD_LP_REDEEM,
+            ApricotInstruction::LpOpCheck { .. } => consts::CMD_LP_OP_CHECK,
+            ApricotInstruction::LpOpEndcheck { .. } => consts::CMD_LP_OP_ENDCHECK,
+            ApricotInstruction::LpStake { .. } => consts::CMD_LP_STAKE,
+            ApricotInstruction::LpUnstake { .. } => consts::CMD_LP_UNSTAKE,
+            ApricotInstruction::LpStakeSecond { .. } => consts::CMD_LP_STAKE_SECOND,
+            ApricotInstruction::LpUnstakeSecond { .. } => consts::CMD_LP_UNSTAKE_SECOND,
+            ApricotInstruction::ClaimAptLmReward { .. } => consts::CMD_CLAIM_APT_LM_REWARD,
+            ApricotInstruction::MakeLmRewardClaimable { .. } => {
+                consts::CMD_MAKE_LM_REWARD_AVAILABLE
+            }
+        }
+    }
+}
+
+fn decode_lp_op_accounts(a: &mut AccountReader) -> Result<LpOpAccounts, ProgramError> {
+    Ok(LpOpAccounts {
+        user_wallet: a.next()?,
+        user_info: a.next()?,
+        base_pda: a.next()?,
+        left_asset_pool: a.next()?,
+        left_asset_pool_spl: a.next()?,
+        right_asset_pool: a.next()?,
+        right_asset_pool_spl: a.next()?,
+        lp_asset_pool: a.next()?,
+        lp_asset_pool_spl: a.next()?,
+        pool_summaries: a.next()?,
+        price_summaries: a.next()?,
+        token_program: a.next()?,
+    })
+}
+
+#[cfg(test)]
+pub mod decoder_test {
+    use super::*;
+    use crate::instructions;
+
+    #[test]
+    fn test_decode_builders() {
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+
+        let ix = instructions::deposit(&wallet, &spl, 1_000_000, 3);
+        match ApricotInstruction::from_instruction(&ix).unwrap() {
+            ApricotInstruction::Deposit { param, accounts } => {
+                assert_eq!({ param.amount }, 1_000_000);
+                assert_eq!(param.pool_id, 3);
+                assert_eq!(accounts.user_wallet, wallet);
+                assert_eq!(accounts.user_spl, spl);
+                assert_eq!(accounts.asset_pool, consts::get_asset_pool_k(3));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let ix = instructions::withdraw(&wallet, &spl, true, 5, 4, 7);
+        match ApricotInstruction::from_instruction(&ix).unwrap() {
+            ApricotInstruction::WithdrawAndRemoveUser { accounts, .. } => {
+                assert_eq!(accounts.users_page, Some(consts::get_users_page_k(7)));
+                assert_eq!(accounts.token_program, spl_token::ID);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let config = AssistConfig::custom(0.95, 0.8);
+        let ix = instructions::update_user_config(&wallet, &config).unwrap();
+        let decoded = ApricotInstruction::from_instruction(&ix).unwrap();
+        assert_eq!(decoded.cmd(), consts::CMD_UPDATE_USER_CONFIG);
+        match decoded {
+            ApricotInstruction::UpdateUserConfig { config: c, .. } => assert_eq!(c, config),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_bad_input() {
+        let wallet = Pubkey::new_unique();
+        let mut ix = instructions::refresh_user(&wallet);
+
+        ix.data = vec![];
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        ix.data = vec![0xff];
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::InvalidInstructionData)
+        );
+
+        let mut ix = instructions::borrow(&wallet, &wallet, 10, 1);
+        ix.data.pop();
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        let mut ix = instructions::borrow(&wallet, &wallet, 10, 1);
+        ix.data.push(0);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        let mut ix = instructions::borrow(&wallet, &wallet, 10, 1);
+        ix.accounts.truncate(3);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        let mut ix = instructions::borrow(&wallet, &wallet, 10, 1);
+        ix.program_id = Pubkey::new_unique();
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::IncorrectProgramId)
+        );
+
+        let keys = vec![consts::program::ID, wallet];
+        let compiled = CompiledInstruction::new_from_raw_parts(
+            0,
+            vec![consts::CMD_REFRESH_USER],
+            vec![1, 9, 1],
+        );
+        assert_eq!(
+            ApricotInstruction::from_compiled(&keys, &compiled),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+
+    #[test]
+    fn test_decode_with_env() {
+        let env = ApricotEnv::alpha();
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+
+        let ix = instructions::deposit_with_env(&env, &wallet, &spl, 10, 3);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        match ApricotInstruction::from_instruction_with_env(&env, &ix).unwrap() {
+            ApricotInstruction::Deposit { accounts, .. } => {
+                assert_eq!(accounts.user_info, env.user_info_k(&wallet));
+                assert_eq!(accounts.asset_pool, env.asset_pool_k(3));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // a message with one alpha and one mainnet refresh_user
+        let keys = vec![
+            env.program_id,
+            consts::program::ID,
+            wallet,
+            env.user_info_k(&wallet),
+            env.pool_summaries,
+        ];
+        let refresh = |program_index: u8| {
+            let data = vec![consts::CMD_REFRESH_USER];
+            CompiledInstruction::new_from_raw_parts(program_index, data, vec![2, 3, 4])
+        };
+        let instructions = vec![refresh(0), refresh(1)];
+        let decoded =
+            ApricotInstruction::from_compiled_message_with_env(&env, &keys, &instructions);
+        assert_eq!(decoded.len(), 1);
+        match &decoded[0] {
+            Ok(ApricotInstruction::RefreshUser { accounts }) => {
+                assert_eq!(accounts.user_wallet, wallet);
+                assert_eq!(accounts.pool_summaries, env.pool_summaries);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            ApricotInstruction::from_compiled_message(&keys, &instructions).len(),
+            1
+        );
+    }
+}
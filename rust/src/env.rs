@@ -0,0 +1,134 @@
+use solana_program::pubkey::Pubkey;
+
+use crate::consts;
+
+/**
+ * Root accounts of an Apricot deployment. User infos, asset pools, users pages and stake tables
+ * are all derived from the program id and base_pda, see the *_k methods. mainnet() is the
+ * default everywhere, use alpha() or new() to point the *_with_env builders and the accounts
+ * resolvers at another deployment, e.g. one loaded into a local validator.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ApricotEnv {
+    pub program_id: Pubkey,
+    pub base_pda: Pubkey,
+    pub price_pda: Pubkey,
+    pub user_stats: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub lm_apt_vault: Pubkey,
+}
+
+impl ApricotEnv {
+    pub fn mainnet() -> Self {
+        ApricotEnv {
+            program_id: consts::program::ID,
+            base_pda: consts::base_pda::ID,
+            price_pda: consts::price_pda::ID,
+            user_stats: consts::user_stats::ID,
+            pool_summaries: consts::pool_summaries::ID,
+            price_summaries: consts::price_summaries::ID,
+            lm_apt_vault: consts::lm_apt_vault::ID,
+        }
+    }
+
+    // same derivation as AppConfig/AddressLookup in sdk-ts
+    pub fn new(program_id: &Pubkey, admin: &Pubkey, lm_apt_vault: &Pubkey) -> Self {
+        let (base_pda, _) = Pubkey::find_program_address(&[consts::BASE_PDA_SEED], program_id);
+        let (price_pda, _) = Pubkey::find_program_address(&[consts::PRICE_PDA_SEED], program_id);
+        ApricotEnv {
+            program_id: *program_id,
+            base_pda,
+            price_pda,
+            user_stats: Pubkey::create_with_seed(admin, consts::USER_PAGES_STATS_SEED, program_id)
+                .unwrap(),
+            pool_summaries: Pubkey::create_with_seed(
+                admin,
+                consts::POOL_SUMMARIES_SEED,
+                program_id,
+            )
+            .unwrap(),
+            price_summaries: Pubkey::create_with_seed(
+                &base_pda,
+                consts::PRICE_SUMMARIES_SEED,
+                program_id,
+            )
+            .unwrap(),
+            lm_apt_vault: *lm_apt_vault,
+        }
+    }
+
+    pub fn alpha() -> Self {
+        Self::new(
+            &consts::alpha::program::ID,
+            &consts::alpha::admin::ID,
+            &consts::alpha::lm_apt_vault::ID,
+        )
+    }
+
+    pub fn user_info_k(&self, user_wallet: &Pubkey) -> Pubkey {
+        Pubkey::create_with_seed(user_wallet, consts::USER_INFO_SEED, &self.program_id).unwrap()
+    }
+
+    pub fn users_page_k(&self, page_id: u16) -> Pubkey {
+        let seed = format!("UsersPage_{}", page_id);
+        Pubkey::create_with_seed(&self.base_pda, &seed, &self.program_id).unwrap()
+    }
+
+    pub fn asset_pool_k(&self, pool_id: u8) -> Pubkey {
+        let mut mint_seed_buffer = [0_u8; 8];
+        let pool_seed_str = consts::pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+        Pubkey::create_with_seed(&self.base_pda, pool_seed_str, &self.program_id).unwrap()
+    }
+
+    pub fn asset_pool_spl_k(&self, token_program_id: &Pubkey, pool_id: u8) -> Pubkey {
+        let mut mint_seed_buffer = [0_u8; 8];
+        let pool_seed_str = consts::pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+        Pubkey::create_with_seed(&self.base_pda, pool_seed_str, token_program_id).unwrap()
+    }
+
+    // only LP pools that need second stake have a stake table
+    pub fn asset_pool_stake_table_k(&self, pool_id: u8) -> Pubkey {
+        let mut stake_seed_buffer = [0_u8; 8];
+        let stake_seed_str =
+            consts::pool_id_to_stake_table_seed_str(pool_id, &mut stake_seed_buffer);
+        Pubkey::create_with_seed(&self.base_pda, stake_seed_str, &self.program_id).unwrap()
+    }
+}
+
+impl Default for ApricotEnv {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+pub mod env_test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_new_derives_mainnet() {
+        let env = ApricotEnv::new(
+            &consts::program::ID,
+            &consts::admin::ID,
+            &consts::lm_apt_vault::ID,
+        );
+        assert_eq!(env, ApricotEnv::mainnet());
+        assert_eq!(
+            Pubkey::find_program_address(&[consts::BASE_PDA_SEED], &consts::program::ID).1,
+            consts::base_pda::BUMP
+        );
+    }
+
+    #[test]
+    fn test_alpha() {
+        let env = ApricotEnv::alpha();
+        assert_eq!(env.program_id, consts::alpha::program::ID);
+        assert_eq!(
+            env.base_pda,
+            Pubkey::from_str("GipxmFXdiJaSevu6StymY2aphKVxgYmAmf2dT3fTEASc").unwrap()
+        );
+        assert_ne!(env.asset_pool_k(0), consts::get_asset_pool_k(0));
+    }
+}
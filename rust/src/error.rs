@@ -0,0 +1,235 @@
+use crate::consts;
+use solana_program::{
+    decode_error::DecodeError, instruction::InstructionError, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+// the ranges consts.rs groups the ERR_* codes into, code >> 12
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ErrorCategory {
+    Account,  // 0x1000, wrong account supplied
+    Data,     // 0x2000, wrong instruction data
+    Internal, // 0x3000, client logic error
+    User,     // 0x4000, rejected by lending logic, e.g. not enough borrow power
+}
+
+macro_rules! apricot_errors {
+    ($($variant:ident = $code:ident => $message:expr,)*) => {
+        /**
+         * The Apricot program's custom error codes (consts::ERR_*) with a human readable message,
+         * the Rust counterpart of Errors.exceptionToString in sdk-ts.
+        */
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+        #[repr(u32)]
+        pub enum ApricotError {
+            $($variant = consts::$code,)*
+        }
+
+        impl ApricotError {
+            pub fn from_code(code: u32) -> Option<Self> {
+                match code {
+                    $(consts::$code => Some(ApricotError::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub fn message(self) -> &'static str {
+                match self {
+                    $(ApricotError::$variant => $message,)*
+                }
+            }
+        }
+    };
+}
+
+apricot_errors! {
+    IncorrectBasePda = ERR_INCORRECT_BASE_PDA => "incorrect base_pda account",
+    IncorrectUserPagesStats = ERR_INCORRECT_USER_PAGES_STATS => "incorrect user_pages_stats account",
+    IncorrectUsersPage = ERR_INCORRECT_USERS_PAGE => "incorrect users_page account",
+    IncorrectUserInfo = ERR_INCORRECT_USER_INFO => "incorrect user_info account",
+    IncorrectAssetPool = ERR_INCORRECT_ASSET_POOL => "incorrect asset_pool account",
+    IncorrectAssetPrice = ERR_INCORRECT_ASSET_PRICE => "incorrect asset_price account",
+    IncorrectAssetPoolSpl = ERR_INCORRECT_ASSET_POOL_SPL => "incorrect asset_pool_spl account",
+    IncorrectUserAssetInfo = ERR_INCORRECT_USER_ASSET_INFO => "incorrect user_asset_info account",
+    MissingActiveAccounts = ERR_MISSING_ACTIVE_ACCOUNTS => "missing active accounts",
+    IncorrectIntermediateSpl = ERR_INCORRECT_INTERMEDIATE_SPL => "incorrect intermediate_spl account",
+    IncorrectSellMarket = ERR_INCORRECT_SELL_MARKET => "incorrect sell market account",
+    IncorrectBuyMarket = ERR_INCORRECT_BUY_MARKET => "incorrect buy market account",
+    IncorrectSerumProgram = ERR_INCORRECT_SERUM_PROGRAM => "incorrect serum_program account",
+    IncorrectAdmin = ERR_INCORRECT_ADMIN => "incorrect admin account",
+    IncorrectIntermediateSplOwner = ERR_INCORRECT_INTERMEDIATE_SPL_OWNER => "incorrect intermediate_spl owner",
+    IncorrectPoolList = ERR_INCORRECT_POOL_LIST => "incorrect pool list",
+    IncorrectPoolSummaries = ERR_INCORRECT_POOL_SUMMARIES => "incorrect pool_summaries account",
+    IncorrectPriceSummaries = ERR_INCORRECT_PRICE_SUMMARIES => "incorrect price_summaries account",
+    IncorrectPricePda = ERR_INCORRECT_PRICE_PDA => "incorrect price_pda account",
+    IncorrectTokenProgram = ERR_INCORRECT_TOKEN_PROGRAM => "incorrect token program",
+    IncorrectAssetPoolAtokenMint = ERR_INCORRECT_ASSET_POOL_ATOKEN_MINT => "incorrect asset pool atoken mint",
+    IncorrectInstructionsSysvar = ERR_INCORRECT_INSTRUCTIONS_SYSVAR => "incorrect instructions sysvar",
+
+    MissingPageId = ERR_MISSING_PAGE_ID => "missing page_id",
+    PageIdTooLarge = ERR_PAGE_ID_TOO_LARGE => "page_id too large",
+    MissingAmount = ERR_MISSING_AMOUNT => "missing amount",
+    MissingMintSeedStr = ERR_MISSING_MINT_SEED_STR => "missing seed_str",
+    MissingActiveMintSeedStr = ERR_MISSING_ACTIVE_MINT_SEED_STR => "missing active seed_str",
+    WrongDataSize = ERR_WRONG_DATA_SIZE => "wrong data size",
+
+    AccountAlreadyAdded = ERR_ACCOUNT_ALREADY_ADDED => "account already added, use deposit",
+    NoAvailableSlots = ERR_NO_AVAILABLE_SLOTS => "no available slots in the chosen users_page",
+    AccountNotAdded = ERR_ACCOUNT_NOT_ADDED => "account not added, use add_user_and_deposit",
+    WalletDidNotSign = ERR_WALLET_DID_NOT_SIGN => "wallet did not sign",
+    MaximumNumPoolsReached = ERR_MAXIMUM_NUM_POOLS_REACHED => "maximum number of pools reached",
+    UserHasNoSuchAsset = ERR_USER_HAS_NO_SUCH_ASSET => "user does not have this asset",
+    NeedAtLeastBuyOrSell = ERR_NEED_AT_LEAST_BUY_OR_SELL => "need at least a buy or a sell",
+    InsufficientFees = ERR_INSUFFICIENT_FEES => "insufficient fees",
+
+    DepositLessThanMinimum = ERR_DEPOSIT_LESS_THAN_MINIMUM => "deposit less than minimum",
+    InsufficientDeposit = ERR_INSUFFICIENT_DEPOSIT => "cannot withdraw more than the deposit",
+    PoolNoFreeFund = ERR_POOL_NO_FREE_FUND => "pool does not have enough free funds",
+    PleaseWithdrawAll = ERR_PLEASE_WITHDRAW_ALL => "deposit left would be less than minimum, withdraw all",
+    InsufficientBorrowPower = ERR_INSUFFICIENT_BORROW_POWER => "insufficient borrow power",
+    CannotRepayMoreThanDebt = ERR_CANNOT_REPAY_MORE_THAN_DEBT => "cannot repay more than the debt",
+    WithdrawalBelowMinCollateralRatio = ERR_WITHDRAWL_BELOW_MIN_COLLATERAL_RATIO => "withdrawal would break the collateral ratio requirement",
+    LiquidationNotReached = ERR_LIQUIDATION_NOT_REACHED => "liquidation threshold not reached",
+    LiquidatorAskedTooMuchCollateral = ERR_LIQUIDATOR_ASKED_TOO_MUCH_COLLATERAL => "liquidator asked for too much collateral",
+    AccountNotEnoughDebtForLiquidation = ERR_ACCOUNT_NOT_ENOUGH_DEBT_FOR_LIQUIDATION => "liquidator repaid more than the debt",
+    AccountNotEnoughCollateralForLiquidation = ERR_ACCOUNT_NOT_ENOUGH_COLLATERAL_FOR_LIQUIDATION => "liquidator asked for more collateral than deposited",
+    ExceedsLiquidationLimit = ERR_EXCEEDS_LIQUIDATION_LIMIT => "liquidation exceeds the limit, liquidate less",
+    SelfDeleverageFactorTooLarge = ERR_SELF_DELEVERAGE_FACTOR_TOO_LARGE => "self deleverage factor too large",
+    PostDeleverageFactorTooLarge = ERR_POST_DELEVERAGE_FACTOR_TOO_LARGE => "post deleverage factor too large",
+    Deprecated = ERR_DEPRECATED_XXXXXXXXXXXXXX => "deprecated error",
+    SelfDeleverageFactorNotReached = ERR_SELF_DELEVERAGE_FACTOR_NOT_REACHED => "self deleverage factor not reached",
+    SelfDeleverageTargetExceeded = ERR_SELF_DELEVERAGE_TARGET_EXCEEDED => "self deleverage target exceeded",
+    SelfDeleverageHighSlippage = ERR_SELF_DELEVERAGE_HIGH_SLIPPAGE => "self deleverage slippage too high",
+    MaxNumAssetsReached = ERR_MAX_NUM_ASSETS_REACHED => "maximum number of assets reached",
+    SwapBoughtLessThanMin = ERR_SWAP_BOUGHT_LESS_THAN_MIN => "swap bought less than min",
+    AssetNotUsedAsCollateral = ERR_ASSET_NOT_USED_AS_COLLATERAL => "asset not used as collateral",
+    InsufficientWalletBalance = ERR_INSUFFICIENT_WALLET_BALANCE => "insufficient wallet balance",
+    SwapLpGotLessThanMin = ERR_SWAP_LP_GOT_LESS_THAN_MIN => "lp swap got less than min",
+    AssistAlreadyExecuted = ERR_ASSIST_ALREADY_EXECUTED => "assist already executed",
+    AssistNotEnabled = ERR_ASSIST_NOT_ENABLED => "assist not enabled",
+    AssistCheckMustBeFirst = ERR_ASSIST_CHECK_MUST_BE_FIRST => "assist check must be the first instruction",
+    AssistActionNotAllowed = ERR_ASSIST_ACTION_NOT_ALLOWED => "assist action not allowed",
+    AssistInconsistentAction = ERR_ASSIST_INCONSISTENT_ACTION => "assist action inconsistent",
+}
+
+impl ApricotError {
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn category(self) -> ErrorCategory {
+        match self.code() >> 12 {
+            1 => ErrorCategory::Account,
+            2 => ErrorCategory::Data,
+            3 => ErrorCategory::Internal,
+            _ => ErrorCategory::User,
+        }
+    }
+
+    // None for errors that aren't Apricot custom errors
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+
+    /**
+     * For the error of a sent or simulated transaction, e.g. the InstructionError in
+     * TransactionError::InstructionError(index, error).
+     */
+    pub fn from_instruction_error(error: &InstructionError) -> Option<Self> {
+        match error {
+            InstructionError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+
+    /**
+     * Finds the error in transaction logs, i.e. the
+     * "Program <program_id> failed: custom program error: 0x4004" line. Only failures of program_id
+     * are considered, codes of other programs (e.g. the token program) overlap with Apricot's.
+     */
+    pub fn from_logs<S: AsRef<str>>(logs: &[S], program_id: &Pubkey) -> Option<Self> {
+        let prefix = format!("Program {} failed: custom program error: 0x", program_id);
+        logs.iter().rev().find_map(|log| {
+            let code = log.as_ref().strip_prefix(&prefix)?;
+            Self::from_code(u32::from_str_radix(code.trim(), 16).ok()?)
+        })
+    }
+}
+
+impl Display for ApricotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{} (0x{:x})", self.message(), self.code())
+    }
+}
+
+impl std::error::Error for ApricotError {}
+
+impl From<ApricotError> for ProgramError {
+    fn from(error: ApricotError) -> Self {
+        ProgramError::Custom(error.code())
+    }
+}
+
+impl<T> DecodeError<T> for ApricotError {
+    fn type_of() -> &'static str {
+        "ApricotError"
+    }
+}
+
+#[cfg(test)]
+pub mod error_test {
+    use super::*;
+
+    #[test]
+    fn test_codes() {
+        let error = ApricotError::from_code(0x4004).unwrap();
+        assert_eq!(error, ApricotError::InsufficientBorrowPower);
+        assert_eq!(error.code(), consts::ERR_INSUFFICIENT_BORROW_POWER);
+        assert_eq!(error.category(), ErrorCategory::User);
+        assert_eq!(error.to_string(), "insufficient borrow power (0x4004)");
+        assert_eq!(ApricotError::WrongDataSize.category(), ErrorCategory::Data);
+        assert_eq!(ApricotError::from_code(0x4022), None);
+
+        assert_eq!(
+            ApricotError::from_program_error(&ProgramError::Custom(0x1004)),
+            Some(ApricotError::IncorrectAssetPool)
+        );
+        assert_eq!(
+            ApricotError::from_program_error(&ProgramError::InvalidArgument),
+            None
+        );
+        assert_eq!(
+            ProgramError::from(ApricotError::AssistNotEnabled),
+            ProgramError::Custom(consts::ERR_ASSIST_NOT_ENABLED)
+        );
+        assert_eq!(
+            ApricotError::from_instruction_error(&InstructionError::Custom(0x4006)),
+            Some(ApricotError::WithdrawalBelowMinCollateralRatio)
+        );
+    }
+
+    #[test]
+    fn test_from_logs() {
+        let program_id = consts::program::ID;
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!(
+                "Program {} failed: custom program error: 0x1",
+                spl_token::ID
+            ),
+            format!(
+                "Program {} failed: custom program error: 0x4002",
+                program_id
+            ),
+        ];
+        assert_eq!(
+            ApricotError::from_logs(&logs, &program_id),
+            Some(ApricotError::PoolNoFreeFund)
+        );
+        assert_eq!(ApricotError::from_logs(&logs[..2], &program_id), None);
+    }
+}
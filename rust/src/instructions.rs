@@ -1,52 +1,138 @@
+// _full builders take every account of the instruction, in the order the program expects them.
+// The other builders derive accounts for mainnet, their _with_env variants for any ApricotEnv and
+// _with_registry variants for the env and pools of a PoolRegistry.
+#![allow(clippy::too_many_arguments)]
+
 use solana_program::{
     instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::{Pack, Sealed},
     pubkey::Pubkey,
-    system_program,
+    sysvar,
 };
 use spl_token;
+use std::fmt::{Display, Formatter, Result as FormatResult};
 
+use crate::accounts::{
+    AddUserAndDepositAccounts, BorrowAccounts, DepositAccounts, ExternLiquidateAccounts,
+    LpOpAccounts, LpOpCheckAccounts, MarginSwapAccounts, RepayAccounts,
+    UpdateUserAssetConfigAccounts, UpdateUserConfigAccounts, WithdrawAccounts,
+};
 use crate::consts;
+use crate::env::ApricotEnv;
+use crate::layout::{LayoutReader, LayoutWriter};
+use crate::lp::{LpFarm, LpMarket, LpRoute, OrcaDoubleDipFarm, RaydiumFarm};
+use crate::registry::PoolRegistry;
+use crate::swap::SwapRoute;
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct UpdateUserAssetConfigParam {
     pub use_as_collateral: u8,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MarginSwapParam {
+    pub is_buy: u8,
+    pub sell_amount: u64,
+    // minimum amount to receive, contract fails with ERR_SWAP_BOUGHT_LESS_THAN_MIN otherwise
+    pub buy_amount: u64,
+    pub sell_pool_id: u8,
+    pub buy_pool_id: u8,
+    pub target_swap: u8,
+    pub is_swap_all_deposit: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpCreateParam {
+    pub left_amount: u64,
+    pub right_amount: u64,
+    // contract fails with ERR_SWAP_LP_GOT_LESS_THAN_MIN if less LP is minted
+    pub min_lp_amount: u64,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub target_swap: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpRedeemParam {
+    pub min_left_amount: u64,
+    pub min_right_amount: u64,
+    pub lp_amount: u64,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub target_swap: u8,
+}
+
+// same layout as LpCreateParam/LpRedeemParam, followed by is_create
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpOpCheckParam {
+    pub left_amount: u64,
+    pub right_amount: u64,
+    pub lp_amount: u64,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub target_swap: u8,
+    pub is_create: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpStakeParam {
+    // 0 when staking, the contract stakes all unstaked LP of the pool
+    pub amount: u64,
+    pub target_swap: u8,
+    pub lp_pool_id: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LpUnstakeSecondParam {
+    pub amount: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UpdateUserConfigParam {
+    pub assist_mode: u8,
+    pub self_deleverage_factor: f64,
+    pub post_deleverage_factor: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct AddUserAndDepositParam {
     pub page_id: u16,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DepositParam {
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct WithdrawParam {
     pub withdraw_all: u8,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct BorrowParam {
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct RepayParam {
     pub repay_all: u8,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ExternLiquidateParam {
     // how much collateral liquidator wants to receive
     pub min_collateral_amount: u64,
@@ -56,10 +142,203 @@ pub struct ExternLiquidateParam {
     pub borrowed_pool_id: u8,
 }
 
-#[inline(always)]
-pub fn mut_cast<T>(data: &mut [u8]) -> &mut T {
-    assert!(data.len() >= std::mem::size_of::<T>());
-    return unsafe { std::mem::transmute(data.as_ptr()) };
+// Params are sent little-endian, field by field in the order listed, without padding. This is
+// the layout of the contract's #[repr(packed)] structs and of sdk-ts TransactionBuilder.
+macro_rules! impl_param_pack {
+    ($param:ident { $($field:ident: $ty:ident),* $(,)? }) => {
+        impl Sealed for $param {}
+
+        impl Pack for $param {
+            const LEN: usize = 0 $(+ std::mem::size_of::<$ty>())*;
+
+            fn pack_into_slice(&self, dst: &mut [u8]) {
+                let mut writer = LayoutWriter::new(dst);
+                $(writer.$ty(self.$field);)*
+            }
+
+            fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+                let mut reader = LayoutReader::new(src);
+                Ok($param {
+                    $($field: reader.$ty()?,)*
+                })
+            }
+        }
+    };
+}
+
+impl_param_pack!(UpdateUserAssetConfigParam {
+    use_as_collateral: u8,
+    pool_id: u8
+});
+impl_param_pack!(MarginSwapParam {
+    is_buy: u8,
+    sell_amount: u64,
+    buy_amount: u64,
+    sell_pool_id: u8,
+    buy_pool_id: u8,
+    target_swap: u8,
+    is_swap_all_deposit: u8,
+});
+impl_param_pack!(LpCreateParam {
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+    left_pool_id: u8,
+    right_pool_id: u8,
+    lp_pool_id: u8,
+    target_swap: u8,
+});
+impl_param_pack!(LpRedeemParam {
+    min_left_amount: u64,
+    min_right_amount: u64,
+    lp_amount: u64,
+    left_pool_id: u8,
+    right_pool_id: u8,
+    lp_pool_id: u8,
+    target_swap: u8,
+});
+impl_param_pack!(LpOpCheckParam {
+    left_amount: u64,
+    right_amount: u64,
+    lp_amount: u64,
+    left_pool_id: u8,
+    right_pool_id: u8,
+    lp_pool_id: u8,
+    target_swap: u8,
+    is_create: u8,
+});
+impl_param_pack!(LpStakeParam {
+    amount: u64,
+    target_swap: u8,
+    lp_pool_id: u8
+});
+impl_param_pack!(LpUnstakeSecondParam { amount: u64 });
+impl_param_pack!(UpdateUserConfigParam {
+    assist_mode: u8,
+    self_deleverage_factor: f64,
+    post_deleverage_factor: f64,
+});
+impl_param_pack!(AddUserAndDepositParam {
+    page_id: u16,
+    amount: u64,
+    pool_id: u8
+});
+impl_param_pack!(DepositParam {
+    amount: u64,
+    pool_id: u8
+});
+impl_param_pack!(WithdrawParam {
+    withdraw_all: u8,
+    amount: u64,
+    pool_id: u8
+});
+impl_param_pack!(BorrowParam {
+    amount: u64,
+    pool_id: u8
+});
+impl_param_pack!(RepayParam {
+    repay_all: u8,
+    amount: u64,
+    pool_id: u8
+});
+impl_param_pack!(ExternLiquidateParam {
+    min_collateral_amount: u64,
+    repaid_borrow_amount: u64,
+    collateral_pool_id: u8,
+    borrowed_pool_id: u8,
+});
+
+/**
+ * User's assist (automatic deleverage) setup, as sent with CMD_UPDATE_USER_CONFIG.
+ * Once the user's collateral ratio reaches self_deleverage_factor, the assist bot is allowed to sell
+ * collateral and repay debt until the ratio is back to post_deleverage_factor. The contract checks
+ * the factors (ERR_*_DELEVERAGE_FACTOR_TOO_LARGE), the client only checks the mode.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AssistConfig {
+    pub assist_mode: u8, // consts::ASSIST_MODE_*
+    pub self_deleverage_factor: f64,
+    pub post_deleverage_factor: f64,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AssistConfigError {
+    UnknownMode(u8),
+}
+
+impl Display for AssistConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            AssistConfigError::UnknownMode(mode) => write!(f, "unknown assist mode {}", mode),
+        }
+    }
+}
+
+impl std::error::Error for AssistConfigError {}
+
+impl From<AssistConfigError> for ProgramError {
+    fn from(_: AssistConfigError) -> Self {
+        ProgramError::InvalidInstructionData
+    }
+}
+
+impl AssistConfig {
+    pub fn disabled() -> Self {
+        AssistConfig {
+            assist_mode: consts::ASSIST_MODE_DISABLED,
+            self_deleverage_factor: 0.0,
+            post_deleverage_factor: 0.0,
+        }
+    }
+
+    // deleverage by selling any collateral
+    pub fn custom(self_deleverage_factor: f64, post_deleverage_factor: f64) -> Self {
+        AssistConfig {
+            assist_mode: consts::ASSIST_MODE_CUSTOM,
+            self_deleverage_factor,
+            post_deleverage_factor,
+        }
+    }
+
+    // deleverage by selling collateral for stable coins only
+    pub fn stable_only(self_deleverage_factor: f64, post_deleverage_factor: f64) -> Self {
+        AssistConfig {
+            assist_mode: consts::ASSIST_MODE_STABLE_ONLY,
+            self_deleverage_factor,
+            post_deleverage_factor,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.assist_mode != consts::ASSIST_MODE_DISABLED
+    }
+
+    pub fn validate(&self) -> Result<(), AssistConfigError> {
+        match self.assist_mode {
+            consts::ASSIST_MODE_DISABLED
+            | consts::ASSIST_MODE_CUSTOM
+            | consts::ASSIST_MODE_STABLE_ONLY => Ok(()),
+            mode => Err(AssistConfigError::UnknownMode(mode)),
+        }
+    }
+}
+
+impl From<UpdateUserConfigParam> for AssistConfig {
+    fn from(param: UpdateUserConfigParam) -> Self {
+        AssistConfig {
+            assist_mode: param.assist_mode,
+            self_deleverage_factor: param.self_deleverage_factor,
+            post_deleverage_factor: param.post_deleverage_factor,
+        }
+    }
+}
+
+// CMD byte followed by the packed param
+fn instruction_data<T: Pack>(cmd: u8, param: &T) -> Vec<u8> {
+    let mut buffer = vec![0; 1 + T::LEN];
+    buffer[0] = cmd;
+    param.pack_into_slice(&mut buffer[1..]);
+    buffer
 }
 
 pub fn deposit(
@@ -68,54 +347,47 @@ pub fn deposit(
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    deposit_full(
+    deposit_with_env(
+        &ApricotEnv::mainnet(),
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &spl_token::ID,
-        &consts::program::ID,
         amount,
         pool_id,
     )
 }
 
+pub fn deposit_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
+) -> Instruction {
+    let accounts = DepositAccounts::from_user_spl(user_wallet, user_spl, pool_id, env);
+    deposit_full(&accounts, &env.program_id, amount, pool_id)
+}
+
 pub fn deposit_full(
-    user_wallet: &Pubkey,     // user wallet account, needs to be signer
-    user_spl: &Pubkey,        // user's SPL token account
-    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,      // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey,  // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
-    price_summaries: &Pubkey, // consts::get_pool_summaries_k()
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
+    accounts: &DepositAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,        // consts::program::ID
 
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<DepositParam>();
-    let mut buffer = vec![0; data_size];
-
-    buffer[0] = consts::CMD_DEPOSIT;
-    let mut param = mut_cast::<DepositParam>(&mut buffer[1..]);
-    param.amount = amount;
-    param.pool_id = pool_id;
+    let param = DepositParam { amount, pool_id };
+    let buffer = instruction_data(consts::CMD_DEPOSIT, &param);
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new_readonly(*user_wallet, true),
-            AccountMeta::new(*user_spl, false),
-            AccountMeta::new(*user_info, false),
-            AccountMeta::new(*asset_pool, false),
-            AccountMeta::new(*asset_pool_spl, false),
-            AccountMeta::new(*pool_summaries, false),
-            AccountMeta::new(*price_summaries, false),
-            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_spl, false),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new(accounts.asset_pool, false),
+            AccountMeta::new(accounts.asset_pool_spl, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new(accounts.price_summaries, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
         ],
         data: buffer,
     }
@@ -128,71 +400,64 @@ pub fn add_user_and_deposit(
     pool_id: u8,
     page_id: u16,
 ) -> Instruction {
-    add_user_and_deposit_full(
+    add_user_and_deposit_with_env(
+        &ApricotEnv::mainnet(),
         user_wallet,
         user_spl,
-        &consts::get_user_pages_stats_k(),
-        &consts::get_users_page_k(page_id),
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &system_program::ID,
-        &spl_token::ID,
-        &consts::program::ID,
         amount,
         pool_id,
         page_id,
     )
 }
 
+pub fn add_user_and_deposit_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
+    page_id: u16,
+) -> Instruction {
+    let accounts =
+        AddUserAndDepositAccounts::from_user_spl(user_wallet, user_spl, pool_id, page_id, env);
+    add_user_and_deposit_full(&accounts, &env.program_id, amount, pool_id, page_id)
+}
+
 pub fn add_user_and_deposit_full(
-    user_wallet: &Pubkey,      // user wallet account, needs to be signer
-    user_spl: &Pubkey,         // user's SPL token account
-    user_pages_stats: &Pubkey, // consts::get_user_pages_stats_k()
-    users_page: &Pubkey,       // consts::get_users_page_k(page_id)
-    user_info: &Pubkey,        // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,       // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey,   // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey,   // consts::get_pool_summaries_k()
-    price_summaries: &Pubkey,  // consts::get_pool_summaries_k()
-    system_program: &Pubkey,
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
+    accounts: &AddUserAndDepositAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,                  // consts::program::ID
 
     amount: u64,
     pool_id: u8,
     page_id: u16,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<AddUserAndDepositParam>();
-    let mut buffer = vec![0; data_size];
-
-    buffer[0] = consts::CMD_ADD_USER_AND_DEPOSIT;
-    let mut param = mut_cast::<AddUserAndDepositParam>(&mut buffer[1..]);
-    param.amount = amount;
-    param.pool_id = pool_id;
-    param.page_id = page_id;
+    let param = AddUserAndDepositParam {
+        page_id,
+        amount,
+        pool_id,
+    };
+    let buffer = instruction_data(consts::CMD_ADD_USER_AND_DEPOSIT, &param);
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new(*user_wallet, true),
-            AccountMeta::new(*user_spl, false),
-            AccountMeta::new(*user_pages_stats, false),
-            AccountMeta::new(*users_page, false),
-            AccountMeta::new(*user_info, false),
-            AccountMeta::new(*asset_pool, false),
-            AccountMeta::new(*asset_pool_spl, false),
-            AccountMeta::new(*pool_summaries, false),
-            AccountMeta::new(*price_summaries, false),
-            AccountMeta::new_readonly(*system_program, false),
-            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_spl, false),
+            AccountMeta::new(accounts.user_pages_stats, false),
+            AccountMeta::new(accounts.users_page, false),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new(accounts.asset_pool, false),
+            AccountMeta::new(accounts.asset_pool_spl, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new(accounts.price_summaries, false),
+            AccountMeta::new_readonly(accounts.system_program, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
         ],
         data: buffer,
     }
 }
 
+// withdraw_all also removes the user from page_id (UserInfo.page_id)
 pub fn withdraw(
     user_wallet: &Pubkey, // user wallet account, needs to be signer
     user_spl: &Pubkey,    // user's SPL token account
@@ -201,17 +466,10 @@ pub fn withdraw(
     pool_id: u8,
     page_id: u16,
 ) -> Instruction {
-    withdraw_full(
+    withdraw_with_env(
+        &ApricotEnv::mainnet(),
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &consts::get_base_pda(),
-        &spl_token::ID,
-        &consts::program::ID,
         withdraw_all,
         amount,
         pool_id,
@@ -219,54 +477,64 @@ pub fn withdraw(
     )
 }
 
-pub fn withdraw_full(
-    user_wallet: &Pubkey,     // user wallet account, needs to be signer
-    user_spl: &Pubkey,        // user's SPL token account
-    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,      // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey,  // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
-    price_summaries: &Pubkey, // consts::get_price_summaries_k()
-    base_pda: &Pubkey,        // consts::get_base_pda()
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
-
+pub fn withdraw_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
     withdraw_all: bool,
     amount: u64,
     pool_id: u8,
     page_id: u16,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<WithdrawParam>();
-    let mut buffer = vec![0; data_size];
+    let mut accounts = WithdrawAccounts::from_user_spl(user_wallet, user_spl, pool_id, env);
+    if withdraw_all {
+        accounts = accounts.removing_user(page_id, env);
+    }
+    withdraw_full(&accounts, &env.program_id, withdraw_all, amount, pool_id)
+}
+
+// sent as CMD_WITHDRAW_AND_REMOVE_USER when accounts carry user_pages_stats and users_page,
+// see WithdrawAccounts::removing_user
+pub fn withdraw_full(
+    accounts: &WithdrawAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,         // consts::program::ID
 
-    buffer[0] = if withdraw_all {
+    withdraw_all: bool,
+    amount: u64,
+    pool_id: u8,
+) -> Instruction {
+    let cmd = if accounts.removes_user() {
         consts::CMD_WITHDRAW_AND_REMOVE_USER
     } else {
         consts::CMD_WITHDRAW
     };
-    let mut param = mut_cast::<WithdrawParam>(&mut buffer[1..]);
-    param.withdraw_all = if withdraw_all { 1 } else { 0 };
-    param.amount = amount;
-    param.pool_id = pool_id;
+    let param = WithdrawParam {
+        withdraw_all: if withdraw_all { 1 } else { 0 },
+        amount,
+        pool_id,
+    };
+    let buffer = instruction_data(cmd, &param);
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(*user_wallet, true),
-        AccountMeta::new(*user_spl, false),
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.user_wallet, true),
+        AccountMeta::new(accounts.user_spl, false),
     ];
-    if withdraw_all {
-        accounts.push(AccountMeta::new(consts::get_user_pages_stats_k(), false));
-        accounts.push(AccountMeta::new(consts::get_users_page_k(page_id), false));
-    }
-    accounts.push(AccountMeta::new(*user_info, false));
-    accounts.push(AccountMeta::new(*asset_pool, false));
-    accounts.push(AccountMeta::new(*asset_pool_spl, false));
-    accounts.push(AccountMeta::new(*pool_summaries, false));
-    accounts.push(AccountMeta::new_readonly(*price_summaries, false));
-    accounts.push(AccountMeta::new_readonly(*base_pda, false));
-    accounts.push(AccountMeta::new_readonly(*token_program, false));
+    if let (Some(user_pages_stats), Some(users_page)) =
+        (accounts.user_pages_stats, accounts.users_page)
+    {
+        metas.push(AccountMeta::new(user_pages_stats, false));
+        metas.push(AccountMeta::new(users_page, false));
+    }
+    metas.push(AccountMeta::new(accounts.user_info, false));
+    metas.push(AccountMeta::new(accounts.asset_pool, false));
+    metas.push(AccountMeta::new(accounts.asset_pool_spl, false));
+    metas.push(AccountMeta::new(accounts.pool_summaries, false));
+    metas.push(AccountMeta::new_readonly(accounts.price_summaries, false));
+    metas.push(AccountMeta::new_readonly(accounts.base_pda, false));
+    metas.push(AccountMeta::new_readonly(accounts.token_program, false));
     Instruction {
         program_id: *program_id,
-        accounts: accounts,
+        accounts: metas,
         data: buffer,
     }
 }
@@ -277,57 +545,48 @@ pub fn borrow(
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    borrow_full(
+    borrow_with_env(
+        &ApricotEnv::mainnet(),
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &consts::get_base_pda(),
-        &spl_token::ID,
-        &consts::program::ID,
         amount,
         pool_id,
     )
 }
 
+pub fn borrow_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
+) -> Instruction {
+    let accounts = BorrowAccounts::from_user_spl(user_wallet, user_spl, pool_id, env);
+    borrow_full(&accounts, &env.program_id, amount, pool_id)
+}
+
 pub fn borrow_full(
-    user_wallet: &Pubkey,     // user wallet account, needs to be signer
-    user_spl: &Pubkey,        // user's SPL token account
-    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,      // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey,  // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
-    price_summaries: &Pubkey, // consts::get_price_summaries_k()
-    base_pda: &Pubkey,        // consts::get_base_pda()
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
+    accounts: &BorrowAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,       // consts::program::ID
 
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<BorrowParam>();
-    let mut buffer = vec![0; data_size];
-
-    buffer[0] = consts::CMD_BORROW;
-    let mut param = mut_cast::<BorrowParam>(&mut buffer[1..]);
-    param.amount = amount;
-    param.pool_id = pool_id;
+    let param = BorrowParam { amount, pool_id };
+    let buffer = instruction_data(consts::CMD_BORROW, &param);
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new_readonly(*user_wallet, true),
-            AccountMeta::new(*user_spl, false),
-            AccountMeta::new(*user_info, false),
-            AccountMeta::new(*asset_pool, false),
-            AccountMeta::new(*asset_pool_spl, false),
-            AccountMeta::new(*pool_summaries, false),
-            AccountMeta::new_readonly(*price_summaries, false),
-            AccountMeta::new_readonly(*base_pda, false),
-            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_spl, false),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new(accounts.asset_pool, false),
+            AccountMeta::new(accounts.asset_pool_spl, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new_readonly(accounts.price_summaries, false),
+            AccountMeta::new_readonly(accounts.base_pda, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
         ],
         data: buffer,
     }
@@ -340,54 +599,53 @@ pub fn repay(
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    repay_full(
+    repay_with_env(
+        &ApricotEnv::mainnet(),
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &spl_token::ID,
-        &consts::program::ID,
         repay_all,
         amount,
         pool_id,
     )
 }
 
+pub fn repay_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    repay_all: bool,
+    amount: u64,
+    pool_id: u8,
+) -> Instruction {
+    let accounts = RepayAccounts::from_user_spl(user_wallet, user_spl, pool_id, env);
+    repay_full(&accounts, &env.program_id, repay_all, amount, pool_id)
+}
+
 pub fn repay_full(
-    user_wallet: &Pubkey,    // user wallet account, needs to be signer
-    user_spl: &Pubkey,       // user's SPL token account
-    user_info: &Pubkey,      // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,     // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey, // consts::get_pool_summaries_k()
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
+    accounts: &RepayAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,      // consts::program::ID
 
     repay_all: bool,
     amount: u64,
     pool_id: u8,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<RepayParam>();
-    let mut buffer = vec![0; data_size];
-
-    buffer[0] = consts::CMD_REPAY;
-    let mut param = mut_cast::<RepayParam>(&mut buffer[1..]);
-    param.repay_all = if repay_all { 1 } else { 0 };
-    param.amount = amount;
-    param.pool_id = pool_id;
+    let param = RepayParam {
+        repay_all: if repay_all { 1 } else { 0 },
+        amount,
+        pool_id,
+    };
+    let buffer = instruction_data(consts::CMD_REPAY, &param);
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new_readonly(*user_wallet, true),
-            AccountMeta::new(*user_spl, false),
-            AccountMeta::new(*user_info, false),
-            AccountMeta::new(*asset_pool, false),
-            AccountMeta::new(*asset_pool_spl, false),
-            AccountMeta::new(*pool_summaries, false),
-            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_spl, false),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new(accounts.asset_pool, false),
+            AccountMeta::new(accounts.asset_pool_spl, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
         ],
         data: buffer,
     }
@@ -395,17 +653,24 @@ pub fn repay_full(
 
 pub fn refresh_user(user_wallet: &Pubkey, // user wallet account
 ) -> Instruction {
-    let data_size = 1 + 0; // no param
+    refresh_user_with_env(&ApricotEnv::mainnet(), user_wallet)
+}
+
+pub fn refresh_user_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account
+) -> Instruction {
+    let data_size = 1; // no param
     let mut buffer = vec![0; data_size];
 
-    let program_id = consts::program::ID;
-    let user_info = consts::get_user_info_k(user_wallet);
-    let pool_summaries = consts::get_pool_summaries_k();
+    let program_id = env.program_id;
+    let user_info = env.user_info_k(user_wallet);
+    let pool_summaries = env.pool_summaries;
 
     buffer[0] = consts::CMD_REFRESH_USER;
 
     Instruction {
-        program_id: program_id,
+        program_id,
         accounts: vec![
             AccountMeta::new_readonly(*user_wallet, false),
             AccountMeta::new(user_info, false),
@@ -425,20 +690,12 @@ pub fn extern_liquidate(
     borrowed_pool_id: u8,
     collateral_pool_id: u8,
 ) -> Instruction {
-    extern_liquidate_full(
+    extern_liquidate_with_env(
+        &ApricotEnv::mainnet(),
         liquidated_wallet,
         liquidator_wallet,
-        &consts::get_user_info_k(liquidated_wallet),
-        &consts::get_base_pda(),
         liquidator_collateral_spl,
         liquidator_borrowed_spl,
-        &consts::get_asset_pool_k(collateral_pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, collateral_pool_id),
-        &consts::get_asset_pool_k(borrowed_pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, borrowed_pool_id),
-        &consts::get_pool_summaries_k(),
-        &spl_token::ID,
-        &consts::program::ID,
         repaid_borrow_amount,
         min_collateral_amount,
         borrowed_pool_id,
@@ -446,74 +703,966 @@ pub fn extern_liquidate(
     )
 }
 
-// liquidator will help repay "borrowed" and in exchange receive "collateral" asset at 1% discount
-pub fn extern_liquidate_full(
+pub fn extern_liquidate_with_env(
+    env: &ApricotEnv,
     liquidated_wallet: &Pubkey, // wallet key for account to be liquidated
     liquidator_wallet: &Pubkey, // wallet key for liquidator, signer
-
-    user_info: &Pubkey, // consts::get_user_info_k(liquidated_wallet)
-    base_pda: &Pubkey,  // consts::get_base_pda()
-
     liquidator_collateral_spl: &Pubkey, // liquidator's SPL token account for collateral asset
-    liquidator_borrowed_spl: &Pubkey,   // liquidator's SPL token account for repaid asset
-
-    collateral_asset_pool: &Pubkey, // consts::get_asset_pool_k(collateral_pool_id)
-    collateral_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, collateral_pool_id)
-
-    borrowed_asset_pool: &Pubkey, // consts::get_asset_pool_k(borrowed_pool_id)
-    borrowed_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, borrowed_pool_id)
+    liquidator_borrowed_spl: &Pubkey, // liquidator's SPL token account for repaid asset
+    repaid_borrow_amount: u64,
+    min_collateral_amount: u64,
+    borrowed_pool_id: u8,
+    collateral_pool_id: u8,
+) -> Instruction {
+    let accounts = ExternLiquidateAccounts::from_user_spl(
+        liquidated_wallet,
+        liquidator_wallet,
+        liquidator_collateral_spl,
+        liquidator_borrowed_spl,
+        collateral_pool_id,
+        borrowed_pool_id,
+        env,
+    );
+    extern_liquidate_full(
+        &accounts,
+        &env.program_id,
+        repaid_borrow_amount,
+        min_collateral_amount,
+        borrowed_pool_id,
+        collateral_pool_id,
+    )
+}
 
-    pool_summaries: &Pubkey, // consts::get_pool_summaries_k()
-    token_program: &Pubkey,
-    program_id: &Pubkey, // consts::program::ID
+// liquidator will help repay "borrowed" and in exchange receive "collateral" asset at a discount,
+// see PoolConfig::liquidation_discount
+// accounts follow sdk-ts TransactionBuilder.externalLiquidate, including price_summaries after
+// pool_summaries. Earlier versions left it out (and had no price_summaries parameter), which the
+// contract rejects
+pub fn extern_liquidate_full(
+    accounts: &ExternLiquidateAccounts, // liquidator_wallet needs to be signer
+    program_id: &Pubkey,                // consts::program::ID
 
     repaid_borrow_amount: u64,
     min_collateral_amount: u64,
     borrowed_pool_id: u8,
     collateral_pool_id: u8,
 ) -> Instruction {
-    let data_size = 1 + std::mem::size_of::<ExternLiquidateParam>();
-    let mut buffer = vec![0; data_size];
-    buffer[0] = consts::CMD_EXTERN_LIQUIDATE;
+    let param = ExternLiquidateParam {
+        min_collateral_amount,
+        repaid_borrow_amount,
+        collateral_pool_id,
+        borrowed_pool_id,
+    };
+    let buffer = instruction_data(consts::CMD_EXTERN_LIQUIDATE, &param);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.liquidated_wallet, false),
+            AccountMeta::new_readonly(accounts.liquidator_wallet, true),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new_readonly(accounts.base_pda, false),
+            AccountMeta::new(accounts.liquidator_collateral_spl, false),
+            AccountMeta::new(accounts.liquidator_borrowed_spl, false),
+            AccountMeta::new(accounts.collateral_asset_pool, false),
+            AccountMeta::new(accounts.collateral_asset_pool_spl, false),
+            AccountMeta::new(accounts.borrowed_asset_pool, false),
+            AccountMeta::new(accounts.borrowed_asset_pool_spl, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new_readonly(accounts.price_summaries, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+        ],
+        data: buffer,
+    }
+}
+
+pub fn update_user_config(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    config: &AssistConfig,
+) -> Result<Instruction, AssistConfigError> {
+    update_user_config_with_env(&ApricotEnv::mainnet(), user_wallet, config)
+}
+
+pub fn update_user_config_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    config: &AssistConfig,
+) -> Result<Instruction, AssistConfigError> {
+    update_user_config_full(
+        &UpdateUserConfigAccounts::resolve(user_wallet, env),
+        &env.program_id,
+        config,
+    )
+}
+
+// fails if config doesn't pass AssistConfig::validate(), the factors are checked by the contract
+pub fn update_user_config_full(
+    accounts: &UpdateUserConfigAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,                 // consts::program::ID
+
+    config: &AssistConfig,
+) -> Result<Instruction, AssistConfigError> {
+    config.validate()?;
+    let param = UpdateUserConfigParam {
+        assist_mode: config.assist_mode,
+        self_deleverage_factor: config.self_deleverage_factor,
+        post_deleverage_factor: config.post_deleverage_factor,
+    };
+    let buffer = instruction_data(consts::CMD_UPDATE_USER_CONFIG, &param);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_info, false),
+        ],
+        data: buffer,
+    })
+}
+
+pub fn update_user_asset_config(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    update_user_asset_config_with_env(
+        &ApricotEnv::mainnet(),
+        user_wallet,
+        use_as_collateral,
+        pool_id,
+    )
+}
+
+pub fn update_user_asset_config_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    update_user_asset_config_full(
+        &UpdateUserAssetConfigAccounts::resolve(user_wallet, env),
+        &env.program_id,
+        use_as_collateral,
+        pool_id,
+    )
+}
+
+// toggles UserAssetInfo.use_as_collateral for the user's position in pool_id, turning collateral off is
+// rejected by the contract if it would leave the user below the minimum collateral ratio.
+// UNVERIFIED: sdk-ts has no builder for CMD_UPDATE_USER_ASSET_CONFIG, the accounts are those of
+// TransactionBuilder.updateUserConfig followed by pool_summaries and price_summaries, read-only as
+// in refreshUser and externalLiquidate, for the collateral ratio check.
+pub fn update_user_asset_config_full(
+    accounts: &UpdateUserAssetConfigAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,                      // consts::program::ID
+
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    let param = UpdateUserAssetConfigParam {
+        use_as_collateral: if use_as_collateral { 1 } else { 0 },
+        pool_id,
+    };
+    let buffer = instruction_data(consts::CMD_UPDATE_USER_ASSET_CONFIG, &param);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.user_wallet, true),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new_readonly(accounts.pool_summaries, false),
+            AccountMeta::new_readonly(accounts.price_summaries, false),
+        ],
+        data: buffer,
+    }
+}
+
+// route is SwapRoute::direct(sell_pool_id, buy_pool_id), InvalidArgument if there is none
+pub fn margin_swap(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    sell_pool_id: u8,
+    buy_pool_id: u8,
+    sell_amount: u64,
+    min_buy_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route =
+        SwapRoute::direct(sell_pool_id, buy_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    margin_swap_with_env(
+        &ApricotEnv::mainnet(),
+        user_wallet,
+        &route,
+        sell_pool_id,
+        buy_pool_id,
+        sell_amount,
+        min_buy_amount,
+    )
+}
+
+pub fn margin_swap_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    route: &SwapRoute,    // market between sell_pool_id and buy_pool_id
+    sell_pool_id: u8,
+    buy_pool_id: u8,
+    sell_amount: u64,
+    min_buy_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    margin_swap_full(
+        &MarginSwapAccounts::resolve(user_wallet, sell_pool_id, buy_pool_id, env),
+        &env.program_id,
+        route,
+        sell_pool_id,
+        buy_pool_id,
+        sell_amount,
+        min_buy_amount,
+        false,
+    )
+}
+
+// sells the user's deposit in sell pool for the asset in buy pool through route, without leaving apricot.
+// Fails with ERR_INCORRECT_SELL_MARKET/ERR_INCORRECT_BUY_MARKET if the pools don't match the route.
+pub fn margin_swap_full(
+    accounts: &MarginSwapAccounts, // user_wallet needs to be signer
+    program_id: &Pubkey,           // consts::program::ID
+
+    route: &SwapRoute,
+    sell_pool_id: u8,
+    buy_pool_id: u8,
+    sell_amount: u64,
+    min_buy_amount: u64,
+    is_swap_all_deposit: bool,
+) -> Result<Instruction, ProgramError> {
+    let is_buy = route.is_buy(sell_pool_id, buy_pool_id)?;
+
+    let param = MarginSwapParam {
+        is_buy: if is_buy { 1 } else { 0 },
+        sell_amount,
+        buy_amount: min_buy_amount,
+        sell_pool_id,
+        buy_pool_id,
+        target_swap: route.venue() as u8,
+        is_swap_all_deposit: if is_swap_all_deposit { 1 } else { 0 },
+    };
+    let buffer = instruction_data(consts::CMD_MARGIN_SWAP, &param);
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.user_wallet, true),
+        AccountMeta::new(accounts.user_info, false),
+        AccountMeta::new_readonly(accounts.base_pda, false),
+        AccountMeta::new(accounts.sell_asset_pool, false),
+        AccountMeta::new(accounts.sell_asset_pool_spl, false),
+        AccountMeta::new(accounts.buy_asset_pool, false),
+        AccountMeta::new(accounts.buy_asset_pool_spl, false),
+        AccountMeta::new(accounts.pool_summaries, false),
+        AccountMeta::new_readonly(accounts.price_summaries, false),
+        AccountMeta::new_readonly(accounts.token_program, false),
+    ];
+    metas.extend(route.swap_keys(is_buy));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: buffer,
+    })
+}
+
+// route is LpRoute::from_config(lp_pool_id), InvalidArgument if lp_pool_id isn't an LP pool
+pub fn lp_create(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route = LpRoute::from_config(lp_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    Ok(lp_create_with_env(
+        &ApricotEnv::mainnet(),
+        user_wallet,
+        &route,
+        left_amount,
+        right_amount,
+        min_lp_amount,
+    ))
+}
+
+pub fn lp_create_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    route: &LpRoute,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Instruction {
+    lp_create_full(
+        &LpOpAccounts::resolve(user_wallet, route, env),
+        &env.asset_pool_stake_table_k(route.lp_pool_id),
+        &env.program_id,
+        route,
+        left_amount,
+        right_amount,
+        min_lp_amount,
+    )
+}
+
+// route is LpRoute::from_registry with the market of LpMarket::from_config, pools with other DEX
+// accounts go through LpRoute::from_registry and lp_create_with_env
+pub fn lp_create_with_registry(
+    registry: &PoolRegistry,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route = registry_lp_route(registry, lp_pool_id)?;
+    Ok(lp_create_with_env(
+        &registry.env,
+        user_wallet,
+        &route,
+        left_amount,
+        right_amount,
+        min_lp_amount,
+    ))
+}
+
+// uses the user's left and right deposits to mint LP through route, the LP is deposited back to
+// the user's lp pool position. Staking the new LP into the farm is a separate instruction.
+pub fn lp_create_full(
+    accounts: &LpOpAccounts, // user_wallet needs to be signer
+    stake_table: &Pubkey, // consts::get_asset_pool_stake_table_k(route.lp_pool_id), only used if route.needs_second_stake
+    program_id: &Pubkey,  // consts::program::ID
+
+    route: &LpRoute,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Instruction {
+    let param = LpCreateParam {
+        left_amount,
+        right_amount,
+        min_lp_amount,
+        left_pool_id: route.left_pool_id,
+        right_pool_id: route.right_pool_id,
+        lp_pool_id: route.lp_pool_id,
+        target_swap: route.venue() as u8,
+    };
+    let buffer = instruction_data(consts::CMD_LP_CREATE, &param);
+
+    let mut metas = vec![AccountMeta::new_readonly(accounts.user_wallet, true)];
+    metas.extend(lp_op_metas(accounts));
+    metas.extend(route.deposit_keys());
+    if route.needs_second_stake {
+        metas.push(AccountMeta::new(*stake_table, false));
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: buffer,
+    }
+}
 
-    let mut param = mut_cast::<ExternLiquidateParam>(&mut buffer[1..]);
-    param.repaid_borrow_amount = repaid_borrow_amount;
-    param.min_collateral_amount = min_collateral_amount;
-    param.borrowed_pool_id = borrowed_pool_id;
-    param.collateral_pool_id = collateral_pool_id;
+fn registry_lp_route(registry: &PoolRegistry, lp_pool_id: u8) -> Result<LpRoute, ProgramError> {
+    LpMarket::from_config(lp_pool_id)
+        .and_then(|market| LpRoute::from_registry(registry, lp_pool_id, market))
+        .ok_or(ProgramError::InvalidArgument)
+}
+
+// accounts shared by lp_create and lp_redeem after user_wallet
+fn lp_op_metas(accounts: &LpOpAccounts) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(accounts.user_info, false),
+        AccountMeta::new_readonly(accounts.base_pda, false),
+        AccountMeta::new(accounts.left_asset_pool, false),
+        AccountMeta::new(accounts.left_asset_pool_spl, false),
+        AccountMeta::new(accounts.right_asset_pool, false),
+        AccountMeta::new(accounts.right_asset_pool_spl, false),
+        AccountMeta::new(accounts.lp_asset_pool, false),
+        AccountMeta::new(accounts.lp_asset_pool_spl, false),
+        AccountMeta::new(accounts.pool_summaries, false),
+        AccountMeta::new_readonly(accounts.price_summaries, false),
+        AccountMeta::new_readonly(accounts.token_program, false),
+    ]
+}
+
+// route is LpRoute::from_config(lp_pool_id), InvalidArgument if lp_pool_id isn't an LP pool
+pub fn lp_redeem(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    min_left_amount: u64,
+    min_right_amount: u64,
+    lp_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route = LpRoute::from_config(lp_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    Ok(lp_redeem_with_env(
+        &ApricotEnv::mainnet(),
+        user_wallet,
+        &route,
+        min_left_amount,
+        min_right_amount,
+        lp_amount,
+    ))
+}
+
+pub fn lp_redeem_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    route: &LpRoute,
+    min_left_amount: u64,
+    min_right_amount: u64,
+    lp_amount: u64,
+) -> Instruction {
+    lp_redeem_full(
+        &LpOpAccounts::resolve(user_wallet, route, env),
+        &env.asset_pool_stake_table_k(route.lp_pool_id),
+        &env.program_id,
+        route,
+        min_left_amount,
+        min_right_amount,
+        lp_amount,
+    )
+}
+
+// route as in lp_create_with_registry
+pub fn lp_redeem_with_registry(
+    registry: &PoolRegistry,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    min_left_amount: u64,
+    min_right_amount: u64,
+    lp_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route = registry_lp_route(registry, lp_pool_id)?;
+    Ok(lp_redeem_with_env(
+        &registry.env,
+        user_wallet,
+        &route,
+        min_left_amount,
+        min_right_amount,
+        lp_amount,
+    ))
+}
+
+// burns lp_amount of the user's LP deposit through route and deposits the underlying tokens back
+// to the user's left and right pool positions. The LP has to be unstaked from the farm first.
+pub fn lp_redeem_full(
+    accounts: &LpOpAccounts, // user_wallet needs to be signer
+    stake_table: &Pubkey, // consts::get_asset_pool_stake_table_k(route.lp_pool_id), only used if route.needs_second_stake
+    program_id: &Pubkey,  // consts::program::ID
+
+    route: &LpRoute,
+    min_left_amount: u64,
+    min_right_amount: u64,
+    lp_amount: u64,
+) -> Instruction {
+    let param = LpRedeemParam {
+        min_left_amount,
+        min_right_amount,
+        lp_amount,
+        left_pool_id: route.left_pool_id,
+        right_pool_id: route.right_pool_id,
+        lp_pool_id: route.lp_pool_id,
+        target_swap: route.venue() as u8,
+    };
+    let buffer = instruction_data(consts::CMD_LP_REDEEM, &param);
+
+    let mut metas = vec![AccountMeta::new(accounts.user_wallet, true)];
+    metas.extend(lp_op_metas(accounts));
+    metas.extend(route.withdraw_keys());
+    if route.needs_second_stake {
+        metas.push(AccountMeta::new(*stake_table, false));
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: buffer,
+    }
+}
+
+// stakes all of the lp pool's unstaked LP into LpFarm::from_config(lp_pool_id). Raydium pools and
+// pools with Orca double-dip need a second stake, they are staked by lp_stake_second_raydium and
+// lp_stake_second_orca instead and get InvalidArgument here.
+pub fn lp_stake(lp_pool_id: u8) -> Result<Instruction, ProgramError> {
+    let farm = LpFarm::from_config(lp_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    lp_stake_with_env(&ApricotEnv::mainnet(), lp_pool_id, &farm)
+}
+
+pub fn lp_stake_with_env(
+    env: &ApricotEnv,
+    lp_pool_id: u8,
+    farm: &LpFarm,
+) -> Result<Instruction, ProgramError> {
+    lp_stake_or_unstake(env, consts::CMD_LP_STAKE, lp_pool_id, farm, 0)
+}
+
+// unstakes amount of LP from the pool's farm back to the lp pool, usually followed by lp_redeem.
+// InvalidArgument for pools that need a second stake, see lp_unstake_second_*
+pub fn lp_unstake(lp_pool_id: u8, amount: u64) -> Result<Instruction, ProgramError> {
+    let farm = LpFarm::from_config(lp_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    lp_unstake_with_env(&ApricotEnv::mainnet(), lp_pool_id, &farm, amount)
+}
+
+pub fn lp_unstake_with_env(
+    env: &ApricotEnv,
+    lp_pool_id: u8,
+    farm: &LpFarm,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    lp_stake_or_unstake(env, consts::CMD_LP_UNSTAKE, lp_pool_id, farm, amount)
+}
+
+fn lp_stake_or_unstake(
+    env: &ApricotEnv,
+    cmd: u8,
+    lp_pool_id: u8,
+    farm: &LpFarm,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let stake_keys = match farm {
+        LpFarm::Orca(f) => f.stake_keys(),
+        LpFarm::Saber(f) => f.stake_keys(),
+        // pools that need a second stake (LP_TO_NEED_2ND_STAKE in sdk-ts) are only staked by the
+        // lp_stake_second_*/lp_unstake_second_* builders
+        LpFarm::Raydium(_) | LpFarm::OrcaDoubleDip(_) => return Err(ProgramError::InvalidArgument),
+    };
+
+    let param = LpStakeParam {
+        amount,
+        target_swap: farm.venue() as u8,
+        lp_pool_id,
+    };
+    let buffer = instruction_data(cmd, &param);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(env.pool_summaries, false),
+        AccountMeta::new(env.asset_pool_spl_k(&spl_token::ID, lp_pool_id), false),
+        AccountMeta::new_readonly(env.base_pda, false),
+    ];
+    accounts.extend(stake_keys);
+
+    Ok(Instruction {
+        program_id: env.program_id,
+        accounts,
+        data: buffer,
+    })
+}
+
+// stakes the pool's unstaked LP into both orca farms, signed by the apricot refresher.
+// InvalidArgument unless lp_pool_id is an orca double-dip pool.
+pub fn lp_stake_second_orca(
+    refresher: &Pubkey, // needs to be signer
+    lp_pool_id: u8,
+) -> Result<Instruction, ProgramError> {
+    let farm = config_double_dip_farm(lp_pool_id)?;
+    Ok(lp_stake_second_orca_with_env(
+        &ApricotEnv::mainnet(),
+        refresher,
+        lp_pool_id,
+        &farm,
+    ))
+}
+
+fn config_double_dip_farm(lp_pool_id: u8) -> Result<OrcaDoubleDipFarm, ProgramError> {
+    match LpFarm::from_config(lp_pool_id) {
+        Some(LpFarm::OrcaDoubleDip(farm)) => Ok(farm),
+        _ => Err(ProgramError::InvalidArgument),
+    }
+}
+
+fn config_raydium_farm(lp_pool_id: u8) -> Result<RaydiumFarm, ProgramError> {
+    match LpFarm::from_config(lp_pool_id) {
+        Some(LpFarm::Raydium(farm)) => Ok(farm),
+        _ => Err(ProgramError::InvalidArgument),
+    }
+}
+
+pub fn lp_stake_second_orca_with_env(
+    env: &ApricotEnv,
+    refresher: &Pubkey, // needs to be signer
+    lp_pool_id: u8,
+    farm: &OrcaDoubleDipFarm,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*refresher, true),
+        AccountMeta::new(env.asset_pool_k(lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_spl_k(&spl_token::ID, lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_stake_table_k(lp_pool_id), false),
+        AccountMeta::new(farm.floating_lp, false),
+        AccountMeta::new_readonly(env.base_pda, false),
+    ];
+    accounts.extend(farm.first.stake_keys());
+    accounts.extend(farm.second.stake_keys());
+
+    Instruction {
+        program_id: env.program_id,
+        accounts,
+        data: vec![consts::CMD_LP_STAKE_SECOND],
+    }
+}
+
+// stakes the pool's unstaked LP into the raydium farm, anyone can send it.
+// InvalidArgument unless lp_pool_id is a raydium pool with a farm.
+pub fn lp_stake_second_raydium(lp_pool_id: u8) -> Result<Instruction, ProgramError> {
+    let farm = config_raydium_farm(lp_pool_id)?;
+    Ok(lp_stake_second_raydium_with_env(
+        &ApricotEnv::mainnet(),
+        lp_pool_id,
+        &farm,
+    ))
+}
+
+pub fn lp_stake_second_raydium_with_env(
+    env: &ApricotEnv,
+    lp_pool_id: u8,
+    farm: &RaydiumFarm,
+) -> Instruction {
+    let mut accounts = vec![
+        // placeholder, no signer needed
+        AccountMeta::new_readonly(sysvar::clock::ID, false),
+        AccountMeta::new(env.asset_pool_k(lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_spl_k(&spl_token::ID, lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_stake_table_k(lp_pool_id), false),
+        AccountMeta::new_readonly(env.base_pda, false),
+    ];
+    accounts.extend(farm.stake_keys());
+
+    Instruction {
+        program_id: env.program_id,
+        accounts,
+        data: vec![consts::CMD_LP_STAKE_SECOND],
+    }
+}
+
+// unstakes amount of user_wallet's LP from both orca farms, the double-dip farm first
+pub fn lp_unstake_second_orca(
+    unstake_identity: &Pubkey, // user wallet or apricot refresher, needs to be signer
+    user_wallet: &Pubkey,
+    lp_pool_id: u8,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let farm = config_double_dip_farm(lp_pool_id)?;
+    Ok(lp_unstake_second_orca_with_env(
+        &ApricotEnv::mainnet(),
+        unstake_identity,
+        user_wallet,
+        lp_pool_id,
+        &farm,
+        amount,
+    ))
+}
+
+pub fn lp_unstake_second_orca_with_env(
+    env: &ApricotEnv,
+    unstake_identity: &Pubkey, // user wallet or apricot refresher, needs to be signer
+    user_wallet: &Pubkey,
+    lp_pool_id: u8,
+    farm: &OrcaDoubleDipFarm,
+    amount: u64,
+) -> Instruction {
+    let param = LpUnstakeSecondParam { amount };
+    let buffer = instruction_data(consts::CMD_LP_UNSTAKE_SECOND, &param);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*unstake_identity, true),
+        AccountMeta::new_readonly(*user_wallet, false),
+        AccountMeta::new_readonly(env.user_info_k(user_wallet), false),
+        AccountMeta::new(env.asset_pool_k(lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_spl_k(&spl_token::ID, lp_pool_id), false),
+        AccountMeta::new(env.asset_pool_stake_table_k(lp_pool_id), false),
+        AccountMeta::new(farm.floating_lp, false),
+        AccountMeta::new_readonly(env.base_pda, false),
+    ];
+    accounts.extend(farm.second.stake_keys());
+    accounts.extend(farm.first.stake_keys());
+
+    Instruction {
+        program_id: env.program_id,
+        accounts,
+        data: buffer,
+    }
+}
+
+// unstakes amount of user_wallet's LP from the raydium farm, route gives the underlying pools
+// that the farm rewards are accounted to
+pub fn lp_unstake_second_raydium(
+    unstake_identity: &Pubkey, // user wallet or apricot refresher, needs to be signer
+    user_wallet: &Pubkey,
+    lp_pool_id: u8,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let route = LpRoute::from_config(lp_pool_id).ok_or(ProgramError::InvalidArgument)?;
+    let farm = config_raydium_farm(lp_pool_id)?;
+    Ok(lp_unstake_second_raydium_with_env(
+        &ApricotEnv::mainnet(),
+        unstake_identity,
+        user_wallet,
+        &route,
+        &farm,
+        amount,
+    ))
+}
+
+pub fn lp_unstake_second_raydium_with_env(
+    env: &ApricotEnv,
+    unstake_identity: &Pubkey, // user wallet or apricot refresher, needs to be signer
+    user_wallet: &Pubkey,
+    route: &LpRoute,
+    farm: &RaydiumFarm,
+    amount: u64,
+) -> Instruction {
+    let param = LpUnstakeSecondParam { amount };
+    let buffer = instruction_data(consts::CMD_LP_UNSTAKE_SECOND, &param);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*unstake_identity, true),
+        AccountMeta::new_readonly(*user_wallet, false),
+        AccountMeta::new(env.user_info_k(user_wallet), false),
+        AccountMeta::new(env.asset_pool_k(route.lp_pool_id), false),
+        AccountMeta::new(
+            env.asset_pool_spl_k(&spl_token::ID, route.lp_pool_id),
+            false,
+        ),
+        AccountMeta::new(env.asset_pool_stake_table_k(route.lp_pool_id), false),
+        AccountMeta::new_readonly(env.base_pda, false),
+        AccountMeta::new(env.asset_pool_k(route.left_pool_id), false),
+        AccountMeta::new(env.asset_pool_k(route.right_pool_id), false),
+        AccountMeta::new(env.pool_summaries, false),
+    ];
+    accounts.extend(farm.stake_keys());
+
+    Instruction {
+        program_id: env.program_id,
+        accounts,
+        data: buffer,
+    }
+}
+
+// amounts are the ones of the bracketed lp_create (left, right, min_lp) or lp_redeem
+// (min_left, min_right, lp). Prefer bracket_lp_op(), which fills these in from the inner instruction.
+pub fn lp_op_check(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    route: &LpRoute,
+    is_create: bool,
+    left_amount: u64,
+    right_amount: u64,
+    lp_amount: u64,
+) -> Instruction {
+    lp_op_check_with_env(
+        &ApricotEnv::mainnet(),
+        user_wallet,
+        route,
+        is_create,
+        left_amount,
+        right_amount,
+        lp_amount,
+    )
+}
+
+pub fn lp_op_check_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    route: &LpRoute,
+    is_create: bool,
+    left_amount: u64,
+    right_amount: u64,
+    lp_amount: u64,
+) -> Instruction {
+    lp_op_check_full(
+        &LpOpCheckAccounts::resolve(user_wallet, route, env),
+        true,
+        &env.program_id,
+        &LpOpCheckParam {
+            left_amount,
+            right_amount,
+            lp_amount,
+            left_pool_id: route.left_pool_id,
+            right_pool_id: route.right_pool_id,
+            lp_pool_id: route.lp_pool_id,
+            target_swap: route.venue() as u8,
+            is_create: if is_create { 1 } else { 0 },
+        },
+    )
+}
+
+pub fn lp_op_check_full(
+    accounts: &LpOpCheckAccounts,
+    is_signed: bool,     // false when a refresher redeems on the user's behalf
+    program_id: &Pubkey, // consts::program::ID
+
+    check: &LpOpCheckParam,
+) -> Instruction {
+    let buffer = instruction_data(consts::CMD_LP_OP_CHECK, check);
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new_readonly(*liquidated_wallet, false),
-            AccountMeta::new_readonly(*liquidator_wallet, true),
-            AccountMeta::new(*user_info, false),
-            AccountMeta::new_readonly(*base_pda, false),
-            AccountMeta::new(*liquidator_collateral_spl, false),
-            AccountMeta::new(*liquidator_borrowed_spl, false),
-            AccountMeta::new(*collateral_asset_pool, false),
-            AccountMeta::new(*collateral_asset_pool_spl, false),
-            AccountMeta::new(*borrowed_asset_pool, false),
-            AccountMeta::new(*borrowed_asset_pool_spl, false),
-            AccountMeta::new(*pool_summaries, false),
-            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(accounts.user_wallet, is_signed),
+            AccountMeta::new(accounts.user_info, false),
+            AccountMeta::new(accounts.left_asset_pool, false),
+            AccountMeta::new(accounts.left_asset_pool_spl, false),
+            AccountMeta::new(accounts.right_asset_pool, false),
+            AccountMeta::new(accounts.right_asset_pool_spl, false),
+            AccountMeta::new(accounts.lp_asset_pool, false),
+            AccountMeta::new(accounts.pool_summaries, false),
+            AccountMeta::new_readonly(accounts.price_summaries, false),
+            AccountMeta::new_readonly(accounts.instructions_sysvar, false),
         ],
         data: buffer,
     }
 }
 
+pub fn lp_op_endcheck() -> Instruction {
+    lp_op_endcheck_with_env(&ApricotEnv::mainnet())
+}
+
+pub fn lp_op_endcheck_with_env(env: &ApricotEnv) -> Instruction {
+    Instruction {
+        program_id: env.program_id,
+        accounts: vec![AccountMeta::new(env.pool_summaries, false)],
+        data: vec![consts::CMD_LP_OP_ENDCHECK],
+    }
+}
+
+/**
+ * Wraps one LP operation into lp_op_check ... lp_op_endcheck, the contract reads the instructions
+ * sysvar and rejects an LP create/redeem that isn't bracketed this way. inner is either
+ * [lp_create] or [lp_create, lp_stake] for a create, and [lp_redeem] or [lp_unstake, lp_redeem]
+ * for a redeem, any other order fails with InvalidArgument. So does a stake/unstake of another
+ * pool, an unstake of another amount than the redeemed lp_amount and any stake/unstake for a
+ * pool that needs a second stake. The check instruction is built
+ * from the accounts and data of the lp_create/lp_redeem.
+ *
+ * Raydium redeems are not bracketed by the contract, they are returned as-is.
+*/
+pub fn bracket_lp_op(inner: Vec<Instruction>) -> Result<Vec<Instruction>, ProgramError> {
+    bracket_lp_op_with_env(&ApricotEnv::mainnet(), inner)
+}
+
+pub fn bracket_lp_op_with_env(
+    env: &ApricotEnv,
+    inner: Vec<Instruction>,
+) -> Result<Vec<Instruction>, ProgramError> {
+    bracket_lp_op_for(env, None, inner)
+}
+
+// also fails with InvalidArgument if the LP pool isn't an LP pool of registry with those components
+pub fn bracket_lp_op_with_registry(
+    registry: &PoolRegistry,
+    inner: Vec<Instruction>,
+) -> Result<Vec<Instruction>, ProgramError> {
+    bracket_lp_op_for(&registry.env, Some(registry), inner)
+}
+
+fn bracket_lp_op_for(
+    env: &ApricotEnv,
+    registry: Option<&PoolRegistry>,
+    inner: Vec<Instruction>,
+) -> Result<Vec<Instruction>, ProgramError> {
+    let cmds: Vec<u8> = inner
+        .iter()
+        .map(|ix| {
+            if ix.program_id != env.program_id || ix.data.is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            Ok(ix.data[0])
+        })
+        .collect::<Result<_, _>>()?;
+
+    let (op_index, is_create) = match cmds.as_slice() {
+        [consts::CMD_LP_CREATE] | [consts::CMD_LP_CREATE, consts::CMD_LP_STAKE] => (0, true),
+        [consts::CMD_LP_REDEEM] => (0, false),
+        [consts::CMD_LP_UNSTAKE, consts::CMD_LP_REDEEM] => (1, false),
+        _ => return Err(ProgramError::InvalidArgument),
+    };
+
+    let op = &inner[op_index];
+    if op.data.len() != 1 + LpCreateParam::LEN || op.accounts.len() < 11 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    // lp_redeem params share the layout of lp_create params
+    let param = LpCreateParam::unpack_from_slice(&op.data[1..])?;
+    if let Some(registry) = registry {
+        let components = registry
+            .by_id(param.lp_pool_id)
+            .and_then(|pool| pool.lp_components);
+        if components != Some((param.left_pool_id, param.right_pool_id)) {
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+    if inner.len() == 2 {
+        // the stake/unstake is for the same pool and unstakes exactly the redeemed LP
+        let stake_ix = &inner[1 - op_index];
+        if stake_ix.data.len() != 1 + LpStakeParam::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let stake = LpStakeParam::unpack_from_slice(&stake_ix.data[1..])?;
+        if stake.lp_pool_id != param.lp_pool_id
+            || stake.target_swap != param.target_swap
+            || (!is_create && stake.amount != param.min_lp_amount)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // pools that need a second stake, their create/redeem carries the stake table, are
+        // staked by lp_stake_second_* outside of the bracket
+        let stake_table = env.asset_pool_stake_table_k(param.lp_pool_id);
+        if op.accounts.last().map(|meta| meta.pubkey) == Some(stake_table) {
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+    if !is_create && param.target_swap == consts::SWAP_RAYDIUM {
+        return Ok(inner);
+    }
+    let check = LpOpCheckParam {
+        left_amount: param.left_amount,
+        right_amount: param.right_amount,
+        lp_amount: param.min_lp_amount,
+        left_pool_id: param.left_pool_id,
+        right_pool_id: param.right_pool_id,
+        lp_pool_id: param.lp_pool_id,
+        target_swap: param.target_swap,
+        is_create: if is_create { 1 } else { 0 },
+    };
+
+    // accounts of lp_create/lp_redeem, see lp_create_full
+    let keys = &op.accounts;
+    let accounts = LpOpCheckAccounts {
+        user_wallet: keys[0].pubkey,
+        user_info: keys[1].pubkey,
+        left_asset_pool: keys[3].pubkey,
+        left_asset_pool_spl: keys[4].pubkey,
+        right_asset_pool: keys[5].pubkey,
+        right_asset_pool_spl: keys[6].pubkey,
+        lp_asset_pool: keys[7].pubkey,
+        pool_summaries: keys[9].pubkey,
+        price_summaries: keys[10].pubkey,
+        instructions_sysvar: sysvar::instructions::ID,
+    };
+    let check_ix = lp_op_check_full(&accounts, keys[0].is_signer, &op.program_id, &check);
+
+    let mut bracketed = Vec::with_capacity(inner.len() + 2);
+    bracketed.push(check_ix);
+    bracketed.extend(inner);
+    bracketed.push(lp_op_endcheck_with_env(env));
+    Ok(bracketed)
+}
+
 // APT Reward has one week accumalating period and one week vestin period,
 // Use this method to make any APT reward post-vesting available, contract will find how much APT
 // reward has finished vesting and make it available.
 pub fn make_lm_reward_claimable(user_wallet: &Pubkey) -> Instruction {
-    let user_info = consts::get_user_info_k(user_wallet);
-    let pool_summaries = consts::get_pool_summaries_k();
+    make_lm_reward_claimable_with_env(&ApricotEnv::mainnet(), user_wallet)
+}
+
+pub fn make_lm_reward_claimable_with_env(env: &ApricotEnv, user_wallet: &Pubkey) -> Instruction {
+    let user_info = env.user_info_k(user_wallet);
+    let pool_summaries = env.pool_summaries;
 
     let mut buffer = vec![0; 1];
     buffer[0] = consts::CMD_MAKE_LM_REWARD_AVAILABLE;
 
     Instruction {
-        program_id: consts::program::ID,
+        program_id: env.program_id,
         accounts: vec![
             AccountMeta::new_readonly(*user_wallet, true),
             AccountMeta::new_readonly(*user_wallet, false),
@@ -525,16 +1674,24 @@ pub fn make_lm_reward_claimable(user_wallet: &Pubkey) -> Instruction {
 }
 
 pub fn claim_apt_lm_reward(user_wallet: &Pubkey, user_apt_spl: &Pubkey) -> Instruction {
-    let base_pda = consts::get_base_pda();
-    let user_info = consts::get_user_info_k(user_wallet);
-    let lm_apt_vault = consts::lm_apt_vault::ID;
-    let pool_summaries = consts::get_pool_summaries_k();
-    let price_summaries = consts::get_price_summaries_k();
+    claim_apt_lm_reward_with_env(&ApricotEnv::mainnet(), user_wallet, user_apt_spl)
+}
+
+pub fn claim_apt_lm_reward_with_env(
+    env: &ApricotEnv,
+    user_wallet: &Pubkey,
+    user_apt_spl: &Pubkey,
+) -> Instruction {
+    let base_pda = env.base_pda;
+    let user_info = env.user_info_k(user_wallet);
+    let lm_apt_vault = env.lm_apt_vault;
+    let pool_summaries = env.pool_summaries;
+    let price_summaries = env.price_summaries;
 
     let mut buffer = vec![0; 1];
     buffer[0] = consts::CMD_CLAIM_APT_LM_REWARD;
     Instruction {
-        program_id: consts::program::ID,
+        program_id: env.program_id,
         accounts: vec![
             AccountMeta::new_readonly(base_pda, false),
             AccountMeta::new_readonly(*user_wallet, true),
@@ -548,3 +1705,461 @@ pub fn claim_apt_lm_reward(user_wallet: &Pubkey, user_apt_spl: &Pubkey) -> Instr
         data: buffer,
     }
 }
+
+#[cfg(test)]
+pub mod assist_config_test {
+    use super::*;
+
+    #[test]
+    fn test_assist_config_validation() {
+        assert_eq!(AssistConfig::disabled().validate(), Ok(()));
+        assert_eq!(AssistConfig::stable_only(0.95, 0.8).validate(), Ok(()));
+        assert_eq!(AssistConfig::custom(0.95, 0.8).validate(), Ok(()));
+        assert_eq!(
+            AssistConfig::custom(0.95, 0.8).assist_mode,
+            consts::ASSIST_MODE_CUSTOM
+        );
+
+        // the builders refuse what validate() refuses
+        let wallet = Pubkey::new_unique();
+        let unknown_mode = AssistConfig {
+            assist_mode: 3,
+            ..AssistConfig::disabled()
+        };
+        assert_eq!(
+            unknown_mode.validate(),
+            Err(AssistConfigError::UnknownMode(3))
+        );
+        assert_eq!(
+            update_user_config(&wallet, &unknown_mode),
+            Err(AssistConfigError::UnknownMode(3))
+        );
+        assert!(update_user_config(&wallet, &AssistConfig::disabled()).is_ok());
+        // factors are left to the contract
+        assert!(update_user_config(&wallet, &AssistConfig::custom(0.8, 0.95)).is_ok());
+    }
+}
+
+#[cfg(test)]
+pub mod lp_op_test {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_bracket_lp_op() {
+        let wallet = Pubkey::new_unique();
+        let pool_id = config::sol_usdc_orca::POOL_ID;
+        let route = LpRoute::from_config(pool_id).unwrap();
+
+        let create = lp_create(&wallet, pool_id, 100, 200, 10).unwrap();
+        let stake = lp_stake(pool_id).unwrap();
+        let ixs = bracket_lp_op(vec![create.clone(), stake.clone()]).unwrap();
+        assert_eq!(ixs.len(), 4);
+        assert_eq!(ixs[0], lp_op_check(&wallet, &route, true, 100, 200, 10));
+        assert_eq!(ixs[0].accounts[9].pubkey, sysvar::instructions::ID);
+        assert_eq!(ixs[3], lp_op_endcheck());
+
+        let unstake = lp_unstake(pool_id, 10).unwrap();
+        let redeem = lp_redeem(&wallet, pool_id, 90, 190, 10).unwrap();
+        let ixs = bracket_lp_op(vec![unstake.clone(), redeem.clone()]).unwrap();
+        assert_eq!(ixs[0], lp_op_check(&wallet, &route, false, 90, 190, 10));
+
+        // stake before create, redeem before unstake, two ops and nested checks are refused
+        assert!(bracket_lp_op(vec![stake.clone(), create.clone()]).is_err());
+        assert!(bracket_lp_op(vec![redeem.clone(), unstake.clone()]).is_err());
+        assert!(bracket_lp_op(vec![create.clone(), redeem.clone()]).is_err());
+        assert!(bracket_lp_op(vec![lp_op_endcheck(), create.clone()]).is_err());
+        assert!(bracket_lp_op(vec![]).is_err());
+    }
+
+    fn with_stake_param(ix: &Instruction, param: &LpStakeParam) -> Instruction {
+        Instruction {
+            data: instruction_data(ix.data[0], param),
+            ..ix.clone()
+        }
+    }
+
+    #[test]
+    fn test_bracket_lp_op_mismatched_stake() {
+        let wallet = Pubkey::new_unique();
+        let pool_id = config::sol_usdc_orca::POOL_ID;
+        let other_id = config::orca_usdc_orca::POOL_ID;
+        let create = lp_create(&wallet, pool_id, 100, 200, 10).unwrap();
+        let redeem = lp_redeem(&wallet, pool_id, 90, 190, 10).unwrap();
+        let invalid = Err(ProgramError::InvalidArgument);
+
+        // stake or unstake of another pool
+        let other_stake = lp_stake(other_id).unwrap();
+        assert_eq!(bracket_lp_op(vec![create.clone(), other_stake]), invalid);
+        let other_unstake = lp_unstake(other_id, 10).unwrap();
+        assert_eq!(bracket_lp_op(vec![other_unstake, redeem.clone()]), invalid);
+
+        // unstaking more or less than is redeemed
+        assert_eq!(
+            bracket_lp_op(vec![lp_unstake(pool_id, 11).unwrap(), redeem.clone()]),
+            invalid
+        );
+        assert_eq!(
+            bracket_lp_op(vec![lp_unstake(pool_id, 9).unwrap(), redeem]),
+            invalid
+        );
+
+        // a first stake/unstake for pools that need a second stake, built by hand since
+        // lp_stake/lp_unstake refuse them
+        let stake = lp_stake(pool_id).unwrap();
+        let unstake = lp_unstake(pool_id, 10).unwrap();
+        let second_stake_ids = [
+            config::msol_sol_orca::POOL_ID,
+            config::sol_usdc_raydium::POOL_ID,
+        ];
+        for second_id in second_stake_ids.iter() {
+            let route = LpRoute::from_config(*second_id).unwrap();
+            assert!(route.needs_second_stake);
+            let param = |amount| LpStakeParam {
+                amount,
+                target_swap: route.venue() as u8,
+                lp_pool_id: *second_id,
+            };
+            let create = lp_create(&wallet, *second_id, 100, 200, 10).unwrap();
+            let stake = with_stake_param(&stake, &param(0));
+            assert!(bracket_lp_op(vec![create.clone()]).is_ok());
+            assert_eq!(bracket_lp_op(vec![create, stake]), invalid);
+            let redeem = lp_redeem(&wallet, *second_id, 90, 190, 10).unwrap();
+            let unstake = with_stake_param(&unstake, &param(10));
+            assert!(bracket_lp_op(vec![redeem.clone()]).is_ok());
+            assert_eq!(bracket_lp_op(vec![unstake, redeem]), invalid);
+        }
+    }
+
+    #[test]
+    fn test_lp_stake_refuses_second_stake_pools() {
+        for route in config::pools().filter_map(|pool| LpRoute::from_config(pool.pool_id)) {
+            let pool_id = route.lp_pool_id;
+            if route.needs_second_stake {
+                assert_eq!(lp_stake(pool_id), Err(ProgramError::InvalidArgument));
+                assert_eq!(lp_unstake(pool_id, 10), Err(ProgramError::InvalidArgument));
+            } else if LpFarm::from_config(pool_id).is_some() {
+                assert!(lp_stake(pool_id).is_ok());
+                assert!(lp_unstake(pool_id, 10).is_ok());
+            }
+        }
+        let raydium = config::sol_usdc_raydium::POOL_ID;
+        assert_eq!(lp_stake(raydium), Err(ProgramError::InvalidArgument));
+        assert!(lp_stake_second_raydium(raydium).is_ok());
+    }
+
+    #[test]
+    fn test_lp_op_with_registry() {
+        let wallet = Pubkey::new_unique();
+        let pool_id = config::sol_usdc_orca::POOL_ID;
+        let route = LpRoute::from_config(pool_id).unwrap();
+        let mut registry = PoolRegistry::builtin();
+        registry.env = ApricotEnv::alpha();
+
+        let create = lp_create_with_registry(&registry, &wallet, pool_id, 100, 200, 10).unwrap();
+        assert_eq!(
+            create,
+            lp_create_with_env(&registry.env, &wallet, &route, 100, 200, 10)
+        );
+        let redeem = lp_redeem_with_registry(&registry, &wallet, pool_id, 90, 190, 10).unwrap();
+        assert_eq!(
+            redeem,
+            lp_redeem_with_env(&registry.env, &wallet, &route, 90, 190, 10)
+        );
+        let sol = config::sol::POOL_ID;
+        assert!(lp_create_with_registry(&registry, &wallet, sol, 1, 1, 1).is_err());
+
+        let ixs = bracket_lp_op_with_registry(&registry, vec![create.clone()]).unwrap();
+        assert_eq!(
+            ixs,
+            bracket_lp_op_with_env(&registry.env, vec![create.clone()]).unwrap()
+        );
+        assert_eq!(ixs[0].program_id, registry.env.program_id);
+        assert_eq!(ixs[2], lp_op_endcheck_with_env(&registry.env));
+
+        // the registry disagrees with the components of the lp_create
+        let index = registry
+            .pools
+            .iter()
+            .position(|pool| pool.pool_id == pool_id)
+            .unwrap();
+        registry.pools[index].lp_components = Some((route.right_pool_id, route.left_pool_id));
+        assert_eq!(
+            bracket_lp_op_with_registry(&registry, vec![create]),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod instruction_data_test {
+    use super::*;
+
+    // instruction data produced by sdk-ts TransactionBuilder for the same inputs
+    fn ts_data(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_builders_match_ts() {
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+        let amount = 1_234_567_890_123;
+
+        assert_eq!(
+            add_user_and_deposit(&wallet, &spl, amount, 3, 321).data,
+            ts_data("104101cb04fb711f01000003")
+        );
+        assert_eq!(
+            deposit(&wallet, &spl, amount, 3).data,
+            ts_data("11cb04fb711f01000003")
+        );
+        assert_eq!(
+            withdraw(&wallet, &spl, true, amount, 3, 321).data,
+            ts_data("1a01cb04fb711f01000003")
+        );
+        assert_eq!(
+            borrow(&wallet, &spl, amount, 3).data,
+            ts_data("13cb04fb711f01000003")
+        );
+        assert_eq!(
+            repay(&wallet, &spl, false, amount, 3).data,
+            ts_data("1400cb04fb711f01000003")
+        );
+        assert_eq!(
+            update_user_config(&wallet, &AssistConfig::stable_only(0.8, 0.6))
+                .unwrap()
+                .data,
+            ts_data("17029a9999999999e93f333333333333e33f")
+        );
+    }
+
+    #[test]
+    fn test_params_match_ts() {
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            (
+                instruction_data(
+                    consts::CMD_EXTERN_LIQUIDATE,
+                    &ExternLiquidateParam {
+                        min_collateral_amount: 1_000_000,
+                        repaid_borrow_amount: 2_000_000,
+                        collateral_pool_id: 0,
+                        borrowed_pool_id: 3,
+                    },
+                ),
+                "1540420f000000000080841e00000000000003",
+            ),
+            (
+                instruction_data(
+                    consts::CMD_MARGIN_SWAP,
+                    &MarginSwapParam {
+                        is_buy: 1,
+                        sell_amount: 1_000_000,
+                        buy_amount: 990_000,
+                        sell_pool_id: 0,
+                        buy_pool_id: 3,
+                        target_swap: consts::SWAP_ORCA,
+                        is_swap_all_deposit: 0,
+                    },
+                ),
+                "180140420f0000000000301b0f000000000000030500",
+            ),
+            (
+                instruction_data(
+                    consts::CMD_LP_CREATE,
+                    &LpCreateParam {
+                        left_amount: 1_000_000,
+                        right_amount: 2_000_000,
+                        min_lp_amount: 1_414_000,
+                        left_pool_id: 0,
+                        right_pool_id: 3,
+                        lp_pool_id: 20,
+                        target_swap: consts::SWAP_ORCA,
+                    },
+                ),
+                "1d40420f000000000080841e0000000000709315000000000000031405",
+            ),
+            (
+                instruction_data(
+                    consts::CMD_LP_OP_CHECK,
+                    &LpOpCheckParam {
+                        left_amount: 1_000_000,
+                        right_amount: 2_000_000,
+                        lp_amount: 1_414_000,
+                        left_pool_id: 0,
+                        right_pool_id: 3,
+                        lp_pool_id: 20,
+                        target_swap: consts::SWAP_ORCA,
+                        is_create: 1,
+                    },
+                ),
+                "2140420f000000000080841e000000000070931500000000000003140501",
+            ),
+            (
+                instruction_data(
+                    consts::CMD_LP_STAKE,
+                    &LpStakeParam {
+                        amount: 0,
+                        target_swap: consts::SWAP_ORCA,
+                        lp_pool_id: 20,
+                    },
+                ),
+                "2300000000000000000514",
+            ),
+            (
+                instruction_data(
+                    consts::CMD_LP_UNSTAKE_SECOND,
+                    &LpUnstakeSecondParam { amount: 1_414_000 },
+                ),
+                "827093150000000000",
+            ),
+        ];
+        for (data, hex) in cases {
+            assert_eq!(data, ts_data(hex));
+        }
+    }
+
+    #[test]
+    fn test_param_round_trip() {
+        let param = LpRedeemParam {
+            min_left_amount: u64::MAX,
+            min_right_amount: 1,
+            lp_amount: 1 << 40,
+            left_pool_id: 1,
+            right_pool_id: 2,
+            lp_pool_id: 3,
+            target_swap: consts::SWAP_SABER,
+        };
+        let data = instruction_data(consts::CMD_LP_REDEEM, &param);
+        assert_eq!(data.len(), 1 + 28);
+        assert_eq!(LpRedeemParam::unpack_from_slice(&data[1..]), Ok(param));
+        assert_eq!(
+            LpRedeemParam::unpack_from_slice(&data[1..28]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_extern_liquidate_accounts_match_ts() {
+        let liquidated = Pubkey::new_unique();
+        let liquidator = Pubkey::new_unique();
+        let collateral_spl = Pubkey::new_unique();
+        let borrowed_spl = Pubkey::new_unique();
+        let (borrowed, collateral) = (crate::config::usdc::POOL_ID, crate::config::sol::POOL_ID);
+        let ix = extern_liquidate(
+            &liquidated,
+            &liquidator,
+            &collateral_spl,
+            &borrowed_spl,
+            10,
+            9,
+            borrowed,
+            collateral,
+        );
+
+        // keys of TransactionBuilder.externalLiquidate, in order
+        let token_program = spl_token::ID;
+        let expected = vec![
+            AccountMeta::new_readonly(liquidated, false),
+            AccountMeta::new_readonly(liquidator, true),
+            AccountMeta::new(consts::get_user_info_k(&liquidated), false),
+            AccountMeta::new_readonly(consts::get_base_pda(), false),
+            AccountMeta::new(collateral_spl, false),
+            AccountMeta::new(borrowed_spl, false),
+            AccountMeta::new(consts::get_asset_pool_k(collateral), false),
+            AccountMeta::new(
+                consts::get_asset_pool_spl_k(&token_program, collateral),
+                false,
+            ),
+            AccountMeta::new(consts::get_asset_pool_k(borrowed), false),
+            AccountMeta::new(
+                consts::get_asset_pool_spl_k(&token_program, borrowed),
+                false,
+            ),
+            AccountMeta::new(consts::get_pool_summaries_k(), false),
+            AccountMeta::new_readonly(consts::get_price_summaries_k(), false),
+            AccountMeta::new_readonly(token_program, false),
+        ];
+        assert_eq!(ix.accounts, expected);
+    }
+
+    #[test]
+    fn test_update_user_asset_config_accounts_match_ts() {
+        let wallet = Pubkey::new_unique();
+        let ix = update_user_asset_config(&wallet, true, 3);
+
+        let expected = vec![
+            // keys of TransactionBuilder.updateUserConfig
+            AccountMeta::new_readonly(wallet, true),
+            AccountMeta::new(consts::get_user_info_k(&wallet), false),
+            // PoolSummaries as in TransactionBuilder.refreshUser
+            AccountMeta::new_readonly(consts::get_pool_summaries_k(), false),
+            // PriceSummaries as in TransactionBuilder.externalLiquidate
+            AccountMeta::new_readonly(consts::get_price_summaries_k(), false),
+        ];
+        assert_eq!(ix.accounts, expected);
+        let config = update_user_config(&wallet, &AssistConfig::disabled()).unwrap();
+        assert_eq!(ix.accounts[..2], config.accounts[..]);
+    }
+
+    #[test]
+    fn test_margin_swap_direct() {
+        let wallet = Pubkey::new_unique();
+        let usdc = crate::config::usdc::POOL_ID;
+        let sol = crate::config::sol::POOL_ID;
+
+        // buying SOL with USDC, see DIRECT_SWAP_META and TransactionBuilder.marginSwap in sdk-ts
+        let ix = margin_swap(&wallet, usdc, sol, 1_000_000, 5_000_000).unwrap();
+        let param = MarginSwapParam::unpack_from_slice(&ix.data[1..]).unwrap();
+        assert_eq!(param.is_buy, 1);
+        assert_eq!(param.target_swap, consts::SWAP_RAYDIUM);
+        assert_eq!(ix.accounts.len(), 10 + 15);
+        assert_eq!(
+            ix.accounts[10].pubkey,
+            crate::config::raydium_amm_program::ID
+        );
+
+        let ix = margin_swap(&wallet, sol, usdc, 5_000_000, 1_000_000).unwrap();
+        assert_eq!(
+            MarginSwapParam::unpack_from_slice(&ix.data[1..])
+                .unwrap()
+                .is_buy,
+            0
+        );
+
+        assert_eq!(
+            margin_swap(&wallet, crate::config::srm::POOL_ID, usdc, 1, 1),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod env_builders_test {
+    use super::*;
+
+    #[test]
+    fn test_builders_follow_env() {
+        let env = ApricotEnv::alpha();
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+
+        let ix = deposit_with_env(&env, &wallet, &spl, 100, 3);
+        assert_eq!(ix.program_id, env.program_id);
+        assert_eq!(ix.accounts[2].pubkey, env.user_info_k(&wallet));
+        assert_eq!(ix.accounts[3].pubkey, env.asset_pool_k(3));
+        assert_eq!(ix.accounts[6].pubkey, env.price_summaries);
+        assert_eq!(ix.data, deposit(&wallet, &spl, 100, 3).data);
+
+        let ix = claim_apt_lm_reward_with_env(&env, &wallet, &spl);
+        assert_eq!(ix.accounts[0].pubkey, env.base_pda);
+        assert_eq!(ix.accounts[4].pubkey, consts::alpha::lm_apt_vault::ID);
+
+        let ix = lp_op_endcheck_with_env(&env);
+        assert_eq!(ix.program_id, consts::alpha::program::ID);
+        assert_eq!(ix.accounts[0].pubkey, env.pool_summaries);
+    }
+}
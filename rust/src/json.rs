@@ -0,0 +1,283 @@
+use crate::state::{AssetPool, Assist, RewardInfo, UserAssetInfo, UserInfo};
+use serde::{Deserialize, Serialize};
+
+/**
+ * JSON representations of the account state types, enabled by the serde feature and shared with
+ * the JsonUserInfo/JsonUserAssetInfo types of sdk-ts. RawAmt fields are native amounts (rounded
+ * down), keys are base58 strings, token_name is decoded and the AssetPool flags are named.
+ * Fields are only ever added, never renamed.
+*/
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonUserAssetInfo {
+    pub pool_id: u8,
+    pub use_as_collateral: u8,
+
+    pub deposit_amount: u64,
+    pub deposit_interests: u64,
+    pub deposit_index: f64,
+    pub reward_deposit_amount: f64,
+    pub reward_deposit_index: f64,
+
+    pub borrow_amount: u64,
+    pub borrow_interests: u64,
+    pub borrow_index: f64,
+    pub reward_borrow_amount: f64,
+    pub reward_borrow_index: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRewardInfo {
+    pub vesting: [f64; 4],
+    pub prev_week_apt: f64,
+    pub vesting_apt: f64,
+    pub available_apt: f64,
+    pub available_mnde: f64,
+    pub available_wldo: f64,
+    pub available_b180socn: f64,
+    pub available_wluna: f64,
+}
+
+// price triggered actions are skipped, as in sdk-ts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonAssist {
+    pub assist_mode: u8,
+    pub self_deleverage_factor: f64,
+    pub post_deleverage_factor: f64,
+    pub sell_sequence: Vec<u8>,
+    pub buy_sequence: Vec<u8>,
+    pub num_actions: u8,
+    pub num_executed: u8,
+}
+
+// user_asset_info only holds the num_assets used entries
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonUserInfo {
+    pub page_id: u16,
+    pub num_assets: u8,
+    pub user_asset_info: Vec<JsonUserAssetInfo>,
+    pub reward: JsonRewardInfo,
+    pub last_vest_cutoff_time: u64,
+    pub last_update_time: u64,
+    pub assist: JsonAssist,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonAssetPool {
+    pub token_name: String,
+    pub mint_key: String,
+    pub mint_decimal_multiplier: u64,
+    pub pool_id: u8,
+
+    pub deposit_amount: u64,
+    pub deposit_index: f64,
+    pub borrow_amount: u64,
+    pub borrow_index: f64,
+
+    pub reserve_factor: f64,
+    pub fee_amount: u64,
+    pub fee_withdrawn_amount: u64,
+    pub current_fee_rate: f64,
+
+    pub last_update_time: u64,
+
+    pub spl_key: String,
+    pub atoken_mint_key: String,
+    pub asset_price_key: String,
+    pub pyth_price_key: String,
+
+    pub serum_next_cl_id: u64,
+    pub ltv: f64,
+    pub safe_factor: f64,
+    pub flags: u8,
+    pub allow_borrow: bool,
+    pub is_lp: bool,
+    pub is_stable: bool,
+
+    pub base_rate: f64,
+    pub multiplier: f64,
+    pub jump_multiplier: f64,
+    pub kink: f64,
+    pub current_borrow_rate: f64,
+    pub current_deposit_rate: f64,
+
+    pub reward_multiplier: f64,
+    pub reward_deposit_intra_share: f64,
+    pub reward_apr_per_year: u64,
+    pub deposit_apt_reward_amount_per_year: u64,
+    pub borrow_apt_reward_amount_per_year: u64,
+    pub apt_reward_per_year_per_deposit: f64,
+    pub apt_reward_per_year_per_borrow: f64,
+    pub reward_deposit_index: f64,
+    pub reward_borrow_index: f64,
+
+    pub deposit_cap: u64,
+    pub is_disabled: bool,
+    pub farm_yield: f64,
+}
+
+impl From<&UserAssetInfo> for JsonUserAssetInfo {
+    fn from(info: &UserAssetInfo) -> Self {
+        JsonUserAssetInfo {
+            pool_id: info.pool_id,
+            use_as_collateral: info.use_as_collateral,
+            deposit_amount: info.deposit_amount.to_native_amount(),
+            deposit_interests: info.deposit_interests,
+            deposit_index: info.deposit_index,
+            reward_deposit_amount: info.reward_deposit_amount,
+            reward_deposit_index: info.reward_deposit_index,
+            borrow_amount: info.borrow_amount.to_native_amount(),
+            borrow_interests: info.borrow_interests,
+            borrow_index: info.borrow_index,
+            reward_borrow_amount: info.reward_borrow_amount,
+            reward_borrow_index: info.reward_borrow_index,
+        }
+    }
+}
+
+impl From<&RewardInfo> for JsonRewardInfo {
+    fn from(reward: &RewardInfo) -> Self {
+        JsonRewardInfo {
+            vesting: reward.vesting,
+            prev_week_apt: reward.prev_week_apt,
+            vesting_apt: reward.vesting_apt,
+            available_apt: reward.available_apt,
+            available_mnde: reward.available_mnde,
+            available_wldo: reward.available_wldo,
+            available_b180socn: reward.available_b180socn,
+            available_wluna: reward.available_wluna,
+        }
+    }
+}
+
+impl From<&Assist> for JsonAssist {
+    fn from(assist: &Assist) -> Self {
+        JsonAssist {
+            assist_mode: assist.assist_mode,
+            self_deleverage_factor: assist.self_deleverage_factor,
+            post_deleverage_factor: assist.post_deleverage_factor,
+            sell_sequence: assist.sell_sequence.to_vec(),
+            buy_sequence: assist.buy_sequence.to_vec(),
+            num_actions: assist.num_actions,
+            num_executed: assist.num_executed,
+        }
+    }
+}
+
+impl From<&UserInfo> for JsonUserInfo {
+    fn from(user_info: &UserInfo) -> Self {
+        let num_assets = (user_info.num_assets as usize).min(user_info.user_asset_info.len());
+        JsonUserInfo {
+            page_id: user_info.page_id,
+            num_assets: user_info.num_assets,
+            user_asset_info: user_info.user_asset_info[..num_assets]
+                .iter()
+                .map(JsonUserAssetInfo::from)
+                .collect(),
+            reward: JsonRewardInfo::from(&user_info.reward),
+            last_vest_cutoff_time: user_info.last_vest_cutoff_timestamp,
+            last_update_time: user_info.last_update_timestamp,
+            assist: JsonAssist::from(&user_info.assist),
+        }
+    }
+}
+
+impl From<&AssetPool> for JsonAssetPool {
+    fn from(pool: &AssetPool) -> Self {
+        JsonAssetPool {
+            token_name: pool.token_name_str(),
+            mint_key: pool.mint_key.to_string(),
+            mint_decimal_multiplier: pool.mint_decimal_multiplier,
+            pool_id: pool.pool_id,
+            deposit_amount: pool.deposit_amount.to_native_amount(),
+            deposit_index: pool.deposit_index,
+            borrow_amount: pool.borrow_amount.to_native_amount(),
+            borrow_index: pool.borrow_index,
+            reserve_factor: pool.reserve_factor,
+            fee_amount: pool.fee_amount.to_native_amount(),
+            fee_withdrawn_amount: pool.fee_withdrawn_amount,
+            current_fee_rate: pool.current_fee_rate,
+            last_update_time: pool.last_update_time,
+            spl_key: pool.spl_key.to_string(),
+            atoken_mint_key: pool.atoken_mint_key.to_string(),
+            asset_price_key: pool.asset_price_key.to_string(),
+            pyth_price_key: pool.pyth_price_key.to_string(),
+            serum_next_cl_id: pool.serum_next_cl_id,
+            ltv: pool.ltv,
+            safe_factor: pool.safe_factor,
+            flags: pool.flags,
+            allow_borrow: pool.allow_borrow(),
+            is_lp: pool.is_lp(),
+            is_stable: pool.is_stable(),
+            base_rate: pool.base_rate,
+            multiplier: pool.multiplier,
+            jump_multiplier: pool.jump_multiplier,
+            kink: pool.kink,
+            current_borrow_rate: pool.current_borrow_rate,
+            current_deposit_rate: pool.current_deposit_rate,
+            reward_multiplier: pool.reward_multiplier,
+            reward_deposit_intra_share: pool.reward_deposit_intra_share,
+            reward_apr_per_year: pool.reward_apr_per_year,
+            deposit_apt_reward_amount_per_year: pool.deposit_apt_reward_amount_per_year,
+            borrow_apt_reward_amount_per_year: pool.borrow_apt_reward_amount_per_year,
+            apt_reward_per_year_per_deposit: pool.apt_reward_per_year_per_deposit,
+            apt_reward_per_year_per_borrow: pool.apt_reward_per_year_per_borrow,
+            reward_deposit_index: pool.reward_deposit_index,
+            reward_borrow_index: pool.reward_borrow_index,
+            deposit_cap: pool.deposit_cap,
+            is_disabled: pool.is_disabled != 0,
+            farm_yield: pool.farm_yield,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod json_test {
+    use super::*;
+    use crate::consts;
+    use crate::state::RawAmt;
+
+    #[test]
+    fn test_user_info_json() {
+        let mut user_info = UserInfo {
+            page_id: 3,
+            num_assets: 1,
+            last_update_timestamp: 1_650_000_000,
+            ..UserInfo::default()
+        };
+        user_info.user_asset_info[0].pool_id = 9;
+        user_info.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(1_000);
+        user_info.assist.assist_mode = consts::ASSIST_MODE_STABLE_ONLY;
+
+        let json = serde_json::to_value(JsonUserInfo::from(&user_info)).unwrap();
+        assert_eq!(json["page_id"], 3);
+        assert_eq!(json["last_update_time"], 1_650_000_000u64);
+        assert_eq!(json["user_asset_info"].as_array().unwrap().len(), 1);
+        assert_eq!(json["user_asset_info"][0]["deposit_amount"], 1_000);
+        assert_eq!(
+            json["assist"]["assist_mode"],
+            consts::ASSIST_MODE_STABLE_ONLY
+        );
+
+        let parsed: JsonUserInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, JsonUserInfo::from(&user_info));
+    }
+
+    #[test]
+    fn test_asset_pool_json() {
+        let mut pool = AssetPool {
+            pool_id: 9,
+            flags: consts::POOL_FLAG_ALLOW_BORROW | consts::POOL_FLAG_IS_STABLE,
+            is_disabled: 1,
+            ..AssetPool::default()
+        };
+        pool.token_name[..4].copy_from_slice(b"USDC");
+
+        let json = serde_json::to_value(JsonAssetPool::from(&pool)).unwrap();
+        assert_eq!(json["token_name"], "USDC");
+        assert_eq!(json["mint_key"], pool.mint_key.to_string());
+        assert_eq!(json["allow_borrow"], true);
+        assert_eq!(json["is_lp"], false);
+        assert_eq!(json["is_stable"], true);
+        assert_eq!(json["is_disabled"], true);
+    }
+}
@@ -0,0 +1,108 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+// Little-endian field readers/writers for the contract's packed layouts. Instruction params and
+// account states are (de)serialized field by field in declaration order, no padding in between.
+
+pub(crate) struct LayoutReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LayoutReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        LayoutReader { data }
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N], ProgramError> {
+        if self.data.len() < N {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (head, tail) = self.data.split_at(N);
+        self.data = tail;
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(head);
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, ProgramError> {
+        Ok(u16::from_le_bytes(self.bytes()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    pub fn u128(&mut self) -> Result<u128, ProgramError> {
+        Ok(u128::from_le_bytes(self.bytes()?))
+    }
+
+    pub fn f64(&mut self) -> Result<f64, ProgramError> {
+        Ok(f64::from_le_bytes(self.bytes()?))
+    }
+
+    pub fn pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        Ok(Pubkey::new_from_array(self.bytes()?))
+    }
+
+    // splits off the next len bytes, for nested layouts
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        if self.data.len() < len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+}
+
+// panics when dst is too short, like Pack::pack_into_slice implementations do
+pub(crate) struct LayoutWriter<'a> {
+    data: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> LayoutWriter<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self {
+        LayoutWriter { data, offset: 0 }
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        let end = self.offset + bytes.len();
+        self.data[self.offset..end].copy_from_slice(bytes);
+        self.offset = end;
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.bytes(&[value]);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u128(&mut self, value: u128) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn f64(&mut self, value: f64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn pubkey(&mut self, value: &Pubkey) {
+        self.bytes(value.as_ref());
+    }
+
+    // hands out the next len bytes, for nested layouts
+    pub fn take(&mut self, len: usize) -> &mut [u8] {
+        let start = self.offset;
+        self.offset += len;
+        &mut self.data[start..self.offset]
+    }
+}
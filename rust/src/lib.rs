@@ -1,5 +1,18 @@
+pub mod accounts;
+pub mod amount;
 pub mod config;
 pub mod consts;
+pub mod decoder;
+pub mod env;
+pub mod error;
 pub mod instructions;
+#[cfg(feature = "serde")]
+pub mod json;
+mod layout;
+pub mod lp;
+pub mod portfolio;
+pub mod registry;
 pub mod state;
+pub mod summaries;
+pub mod swap;
 pub mod utils;
@@ -0,0 +1,794 @@
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, sysvar};
+use spl_token;
+
+use crate::config::{self, LpAccounts, PoolConfig};
+use crate::consts;
+use crate::registry::PoolRegistry;
+use crate::swap::SwapVenue;
+
+// orca token swap, the LP mint is the swap's pool mint
+#[derive(Copy, Clone, Debug)]
+pub struct OrcaLpMarket {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fees: Pubkey,
+}
+
+// event queue, bids and asks of the serum market, only some raydium pools need them on withdraw
+#[derive(Copy, Clone, Debug)]
+pub struct SerumQueues {
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct RaydiumLpMarket {
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault_coin: Pubkey,
+    pub vault_pc: Pubkey,
+    pub withdraw_queue: Pubkey,
+    pub temp_lp_token_account: Pubkey,
+
+    pub serum_program: Pubkey, // config::serum_program or openbook, depending on the market
+    pub serum_market: Pubkey,
+    pub serum_vault_coin: Pubkey,
+    pub serum_vault_pc: Pubkey,
+    pub serum_vault_signer: Pubkey,
+    pub serum_queues: Option<SerumQueues>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SaberLpMarket {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub fees_a: Pubkey,
+    pub fees_b: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Copy, Clone, Debug)]
+pub enum LpMarket {
+    Orca(OrcaLpMarket),
+    Raydium(RaydiumLpMarket),
+    Saber(SaberLpMarket),
+}
+
+impl LpMarket {
+    // DEX accounts of one of the config::POOLS LP pools
+    pub fn from_config(lp_pool_id: u8) -> Option<LpMarket> {
+        config_market(lp_pool_id)
+    }
+
+    pub fn venue(&self) -> SwapVenue {
+        match self {
+            LpMarket::Orca(_) => SwapVenue::Orca,
+            LpMarket::Raydium(_) => SwapVenue::Raydium,
+            LpMarket::Saber(_) => SwapVenue::Saber,
+        }
+    }
+}
+
+/**
+ * An LP pool on Apricot together with the DEX pool minting it. left_pool_id and right_pool_id
+ * are the Apricot pools of the two underlying tokens, in the order the contract expects them.
+ * needs_second_stake is set for pools whose farming is done by separate second-stake
+ * instructions, these carry the LP pool's stake table on create/redeem.
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct LpRoute {
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub needs_second_stake: bool,
+    pub market: LpMarket,
+}
+
+impl LpRoute {
+    // route of one of the config::POOLS LP pools, with the DEX accounts of *_LP_METAS in sdk-ts
+    pub fn from_config(lp_pool_id: u8) -> Option<LpRoute> {
+        let pool = config::pool_by_id(lp_pool_id)?;
+        let (left_pool_id, right_pool_id) = pool.lp_components?;
+        Some(LpRoute {
+            left_pool_id,
+            right_pool_id,
+            lp_pool_id,
+            needs_second_stake: needs_second_stake(pool),
+            market: config_market(lp_pool_id)?,
+        })
+    }
+
+    /**
+     * Route of an LP pool of registry, components and second stake come from the registry. A
+     * registry doesn't carry DEX accounts, so market is given, LpMarket::from_config for the
+     * compiled-in LP pools. None if lp_pool_id isn't an LP pool of registry or market is on
+     * another DEX than the pool.
+     */
+    pub fn from_registry(
+        registry: &PoolRegistry,
+        lp_pool_id: u8,
+        market: LpMarket,
+    ) -> Option<LpRoute> {
+        let pool = registry.by_id(lp_pool_id)?;
+        let (left_pool_id, right_pool_id) = pool.lp_components?;
+        if pool.dex.map_or(false, |dex| dex != market.venue()) {
+            return None;
+        }
+        Some(LpRoute {
+            left_pool_id,
+            right_pool_id,
+            lp_pool_id,
+            needs_second_stake: needs_second_stake(pool),
+            market,
+        })
+    }
+
+    pub fn venue(&self) -> SwapVenue {
+        self.market.venue()
+    }
+
+    // venue-specific accounts appended after the apricot accounts of an lp create
+    pub fn deposit_keys(&self) -> Vec<AccountMeta> {
+        let program_id = self.venue().program_id();
+        match &self.market {
+            LpMarket::Orca(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(m.swap, false),
+                AccountMeta::new_readonly(m.swap_authority, false),
+                AccountMeta::new(m.vault_a, false),
+                AccountMeta::new(m.vault_b, false),
+                AccountMeta::new(m.lp_mint, false),
+            ],
+            LpMarket::Raydium(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(m.amm, false),
+                AccountMeta::new_readonly(m.amm_authority, false),
+                AccountMeta::new_readonly(m.open_orders, false),
+                AccountMeta::new(m.target_orders, false),
+                AccountMeta::new(m.lp_mint, false),
+                AccountMeta::new(m.vault_coin, false),
+                AccountMeta::new(m.vault_pc, false),
+                AccountMeta::new_readonly(m.serum_market, false),
+            ],
+            LpMarket::Saber(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(m.swap, false),
+                AccountMeta::new_readonly(m.swap_authority, false),
+                AccountMeta::new(m.vault_a, false),
+                AccountMeta::new(m.vault_b, false),
+                AccountMeta::new(m.lp_mint, false),
+                AccountMeta::new_readonly(sysvar::clock::ID, false),
+            ],
+        }
+    }
+
+    // venue-specific accounts appended after the apricot accounts of an lp redeem
+    pub fn withdraw_keys(&self) -> Vec<AccountMeta> {
+        let program_id = self.venue().program_id();
+        match &self.market {
+            LpMarket::Orca(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(m.swap, false),
+                AccountMeta::new_readonly(m.swap_authority, false),
+                AccountMeta::new(m.lp_mint, false),
+                AccountMeta::new(m.vault_a, false),
+                AccountMeta::new(m.vault_b, false),
+                AccountMeta::new(m.fees, false),
+            ],
+            LpMarket::Raydium(m) => {
+                let mut keys = vec![
+                    AccountMeta::new_readonly(program_id, false),
+                    AccountMeta::new(m.amm, false),
+                    AccountMeta::new_readonly(m.amm_authority, false),
+                    AccountMeta::new(m.open_orders, false),
+                    AccountMeta::new(m.target_orders, false),
+                    AccountMeta::new(m.lp_mint, false),
+                    AccountMeta::new(m.vault_coin, false),
+                    AccountMeta::new(m.vault_pc, false),
+                    AccountMeta::new(m.withdraw_queue, false),
+                    AccountMeta::new(m.temp_lp_token_account, false),
+                    AccountMeta::new_readonly(m.serum_program, false),
+                    AccountMeta::new(m.serum_market, false),
+                    AccountMeta::new(m.serum_vault_coin, false),
+                    AccountMeta::new(m.serum_vault_pc, false),
+                    AccountMeta::new_readonly(m.serum_vault_signer, false),
+                ];
+                if let Some(q) = &m.serum_queues {
+                    keys.push(AccountMeta::new(q.event_queue, false));
+                    keys.push(AccountMeta::new(q.bids, false));
+                    keys.push(AccountMeta::new(q.asks, false));
+                }
+                keys
+            }
+            LpMarket::Saber(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(m.swap, false),
+                AccountMeta::new_readonly(m.swap_authority, false),
+                AccountMeta::new(m.lp_mint, false),
+                AccountMeta::new(m.vault_a, false),
+                AccountMeta::new(m.vault_b, false),
+                AccountMeta::new(m.fees_a, false),
+                AccountMeta::new(m.fees_b, false),
+                AccountMeta::new_readonly(sysvar::clock::ID, false),
+            ],
+        }
+    }
+}
+
+// LP_TO_NEED_2ND_STAKE in sdk-ts: raydium pools with a farm and orca pools with double-dip
+fn needs_second_stake(pool: &PoolConfig) -> bool {
+    match pool.lp_accounts {
+        Some(LpAccounts::Raydium { .. }) => true,
+        Some(LpAccounts::Orca { double_dip, .. }) => double_dip.is_some(),
+        _ => false,
+    }
+}
+
+macro_rules! orca_market {
+    ($pool:ident) => {
+        LpMarket::Orca(OrcaLpMarket {
+            swap: config::$pool::swap::ID,
+            swap_authority: config::$pool::swap_authority::ID,
+            vault_a: config::$pool::vault_a::ID,
+            vault_b: config::$pool::vault_b::ID,
+            lp_mint: config::$pool::ID,
+            fees: config::$pool::fees::ID,
+        })
+    };
+}
+
+macro_rules! raydium_market {
+    ($pool:ident) => {
+        raydium_market!($pool, None)
+    };
+    ($pool:ident, serum_queues) => {
+        raydium_market!(
+            $pool,
+            Some(SerumQueues {
+                event_queue: config::$pool::serum_event_queue::ID,
+                bids: config::$pool::serum_bids::ID,
+                asks: config::$pool::serum_asks::ID,
+            })
+        )
+    };
+    ($pool:ident, $serum_queues:expr) => {
+        LpMarket::Raydium(RaydiumLpMarket {
+            amm: config::$pool::amm::ID,
+            amm_authority: config::$pool::amm_authority::ID,
+            open_orders: config::$pool::open_orders::ID,
+            target_orders: config::$pool::target_orders::ID,
+            lp_mint: config::$pool::ID,
+            vault_coin: config::$pool::vault_coin::ID,
+            vault_pc: config::$pool::vault_pc::ID,
+            withdraw_queue: config::$pool::withdraw_queue::ID,
+            temp_lp_token_account: config::$pool::temp_lp_token_account::ID,
+            serum_program: config::$pool::serum_program::ID,
+            serum_market: config::$pool::serum_market::ID,
+            serum_vault_coin: config::$pool::serum_vault_coin::ID,
+            serum_vault_pc: config::$pool::serum_vault_pc::ID,
+            serum_vault_signer: config::$pool::serum_vault_signer::ID,
+            serum_queues: $serum_queues,
+        })
+    };
+}
+
+macro_rules! saber_market {
+    ($pool:ident) => {
+        LpMarket::Saber(SaberLpMarket {
+            swap: config::$pool::swap::ID,
+            swap_authority: config::$pool::swap_authority::ID,
+            vault_a: config::$pool::vault_a::ID,
+            vault_b: config::$pool::vault_b::ID,
+            fees_a: config::$pool::fees_a::ID,
+            fees_b: config::$pool::fees_b::ID,
+            lp_mint: config::$pool::ID,
+        })
+    };
+}
+
+fn config_market(lp_pool_id: u8) -> Option<LpMarket> {
+    use config::*;
+    let market = match lp_pool_id {
+        usdt_usdc_saber::POOL_ID => saber_market!(usdt_usdc_saber),
+        msol_sol_saber::POOL_ID => saber_market!(msol_sol_saber),
+        stsol_sol_saber::POOL_ID => saber_market!(stsol_sol_saber),
+        ust_usdc_saber::POOL_ID => saber_market!(ust_usdc_saber),
+        usdc_usdt_orca::POOL_ID => orca_market!(usdc_usdt_orca),
+        sol_usdc_orca::POOL_ID => orca_market!(sol_usdc_orca),
+        msol_sol_orca::POOL_ID => orca_market!(msol_sol_orca),
+        orca_usdc_orca::POOL_ID => orca_market!(orca_usdc_orca),
+        orca_sol_orca::POOL_ID => orca_market!(orca_sol_orca),
+        eth_usdc_orca::POOL_ID => orca_market!(eth_usdc_orca),
+        sol_usdt_orca::POOL_ID => orca_market!(sol_usdt_orca),
+        eth_sol_orca::POOL_ID => orca_market!(eth_sol_orca),
+        apt_usdc_orca::POOL_ID => orca_market!(apt_usdc_orca),
+        btc_msol_orca::POOL_ID => orca_market!(btc_msol_orca),
+        msol_usdc_orca::POOL_ID => orca_market!(msol_usdc_orca),
+        stsol_ust_orca::POOL_ID => orca_market!(stsol_ust_orca),
+        orca_wheth_orca::POOL_ID => orca_market!(orca_wheth_orca),
+        sol_usdc_raydium::POOL_ID => raydium_market!(sol_usdc_raydium, serum_queues),
+        ray_usdc_raydium::POOL_ID => raydium_market!(ray_usdc_raydium, serum_queues),
+        sol_usdt_raydium::POOL_ID => raydium_market!(sol_usdt_raydium, serum_queues),
+        msol_sol_raydium::POOL_ID => raydium_market!(msol_sol_raydium),
+        ray_usdt_raydium::POOL_ID => raydium_market!(ray_usdt_raydium, serum_queues),
+        ray_eth_raydium::POOL_ID => raydium_market!(ray_eth_raydium, serum_queues),
+        ray_sol_raydium::POOL_ID => raydium_market!(ray_sol_raydium, serum_queues),
+        srm_usdc_raydium::POOL_ID => raydium_market!(srm_usdc_raydium),
+        _ => return None,
+    };
+    Some(market)
+}
+
+// quarry mine staking of saber LP, miner is config::<pool>::miner
+#[derive(Copy, Clone, Debug)]
+pub struct SaberFarm {
+    pub miner: Pubkey,
+    pub quarry: Pubkey,
+    pub miner_vault: Pubkey, // miner's associated token account of the LP mint
+    pub rewarder: Pubkey,
+}
+
+impl SaberFarm {
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(config::saber_quarry_program::ID, false),
+            AccountMeta::new(self.miner, false),
+            AccountMeta::new(self.quarry, false),
+            AccountMeta::new(self.miner_vault, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(self.rewarder, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    }
+}
+
+/**
+ * One orca aquafarm. user_farm_state and reward_token_account belong to base_pda, they are
+ * config::<pool>::user_farm_state and reward_orca_account for the first farm, and
+ * user_farm_state_2nd and reward_dd_account_2nd for the double-dip farm.
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct OrcaFarm {
+    pub global_base_token_vault: Pubkey,
+    pub farm_token_mint: Pubkey,
+    pub farm_token_account: Pubkey, // base_pda's associated token account of farm_token_mint
+    pub global_farm_state: Pubkey,
+    pub user_farm_state: Pubkey,
+    pub global_reward_token_vault: Pubkey,
+    pub reward_token_account: Pubkey,
+    pub reward_token_authority: Pubkey,
+}
+
+impl OrcaFarm {
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(config::orca_farm_program::ID, false),
+            AccountMeta::new(self.global_base_token_vault, false),
+            AccountMeta::new(self.farm_token_mint, false),
+            AccountMeta::new(self.farm_token_account, false),
+            AccountMeta::new(self.global_farm_state, false),
+            AccountMeta::new(self.user_farm_state, false),
+            AccountMeta::new(self.global_reward_token_vault, false),
+            AccountMeta::new(self.reward_token_account, false),
+            AccountMeta::new_readonly(self.reward_token_authority, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ]
+    }
+}
+
+/**
+ * Orca pools with double-dip: LP is staked into the first farm, and the farm token it returns is
+ * staked again into the double-dip farm. floating_lp is config::<pool>::floating_lp_2nd, it holds
+ * first-farm tokens between the two steps.
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct OrcaDoubleDipFarm {
+    pub first: OrcaFarm,
+    pub second: OrcaFarm,
+    pub floating_lp: Pubkey,
+}
+
+// a user reward account of base_pda (config::<pool>::reward_ray_account or reward_second_account)
+// and the farm vault it is paid from
+#[derive(Copy, Clone, Debug)]
+pub struct RaydiumReward {
+    pub reward_account: Pubkey,
+    pub reward_vault: Pubkey,
+}
+
+// raydium staking pool, user_ledger is config::<pool>::user_ledger_account
+#[derive(Clone, Debug)]
+pub struct RaydiumFarm {
+    pub stake_program: Pubkey, // config::raydium_stake_program or raydium_stake_v5_program
+    pub farm: Pubkey,
+    pub farm_authority: Pubkey,
+    pub user_ledger: Pubkey,
+    pub farm_lp_vault: Pubkey,
+    pub rewards: Vec<RaydiumReward>,
+}
+
+impl RaydiumFarm {
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        let mut keys = vec![
+            AccountMeta::new_readonly(self.stake_program, false),
+            AccountMeta::new(self.farm, false),
+            AccountMeta::new_readonly(self.farm_authority, false),
+            AccountMeta::new(self.user_ledger, false),
+            AccountMeta::new(self.farm_lp_vault, false),
+        ];
+        // the first reward goes before clock and token program, the others after them
+        let mut rewards = self.rewards.iter();
+        if let Some(reward) = rewards.next() {
+            keys.push(AccountMeta::new(reward.reward_account, false));
+            keys.push(AccountMeta::new(reward.reward_vault, false));
+        }
+        keys.push(AccountMeta::new_readonly(sysvar::clock::ID, false));
+        keys.push(AccountMeta::new_readonly(spl_token::ID, false));
+        for reward in rewards {
+            keys.push(AccountMeta::new(reward.reward_account, false));
+            keys.push(AccountMeta::new(reward.reward_vault, false));
+        }
+        keys
+    }
+}
+
+macro_rules! saber_farm {
+    ($pool:ident) => {
+        LpFarm::Saber(SaberFarm {
+            miner: config::$pool::miner::ID,
+            quarry: config::$pool::quarry::ID,
+            miner_vault: consts::get_associated_token_k(
+                &config::$pool::miner::ID,
+                &config::$pool::ID,
+            ),
+            rewarder: config::$pool::rewarder::ID,
+        })
+    };
+}
+
+macro_rules! orca_farm {
+    ($pool:ident) => {
+        OrcaFarm {
+            global_base_token_vault: config::$pool::global_lp_vault::ID,
+            farm_token_mint: config::$pool::farm_token_mint::ID,
+            farm_token_account: consts::get_associated_token_k(
+                &consts::base_pda::ID,
+                &config::$pool::farm_token_mint::ID,
+            ),
+            global_farm_state: config::$pool::global_farm_state::ID,
+            user_farm_state: config::$pool::user_farm_state::ID,
+            global_reward_token_vault: config::$pool::global_reward_token_vault::ID,
+            reward_token_account: config::$pool::reward_orca_account::ID,
+            reward_token_authority: config::$pool::reward_token_authority::ID,
+        }
+    };
+}
+
+macro_rules! orca_double_dip_farm {
+    ($pool:ident) => {
+        LpFarm::OrcaDoubleDip(OrcaDoubleDipFarm {
+            first: orca_farm!($pool),
+            second: OrcaFarm {
+                global_base_token_vault: config::$pool::global_lp_vault_2nd::ID,
+                farm_token_mint: config::$pool::farm_token_mint_2nd::ID,
+                farm_token_account: consts::get_associated_token_k(
+                    &consts::base_pda::ID,
+                    &config::$pool::farm_token_mint_2nd::ID,
+                ),
+                global_farm_state: config::$pool::global_farm_state_2nd::ID,
+                user_farm_state: config::$pool::user_farm_state_2nd::ID,
+                global_reward_token_vault: config::$pool::global_reward_token_vault_2nd::ID,
+                reward_token_account: config::$pool::reward_dd_account_2nd::ID,
+                reward_token_authority: config::$pool::reward_token_authority_2nd::ID,
+            },
+            floating_lp: config::$pool::floating_lp_2nd::ID,
+        })
+    };
+}
+
+// $num_rewards is the length of rewardAccounts in RAYDIUM_LP_METAS
+macro_rules! raydium_farm {
+    ($pool:ident, $stake_program:ident, 1) => {
+        raydium_farm!(
+            $pool,
+            $stake_program,
+            vec![raydium_reward!($pool, reward_ray)]
+        )
+    };
+    ($pool:ident, $stake_program:ident, 2) => {
+        raydium_farm!(
+            $pool,
+            $stake_program,
+            vec![
+                raydium_reward!($pool, reward_ray),
+                raydium_reward!($pool, reward_second)
+            ]
+        )
+    };
+    ($pool:ident, $stake_program:ident, $rewards:expr) => {
+        LpFarm::Raydium(RaydiumFarm {
+            stake_program: config::$stake_program::ID,
+            farm: config::$pool::farm::ID,
+            farm_authority: config::$pool::farm_authority::ID,
+            user_ledger: config::$pool::user_ledger_account::ID,
+            farm_lp_vault: config::$pool::farm_lp_vault::ID,
+            rewards: $rewards,
+        })
+    };
+}
+
+macro_rules! raydium_reward {
+    ($pool:ident, reward_ray) => {
+        RaydiumReward {
+            reward_account: config::$pool::reward_ray_account::ID,
+            reward_vault: config::$pool::reward_ray_vault::ID,
+        }
+    };
+    ($pool:ident, reward_second) => {
+        RaydiumReward {
+            reward_account: config::$pool::reward_second_account::ID,
+            reward_vault: config::$pool::reward_second_vault::ID,
+        }
+    };
+}
+
+// the farm an LP pool's LP is staked into
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum LpFarm {
+    Orca(OrcaFarm),
+    OrcaDoubleDip(OrcaDoubleDipFarm),
+    Raydium(RaydiumFarm),
+    Saber(SaberFarm),
+}
+
+impl LpFarm {
+    /**
+     * Farm of one of the config::POOLS LP pools, None for pools that aren't staked. The accounts
+     * held by base_pda are the config::<pool> ones, which belong to the mainnet deployment.
+     */
+    pub fn from_config(lp_pool_id: u8) -> Option<LpFarm> {
+        use config::*;
+        let farm = match lp_pool_id {
+            usdt_usdc_saber::POOL_ID => saber_farm!(usdt_usdc_saber),
+            msol_sol_saber::POOL_ID => saber_farm!(msol_sol_saber),
+            stsol_sol_saber::POOL_ID => saber_farm!(stsol_sol_saber),
+            ust_usdc_saber::POOL_ID => saber_farm!(ust_usdc_saber),
+            usdc_usdt_orca::POOL_ID => LpFarm::Orca(orca_farm!(usdc_usdt_orca)),
+            sol_usdc_orca::POOL_ID => LpFarm::Orca(orca_farm!(sol_usdc_orca)),
+            msol_sol_orca::POOL_ID => orca_double_dip_farm!(msol_sol_orca),
+            orca_usdc_orca::POOL_ID => LpFarm::Orca(orca_farm!(orca_usdc_orca)),
+            orca_sol_orca::POOL_ID => LpFarm::Orca(orca_farm!(orca_sol_orca)),
+            eth_usdc_orca::POOL_ID => LpFarm::Orca(orca_farm!(eth_usdc_orca)),
+            sol_usdt_orca::POOL_ID => LpFarm::Orca(orca_farm!(sol_usdt_orca)),
+            eth_sol_orca::POOL_ID => LpFarm::Orca(orca_farm!(eth_sol_orca)),
+            apt_usdc_orca::POOL_ID => LpFarm::Orca(orca_farm!(apt_usdc_orca)),
+            btc_msol_orca::POOL_ID => orca_double_dip_farm!(btc_msol_orca),
+            msol_usdc_orca::POOL_ID => orca_double_dip_farm!(msol_usdc_orca),
+            stsol_ust_orca::POOL_ID => LpFarm::Orca(orca_farm!(stsol_ust_orca)),
+            orca_wheth_orca::POOL_ID => LpFarm::Orca(orca_farm!(orca_wheth_orca)),
+            sol_usdc_raydium::POOL_ID => {
+                raydium_farm!(sol_usdc_raydium, raydium_stake_v5_program, 2)
+            }
+            ray_usdc_raydium::POOL_ID => raydium_farm!(ray_usdc_raydium, raydium_stake_program, 1),
+            sol_usdt_raydium::POOL_ID => {
+                raydium_farm!(sol_usdt_raydium, raydium_stake_v5_program, 2)
+            }
+            ray_usdt_raydium::POOL_ID => raydium_farm!(ray_usdt_raydium, raydium_stake_program, 1),
+            ray_eth_raydium::POOL_ID => raydium_farm!(ray_eth_raydium, raydium_stake_program, 1),
+            ray_sol_raydium::POOL_ID => raydium_farm!(ray_sol_raydium, raydium_stake_program, 1),
+            srm_usdc_raydium::POOL_ID => {
+                raydium_farm!(srm_usdc_raydium, raydium_stake_v5_program, 2)
+            }
+            _ => return None,
+        };
+        Some(farm)
+    }
+
+    pub fn venue(&self) -> SwapVenue {
+        match self {
+            LpFarm::Orca(_) | LpFarm::OrcaDoubleDip(_) => SwapVenue::Orca,
+            LpFarm::Raydium(_) => SwapVenue::Raydium,
+            LpFarm::Saber(_) => SwapVenue::Saber,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod lp_route_test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_from_config() {
+        for pool in config::pools() {
+            let route = LpRoute::from_config(pool.pool_id);
+            assert_eq!(route.is_some(), pool.is_lp(), "{}", pool.name);
+            if let Some(route) = route {
+                assert_eq!(Some(route.venue()), pool.dex);
+                assert_eq!(
+                    Some((route.left_pool_id, route.right_pool_id)),
+                    pool.lp_components
+                );
+            }
+        }
+        assert!(LpRoute::from_config(7).is_none());
+
+        // LP_TO_NEED_2ND_STAKE in sdk-ts
+        let second_stake: Vec<u8> = config::pools()
+            .filter_map(|pool| LpRoute::from_config(pool.pool_id))
+            .filter(|route| route.needs_second_stake)
+            .map(|route| route.lp_pool_id)
+            .collect();
+        assert_eq!(
+            second_stake,
+            vec![
+                config::msol_sol_orca::POOL_ID,
+                config::btc_msol_orca::POOL_ID,
+                config::msol_usdc_orca::POOL_ID,
+                config::sol_usdc_raydium::POOL_ID,
+                config::ray_usdc_raydium::POOL_ID,
+                config::sol_usdt_raydium::POOL_ID,
+                config::ray_usdt_raydium::POOL_ID,
+                config::ray_eth_raydium::POOL_ID,
+                config::ray_sol_raydium::POOL_ID,
+                config::srm_usdc_raydium::POOL_ID,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_registry() {
+        let registry = PoolRegistry::builtin();
+        for pool in config::pools().filter(|pool| pool.is_lp()) {
+            let market = LpMarket::from_config(pool.pool_id).unwrap();
+            let route = LpRoute::from_registry(&registry, pool.pool_id, market).unwrap();
+            let expected = LpRoute::from_config(pool.pool_id).unwrap();
+            assert_eq!(route.lp_pool_id, expected.lp_pool_id);
+            assert_eq!(route.left_pool_id, expected.left_pool_id);
+            assert_eq!(route.right_pool_id, expected.right_pool_id);
+            assert_eq!(route.needs_second_stake, expected.needs_second_stake);
+            assert_eq!(route.venue(), expected.venue());
+        }
+
+        let (orca, saber) = (
+            config::sol_usdc_orca::POOL_ID,
+            config::usdt_usdc_saber::POOL_ID,
+        );
+        let saber_market = LpMarket::from_config(saber).unwrap();
+        assert!(LpRoute::from_registry(&registry, orca, saber_market).is_none());
+        assert!(LpRoute::from_registry(&registry, config::sol::POOL_ID, saber_market).is_none());
+
+        // components come from the registry, not from config
+        let mut registry = PoolRegistry::builtin();
+        let index = registry
+            .pools
+            .iter()
+            .position(|pool| pool.pool_id == saber)
+            .unwrap();
+        registry.pools[index].lp_components = Some((config::usdc::POOL_ID, config::usdt::POOL_ID));
+        let route = LpRoute::from_registry(&registry, saber, saber_market).unwrap();
+        assert_eq!(route.left_pool_id, config::usdc::POOL_ID);
+    }
+
+    #[test]
+    fn test_raydium_withdraw_keys() {
+        // RAYDIUM_LP_METAS[SOL_USDC_RAYDIUM].getLpWithdrawKeys() in sdk-ts
+        let keys: Vec<Pubkey> = [
+            "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+            "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1",
+            "HmiHHzq4Fym9e1D4qzLS6LDDM3tNsCTBPDWHTLZ763jY",
+            "CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR",
+            "8HoQnePLqPj4M7PUDzfw8e3Ymdwgc7NLGnaTUapubyvu",
+            "DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz",
+            "HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz",
+            "G7xeGGLevkRwB5f44QNgQtrPKBdMfkT6ZZwpS9xcC97n",
+            "Awpt6N7ZYPBa4vG4BQNFhFxDj4sxExAA9rpBAoBw2uok",
+            "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX",
+            "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6",
+            "CKxTHwM9fPMRRvZmFnFoqKNd9pQR21c5Aq9bh5h9oghX",
+            "6A5NHCj1yF6urc9wZNe6Bcjj4LVszQNj5DwAWG97yzMu",
+            "CTz5UMLQm2SRWHzQnU62Pi4yJqbNGjgRBHqqp6oDHfF7",
+            "8CvwxZ9Db6XbLD46NZwwmVDZZRDy7eydFcAGkXKh9axa",
+            "5jWUncPNBMZJ3sTHKmMLszypVkoRK6bfEQMQUHweeQnh",
+            "EaXdHx7x3mdGA38j5RSmKYSXMzAFzzUXCLNBEDXDn1d5",
+        ]
+        .iter()
+        .map(|key| Pubkey::from_str(key).unwrap())
+        .collect();
+        let route = LpRoute::from_config(config::sol_usdc_raydium::POOL_ID).unwrap();
+        let withdraw_keys: Vec<Pubkey> = route.withdraw_keys().iter().map(|m| m.pubkey).collect();
+        assert_eq!(withdraw_keys, keys);
+
+        // no serum queues for SRM_USDC_RAYDIUM
+        let route = LpRoute::from_config(config::srm_usdc_raydium::POOL_ID).unwrap();
+        assert_eq!(route.withdraw_keys().len(), keys.len() - 3);
+    }
+
+    #[test]
+    fn test_farm_from_config() {
+        for pool in config::pools() {
+            let farm = LpFarm::from_config(pool.pool_id);
+            assert_eq!(farm.is_some(), pool.lp_accounts.is_some(), "{}", pool.name);
+            if let Some(farm) = farm {
+                assert_eq!(Some(farm.venue()), pool.dex);
+            }
+        }
+        assert!(LpFarm::from_config(config::msol_sol_raydium::POOL_ID).is_none());
+
+        // ORCA_LP_METAS[SOL_USDC_ORCA].getFirstStakeKeys(base_pda) in sdk-ts
+        let keys: Vec<Pubkey> = [
+            "82yxjeMsvaURa4MbZZ7WZZHfobirZYkH1zF8fmeGtyaQ",
+            "7ipefo5V3QEJWeuT2PohFSEUaranZxMSeWQo2rcNigr3",
+            "FFdjrSvNALfdgxANNpt3x85WpeVMdQSH5SEP2poM8fcK",
+        ]
+        .iter()
+        .map(|key| Pubkey::from_str(key).unwrap())
+        .collect();
+        let farm = match LpFarm::from_config(config::sol_usdc_orca::POOL_ID) {
+            Some(LpFarm::Orca(farm)) => farm,
+            other => panic!("unexpected farm {:?}", other),
+        };
+        let stake_keys = farm.stake_keys();
+        assert_eq!(
+            stake_keys[..3].iter().map(|m| m.pubkey).collect::<Vec<_>>(),
+            keys
+        );
+        assert_eq!(
+            stake_keys[3].pubkey,
+            consts::get_associated_token_k(&consts::base_pda::ID, &keys[2])
+        );
+        assert_eq!(
+            stake_keys[5].pubkey,
+            config::sol_usdc_orca::user_farm_state::ID
+        );
+        assert_eq!(
+            stake_keys[7].pubkey,
+            config::sol_usdc_orca::reward_orca_account::ID
+        );
+
+        match LpFarm::from_config(config::msol_usdc_orca::POOL_ID) {
+            Some(LpFarm::OrcaDoubleDip(farm)) => {
+                let second_mint = config::msol_usdc_orca::farm_token_mint_2nd::ID;
+                assert_eq!(farm.second.farm_token_mint, second_mint);
+                assert_eq!(
+                    farm.second.farm_token_account,
+                    consts::get_associated_token_k(&consts::base_pda::ID, &second_mint)
+                );
+                assert_eq!(
+                    farm.floating_lp,
+                    config::msol_usdc_orca::floating_lp_2nd::ID
+                );
+            }
+            other => panic!("unexpected farm {:?}", other),
+        }
+
+        // v4 farms pay RAY only, v5 farms a second reward too
+        match LpFarm::from_config(config::ray_usdc_raydium::POOL_ID) {
+            Some(LpFarm::Raydium(farm)) => {
+                assert_eq!(farm.stake_program, config::raydium_stake_program::ID);
+                assert_eq!(farm.rewards.len(), 1);
+            }
+            other => panic!("unexpected farm {:?}", other),
+        }
+        match LpFarm::from_config(config::sol_usdc_raydium::POOL_ID) {
+            Some(LpFarm::Raydium(farm)) => {
+                assert_eq!(farm.stake_program, config::raydium_stake_v5_program::ID);
+                assert_eq!(farm.rewards.len(), 2);
+                assert_eq!(farm.stake_keys().len(), 11);
+            }
+            other => panic!("unexpected farm {:?}", other),
+        }
+    }
+}
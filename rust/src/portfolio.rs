@@ -0,0 +1,567 @@
+use crate::config;
+use crate::consts;
+use crate::registry::PoolRegistry;
+use crate::state::{AssetPool, UserInfo};
+use solana_program::program_error::ProgramError;
+use std::collections::HashMap;
+
+/**
+ * Price of a pool's token in USD per whole token, the convention of sdk-ts and
+ * NativeAmount::to_usd_amount. None if the price is unknown. Like sdk-ts, prices come from the
+ * oracles, PriceSummaries isn't a PriceSource while its layout is unverified.
+*/
+pub trait PriceSource {
+    fn price_in_usd(&self, pool_id: u8) -> Option<f64>;
+
+    // USD value of one native unit, using the pool's decimals from config
+    fn native_price(&self, pool_id: u8) -> Option<f64> {
+        let decimals = config::pool_by_id(pool_id)?.decimals;
+        Some(self.price_in_usd(pool_id)? / 10f64.powi(decimals as i32))
+    }
+}
+
+impl PriceSource for HashMap<u8, f64> {
+    fn price_in_usd(&self, pool_id: u8) -> Option<f64> {
+        self.get(&pool_id).copied()
+    }
+}
+
+impl<F: Fn(u8) -> Option<f64>> PriceSource for F {
+    fn price_in_usd(&self, pool_id: u8) -> Option<f64> {
+        self(pool_id)
+    }
+}
+
+// one asset of the user, amounts fast-forwarded to the time the portfolio was built for
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AssetPosition {
+    pub pool_id: u8,
+    pub use_as_collateral: bool,
+    pub ltv: f64,
+    pub deposit_amount: u64, // native amount
+    pub borrow_amount: u64,  // native amount
+    pub deposit_value: f64,  // USD
+    pub borrow_value: f64,   // USD
+}
+
+impl AssetPosition {
+    // deposit value that counts towards borrow power
+    pub fn collateral_value(&self) -> f64 {
+        if self.use_as_collateral {
+            self.ltv * self.deposit_value
+        } else {
+            0.0
+        }
+    }
+}
+
+/**
+ * GUESS: the collateral ratio liquidation_quotes brings a user back to. The contract rejects
+ * liquidations that go too far with ERR_EXCEEDS_LIQUIDATION_LIMIT, but its limit isn't published;
+ * SAFE_LIMIT is assumed. Quotes may be rejected, or leave room, if the real limit differs.
+*/
+pub const ESTIMATED_LIQUIDATION_TARGET: f64 = consts::SAFE_LIMIT;
+
+/**
+ * One way to liquidate a user with extern_liquidate: repay repay_amount (native) of
+ * borrowed_pool_id and receive collateral_amount (native) of collateral_pool_id, to be passed as
+ * repaid_borrow_amount and min_collateral_amount. profit is the USD value received minus repaid.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LiquidationQuote {
+    pub borrowed_pool_id: u8,
+    pub collateral_pool_id: u8,
+    pub repay_amount: u64,
+    pub collateral_amount: u64,
+    pub profit: f64,
+}
+
+/**
+ * Borrow power and health of a user, the Rust counterpart of sdk-ts getBorrowPowerInfo.
+ * collateral_ratio is total borrow value over total collateral value (ltv-weighted deposits used
+ * as collateral): borrowing stops at SAFE_LIMIT, assist is forced at FORCE_ASSIST_LIMIT and the
+ * user can be liquidated from LIQUIDATION_LIMIT on.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Portfolio {
+    pub positions: Vec<AssetPosition>,
+    // self/post deleverage factors if the user has assist enabled
+    pub assist_trigger_limit: Option<f64>,
+    pub assist_target_limit: Option<f64>,
+}
+
+impl Portfolio {
+    /**
+     * pools needs to hold the AssetPool of every asset of user_info, prices the price of each of them,
+     * ERR_INCORRECT_ASSET_POOL and ERR_INCORRECT_ASSET_PRICE are returned otherwise. Pools missing
+     * from config have no decimals and so no price.
+     * Amounts are accrued to now (unix seconds), see UserAssetInfo::fast_forwarded.
+     */
+    pub fn new<P: PriceSource + ?Sized>(
+        user_info: &UserInfo,
+        pools: &[AssetPool],
+        prices: &P,
+        now: u64,
+    ) -> Result<Self, ProgramError> {
+        let num_assets = (user_info.num_assets as usize).min(user_info.user_asset_info.len());
+        let mut positions = Vec::with_capacity(num_assets);
+        for asset in user_info.user_asset_info[..num_assets].iter() {
+            let pool = pools
+                .iter()
+                .find(|pool| pool.pool_id == asset.pool_id)
+                .ok_or(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_POOL))?;
+            let price = prices
+                .native_price(asset.pool_id)
+                .ok_or(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_PRICE))?;
+            let current = asset.fast_forwarded(pool, now);
+            let deposit_amount = current.deposit_amount.to_native_amount();
+            let borrow_amount = current.borrow_amount.to_native_amount();
+            positions.push(AssetPosition {
+                pool_id: asset.pool_id,
+                use_as_collateral: asset.use_as_collateral != 0,
+                ltv: pool.ltv,
+                deposit_amount,
+                borrow_amount,
+                deposit_value: deposit_amount as f64 * price,
+                borrow_value: borrow_amount as f64 * price,
+            });
+        }
+
+        let assist = &user_info.assist;
+        let assist_enabled = assist.assist_mode != consts::ASSIST_MODE_DISABLED;
+        Ok(Portfolio {
+            positions,
+            assist_trigger_limit: Some(assist.self_deleverage_factor).filter(|_| assist_enabled),
+            assist_target_limit: Some(assist.post_deleverage_factor).filter(|_| assist_enabled),
+        })
+    }
+
+    pub fn position(&self, pool_id: u8) -> Option<&AssetPosition> {
+        self.positions
+            .iter()
+            .find(|position| position.pool_id == pool_id)
+    }
+
+    pub fn total_deposit_value(&self) -> f64 {
+        self.positions
+            .iter()
+            .map(|position| position.deposit_value)
+            .sum()
+    }
+
+    pub fn total_borrow_value(&self) -> f64 {
+        self.positions
+            .iter()
+            .map(|position| position.borrow_value)
+            .sum()
+    }
+
+    pub fn total_collateral_value(&self) -> f64 {
+        self.positions
+            .iter()
+            .map(|position| position.collateral_value())
+            .sum()
+    }
+
+    // maximum total borrow value, maxBorrowAllowed in sdk-ts
+    pub fn borrow_power(&self) -> f64 {
+        consts::SAFE_LIMIT * self.total_collateral_value()
+    }
+
+    pub fn remaining_borrow_power(&self) -> f64 {
+        (self.borrow_power() - self.total_borrow_value()).max(0.0)
+    }
+
+    // 0 without borrows, infinite for borrows without collateral
+    pub fn collateral_ratio(&self) -> f64 {
+        let borrow = self.total_borrow_value();
+        if borrow == 0.0 {
+            return 0.0;
+        }
+        let collateral = self.total_collateral_value();
+        if collateral == 0.0 {
+            return f64::INFINITY;
+        }
+        borrow / collateral
+    }
+
+    /**
+     * Fraction the collateral value can drop by (or borrow value grow by, relative to
+     * LIQUIDATION_LIMIT) before the user can be liquidated. 1 without borrows, 0 once liquidatable.
+     */
+    pub fn liquidation_distance(&self) -> f64 {
+        (1.0 - self.collateral_ratio() / consts::LIQUIDATION_LIMIT).max(0.0)
+    }
+
+    pub fn is_liquidatable(&self) -> bool {
+        self.collateral_ratio() >= consts::LIQUIDATION_LIMIT
+    }
+
+    pub fn is_assist_triggered(&self) -> bool {
+        match self.assist_trigger_limit {
+            Some(limit) => self.collateral_ratio() >= limit,
+            None => false,
+        }
+    }
+
+    /**
+     * Valid extern_liquidate pairs of a liquidatable user, most profitable first, empty if the user
+     * is not liquidatable. The liquidator receives the collateral pool's
+     * PoolConfig::liquidation_discount on top of the repaid value, so collateral in pools without
+     * a discount (or missing from config) is skipped.
+     * Each quote repays as much as possible without bringing the collateral ratio below
+     * ESTIMATED_LIQUIDATION_TARGET, limited by the user's debt in the borrowed pool and deposit in
+     * the collateral pool. That target is a guess, see ESTIMATED_LIQUIDATION_TARGET.
+     */
+    pub fn liquidation_quotes(&self) -> Vec<LiquidationQuote> {
+        self.liquidation_quotes_with_discounts(|pool_id| {
+            config::pool_by_id(pool_id).map_or(0.0, |pool| pool.liquidation_discount)
+        })
+    }
+
+    // liquidation_quotes with the discounts of the pools of registry
+    pub fn liquidation_quotes_with_registry(
+        &self,
+        registry: &PoolRegistry,
+    ) -> Vec<LiquidationQuote> {
+        self.liquidation_quotes_with_discounts(|pool_id| {
+            registry
+                .by_id(pool_id)
+                .map_or(0.0, |pool| pool.liquidation_discount)
+        })
+    }
+
+    // liquidation_quotes with the discount of each collateral pool given by discount
+    pub fn liquidation_quotes_with_discounts<D: Fn(u8) -> f64>(
+        &self,
+        discount: D,
+    ) -> Vec<LiquidationQuote> {
+        if !self.is_liquidatable() {
+            return Vec::new();
+        }
+        let borrow_value = self.total_borrow_value();
+        let collateral_value = self.total_collateral_value();
+        let mut quotes = Vec::new();
+        for borrowed in self
+            .positions
+            .iter()
+            .filter(|position| position.borrow_amount > 0)
+        {
+            for collateral in self.positions.iter() {
+                let discount = discount(collateral.pool_id);
+                if collateral.collateral_value() <= 0.0
+                    || collateral.deposit_amount == 0
+                    || discount <= 0.0
+                {
+                    continue;
+                }
+                let borrow_price = borrowed.borrow_value / borrowed.borrow_amount as f64;
+                let collateral_price = collateral.deposit_value / collateral.deposit_amount as f64;
+
+                // repaying x of value removes x of borrow and ltv * (1 + discount) * x of collateral
+                let premium = 1.0 + discount;
+                let mut repay_value = borrowed
+                    .borrow_value
+                    .min(collateral.deposit_value / premium);
+                let target = ESTIMATED_LIQUIDATION_TARGET;
+                let denominator = 1.0 - target * collateral.ltv * premium;
+                if denominator > 0.0 {
+                    let excess_borrow = borrow_value - target * collateral_value;
+                    repay_value = repay_value.min(excess_borrow / denominator);
+                }
+
+                let repay_amount = (repay_value / borrow_price).floor() as u64;
+                let repay_amount = repay_amount.min(borrowed.borrow_amount);
+                let received_value = repay_amount as f64 * borrow_price * premium;
+                let collateral_amount = (received_value / collateral_price).floor() as u64;
+                let collateral_amount = collateral_amount.min(collateral.deposit_amount);
+                if repay_amount == 0 || collateral_amount == 0 {
+                    continue;
+                }
+                quotes.push(LiquidationQuote {
+                    borrowed_pool_id: borrowed.pool_id,
+                    collateral_pool_id: collateral.pool_id,
+                    repay_amount,
+                    collateral_amount,
+                    profit: collateral_amount as f64 * collateral_price
+                        - repay_amount as f64 * borrow_price,
+                });
+            }
+        }
+        quotes.sort_by(|a, b| {
+            b.profit
+                .partial_cmp(&a.profit)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        quotes
+    }
+
+    /**
+     * Largest native amount of pool that can be withdrawn at now without ERR_INSUFFICIENT_DEPOSIT,
+     * ERR_POOL_NO_FREE_FUND or ERR_WITHDRAWL_BELOW_MIN_COLLATERAL_RATIO: bounded by the user's
+     * deposit, the pool's free liquidity and, for collateral, by keeping the collateral ratio at or
+     * below SAFE_LIMIT. 0 for a disabled pool. now should be the time the portfolio was built for.
+     */
+    pub fn max_withdraw_amount(&self, pool: &AssetPool, now: u64) -> u64 {
+        let position = match self.position(pool.pool_id) {
+            Some(position) if pool.is_disabled == 0 => position,
+            _ => return 0,
+        };
+        let mut max_amount = position.deposit_amount.min(pool.free_liquidity(now));
+
+        let borrow_value = self.total_borrow_value();
+        let collateral_per_native = if position.deposit_amount == 0 {
+            0.0
+        } else {
+            position.collateral_value() / position.deposit_amount as f64
+        };
+        if borrow_value > 0.0 && collateral_per_native > 0.0 {
+            let spare_collateral =
+                self.total_collateral_value() - borrow_value / consts::SAFE_LIMIT;
+            let collateral_limit =
+                (spare_collateral.max(0.0) / collateral_per_native).floor() as u64;
+            max_amount = max_amount.min(collateral_limit);
+        }
+        max_amount
+    }
+
+    /**
+     * Largest native amount of pool that can be borrowed at now without ERR_INSUFFICIENT_BORROW_POWER
+     * or ERR_POOL_NO_FREE_FUND: bounded by the remaining borrow power and the pool's free liquidity.
+     * 0 for a disabled pool or one that doesn't allow borrowing (LP pools among others),
+     * ERR_INCORRECT_ASSET_PRICE if prices has no positive price for the pool.
+     */
+    pub fn max_borrow_amount<P: PriceSource + ?Sized>(
+        &self,
+        pool: &AssetPool,
+        prices: &P,
+        now: u64,
+    ) -> Result<u64, ProgramError> {
+        if pool.is_disabled != 0 || !pool.allow_borrow() {
+            return Ok(0);
+        }
+        // a zero price is a missing price, not unlimited borrow power
+        let price = prices
+            .native_price(pool.pool_id)
+            .filter(|price| *price > 0.0)
+            .ok_or(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_PRICE))?;
+        let power_limit = (self.remaining_borrow_power() / price).floor() as u64;
+        Ok(power_limit.min(pool.free_liquidity(now)))
+    }
+}
+
+#[cfg(test)]
+pub mod portfolio_test {
+    use super::*;
+    use crate::state::{RawAmt, UserAssetInfo};
+
+    fn asset(pool_id: u8, use_as_collateral: u8, deposit: u64, borrow: u64) -> UserAssetInfo {
+        UserAssetInfo {
+            pool_id,
+            use_as_collateral,
+            deposit_amount: RawAmt::from_native_amount(deposit),
+            deposit_index: 1.0,
+            borrow_amount: RawAmt::from_native_amount(borrow),
+            borrow_index: 1.0,
+            ..UserAssetInfo::default()
+        }
+    }
+
+    // prices per whole token from USD per native unit, so values below are easy to follow
+    fn per_token_prices(native_prices: &[(u8, f64)]) -> HashMap<u8, f64> {
+        native_prices
+            .iter()
+            .map(|(pool_id, price)| {
+                let decimals = config::pool_by_id(*pool_id).unwrap().decimals;
+                (*pool_id, price * 10f64.powi(decimals as i32))
+            })
+            .collect()
+    }
+
+    fn pool(pool_id: u8, ltv: f64) -> AssetPool {
+        AssetPool {
+            pool_id,
+            ltv,
+            deposit_index: 1.0,
+            borrow_index: 1.0,
+            flags: consts::POOL_FLAG_ALLOW_BORROW,
+            ..AssetPool::default()
+        }
+    }
+
+    #[test]
+    fn test_borrow_power() {
+        let mut user_info = UserInfo {
+            num_assets: 3,
+            ..UserInfo::default()
+        };
+        user_info.user_asset_info[0] = asset(0, 1, 1_000, 0);
+        user_info.user_asset_info[1] = asset(3, 0, 1_000, 0);
+        user_info.user_asset_info[2] = asset(1, 0, 0, 300);
+        user_info.assist.assist_mode = consts::ASSIST_MODE_STABLE_ONLY;
+        user_info.assist.self_deleverage_factor = 0.5;
+
+        let pools = [pool(0, 0.8), pool(1, 0.8), pool(3, 0.5)];
+        let prices = per_token_prices(&[(0, 1.0), (1, 2.0), (3, 1.0)]);
+        let portfolio = Portfolio::new(&user_info, &pools, &prices, 0).unwrap();
+
+        assert_eq!(portfolio.total_deposit_value(), 2_000.0);
+        // pool 3 is not used as collateral
+        assert_eq!(portfolio.total_collateral_value(), 800.0);
+        assert_eq!(portfolio.total_borrow_value(), 600.0);
+        assert_eq!(portfolio.borrow_power(), 720.0);
+        assert_eq!(portfolio.remaining_borrow_power(), 120.0);
+        assert_eq!(portfolio.collateral_ratio(), 0.75);
+        assert!((portfolio.liquidation_distance() - (1.0 - 0.75 / 1.01)).abs() < 1e-12);
+        assert!(!portfolio.is_liquidatable());
+        assert!(portfolio.is_assist_triggered());
+
+        let missing_price = |pool_id: u8| if pool_id == 1 { None } else { Some(1.0) };
+        assert_eq!(
+            Portfolio::new(&user_info, &pools, &missing_price, 0),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_PRICE))
+        );
+        assert_eq!(
+            Portfolio::new(&user_info, &pools[..2], &prices, 0),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_POOL))
+        );
+    }
+
+    #[test]
+    fn test_max_withdraw_and_borrow() {
+        let mut user_info = UserInfo {
+            num_assets: 3,
+            ..UserInfo::default()
+        };
+        user_info.user_asset_info[0] = asset(0, 1, 1_000, 0);
+        user_info.user_asset_info[1] = asset(3, 0, 1_000, 0);
+        user_info.user_asset_info[2] = asset(1, 0, 0, 300);
+
+        let mut pools = [pool(0, 0.8), pool(1, 0.8), pool(3, 0.5)];
+        pools[0].deposit_amount = RawAmt::from_native_amount(10_000);
+        pools[1].deposit_amount = RawAmt::from_native_amount(10_000);
+        pools[1].borrow_amount = RawAmt::from_native_amount(9_950);
+        pools[2].deposit_amount = RawAmt::from_native_amount(10_000);
+        let prices = per_token_prices(&[(0, 1.0), (1, 2.0), (3, 1.0)]);
+        let portfolio = Portfolio::new(&user_info, &pools, &prices, 0).unwrap();
+
+        // borrow value 600 needs 600 / 0.9 of collateral, 800 - 666.67 spare at 0.8 per native
+        assert_eq!(portfolio.max_withdraw_amount(&pools[0], 0), 166);
+        // not used as collateral
+        assert_eq!(portfolio.max_withdraw_amount(&pools[2], 0), 1_000);
+        // no deposit
+        assert_eq!(portfolio.max_withdraw_amount(&pools[1], 0), 0);
+
+        // 120 of borrow power at 1 per native, 60 at 2 per native but only 50 free
+        assert_eq!(portfolio.max_borrow_amount(&pools[0], &prices, 0), Ok(120));
+        assert_eq!(portfolio.max_borrow_amount(&pools[1], &prices, 0), Ok(50));
+        assert_eq!(
+            portfolio.max_borrow_amount(&pools[0], &HashMap::new(), 0),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_PRICE))
+        );
+
+        let zero_price = |pool_id: u8| if pool_id == 0 { Some(0.0) } else { Some(1.0) };
+        assert_eq!(
+            portfolio.max_borrow_amount(&pools[0], &zero_price, 0),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_PRICE))
+        );
+
+        // LP pools and pools without ALLOW_BORROW can't be borrowed from
+        let mut no_borrow = pools[0];
+        no_borrow.flags = 0;
+        assert_eq!(portfolio.max_borrow_amount(&no_borrow, &prices, 0), Ok(0));
+        no_borrow.flags = consts::POOL_FLAG_IS_LP;
+        assert_eq!(portfolio.max_borrow_amount(&no_borrow, &prices, 0), Ok(0));
+
+        pools[2].borrow_amount = RawAmt::from_native_amount(9_500);
+        assert_eq!(portfolio.max_withdraw_amount(&pools[2], 0), 500);
+        pools[0].is_disabled = 1;
+        assert_eq!(portfolio.max_withdraw_amount(&pools[0], 0), 0);
+        assert_eq!(portfolio.max_borrow_amount(&pools[0], &prices, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_liquidation_quotes() {
+        let mut user_info = UserInfo {
+            num_assets: 4,
+            ..UserInfo::default()
+        };
+        user_info.user_asset_info[0] = asset(0, 1, 1_000, 0);
+        user_info.user_asset_info[1] = asset(3, 1, 1_000, 0);
+        user_info.user_asset_info[2] = asset(4, 0, 1_000, 0);
+        user_info.user_asset_info[3] = asset(1, 0, 0, 700);
+
+        let pools = [pool(0, 0.8), pool(1, 0.8), pool(3, 0.5), pool(4, 0.8)];
+        let mut native_prices = [(0, 1.0), (1, 1.0), (3, 0.1), (4, 1.0)];
+        let prices = per_token_prices(&native_prices);
+        let portfolio = Portfolio::new(&user_info, &pools, &prices, 0).unwrap();
+        // 700 / (800 + 50)
+        assert!(!portfolio.is_liquidatable());
+        assert!(portfolio.liquidation_quotes().is_empty());
+
+        native_prices[1].1 = 1.3;
+        let prices = per_token_prices(&native_prices);
+        let portfolio = Portfolio::new(&user_info, &pools, &prices, 0).unwrap();
+        assert!(portfolio.is_liquidatable());
+        let quotes = portfolio.liquidation_quotes();
+        // pool 4 is not used as collateral, pool 3 only holds 100 of value
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(
+            (quotes[0].borrowed_pool_id, quotes[0].collateral_pool_id),
+            (1, 0)
+        );
+        assert_eq!(
+            (quotes[1].borrowed_pool_id, quotes[1].collateral_pool_id),
+            (1, 3)
+        );
+
+        // both collateral pools have a 4% discount:
+        // (910 - 0.9 * 850) / (1 - 0.9 * 0.8 * 1.04) = 577.23 of value, 444 native at 1.3
+        assert_eq!(quotes[0].repay_amount, 444);
+        assert_eq!(quotes[0].collateral_amount, 600);
+        assert!(quotes[0].profit > 0.0);
+        // capped by the 100 of collateral value in pool 3
+        assert_eq!(quotes[1].repay_amount, 73);
+        assert_eq!(quotes[1].collateral_amount, 986);
+        assert!(quotes[1].profit > 0.0 && quotes[1].profit < quotes[0].profit);
+
+        // collateral without a discount can't be seized
+        let no_discount_in_0 = |pool_id: u8| if pool_id == 0 { 0.0 } else { 0.04 };
+        let quotes_3 = portfolio.liquidation_quotes_with_discounts(no_discount_in_0);
+        assert_eq!(quotes_3, vec![quotes[1]]);
+        let mut registry = PoolRegistry::builtin();
+        assert_eq!(
+            portfolio.liquidation_quotes_with_registry(&registry),
+            quotes
+        );
+        registry.pools.retain(|pool| pool.pool_id != 0);
+        assert_eq!(
+            portfolio.liquidation_quotes_with_registry(&registry),
+            quotes_3
+        );
+
+        let after = UserInfo {
+            num_assets: 4,
+            user_asset_info: {
+                let mut assets = user_info.user_asset_info;
+                assets[0] = asset(0, 1, 1_000 - quotes[0].collateral_amount, 0);
+                assets[3] = asset(1, 0, 0, 700 - quotes[0].repay_amount);
+                assets
+            },
+            ..user_info
+        };
+        // back to ESTIMATED_LIQUIDATION_TARGET, up to rounding to native amounts
+        let ratio = Portfolio::new(&after, &pools, &prices, 0)
+            .unwrap()
+            .collateral_ratio();
+        assert!((ratio - ESTIMATED_LIQUIDATION_TARGET).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_empty_portfolio() {
+        let portfolio = Portfolio::new(&UserInfo::default(), &[], &HashMap::new(), 0).unwrap();
+        assert_eq!(portfolio.collateral_ratio(), 0.0);
+        assert_eq!(portfolio.liquidation_distance(), 1.0);
+        assert_eq!(portfolio.assist_trigger_limit, None);
+    }
+}
@@ -0,0 +1,547 @@
+use crate::config::{self, ConfigError, PoolConfig};
+use crate::consts;
+use crate::env::ApricotEnv;
+use solana_program::pubkey::Pubkey;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/**
+ * Pools of one Apricot deployment. builtin() is the compiled-in config::POOLS on mainnet; with the
+ * registry-file feature a registry can also be loaded from a JSON or TOML file (see from_file), so
+ * new pools or test deployments don't need a new crate release.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolRegistry {
+    pub env: ApricotEnv,
+    pub pools: Vec<PoolConfig>,
+    pub aliases: Vec<(Cow<'static, str>, u8)>,
+}
+
+impl Default for PoolRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PoolRegistry {
+    pub fn builtin() -> Self {
+        PoolRegistry {
+            env: ApricotEnv::mainnet(),
+            pools: config::POOLS.to_vec(),
+            aliases: config::POOL_ALIASES
+                .iter()
+                .map(|(alias, pool_id)| (Cow::Borrowed(*alias), *pool_id))
+                .collect(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PoolConfig> {
+        self.pools.iter()
+    }
+
+    pub fn by_id(&self, pool_id: u8) -> Option<&PoolConfig> {
+        self.iter().find(|pool| pool.pool_id == pool_id)
+    }
+
+    // case-insensitive, also accepts aliases
+    pub fn by_name(&self, name: &str) -> Option<&PoolConfig> {
+        let aliases = self
+            .aliases
+            .iter()
+            .map(|(alias, pool_id)| (alias.as_ref(), *pool_id));
+        config::find_pool_by_name(&self.pools, aliases, name)
+    }
+
+    pub fn by_mint(&self, mint: &Pubkey) -> Option<&PoolConfig> {
+        self.iter().find(|pool| pool.mint == *mint)
+    }
+
+    pub fn by_asset_pool_k(&self, asset_pool: &Pubkey) -> Option<&PoolConfig> {
+        self.iter()
+            .find(|pool| pool.asset_pool_k(&self.env) == *asset_pool)
+    }
+
+    pub fn try_pool_id_by_name(&self, name: &str) -> Result<u8, ConfigError> {
+        self.by_name(name)
+            .map(|pool| pool.pool_id)
+            .ok_or_else(|| ConfigError::UnknownPoolName(name.to_string()))
+    }
+
+    pub fn try_mint_by_pool_id(&self, pool_id: u8) -> Result<Pubkey, ConfigError> {
+        self.by_id(pool_id)
+            .map(|pool| pool.mint)
+            .ok_or(ConfigError::UnknownPoolId(pool_id))
+    }
+
+    pub fn try_pool_id_by_mint(&self, mint: &Pubkey) -> Result<u8, ConfigError> {
+        self.by_mint(mint)
+            .map(|pool| pool.pool_id)
+            .ok_or(ConfigError::UnknownMint(*mint))
+    }
+
+    // the wallet's associated token account of the pool's mint, see consts::try_get_user_spl_k
+    pub fn try_get_user_spl_k(
+        &self,
+        user_wallet_key: &Pubkey,
+        pool_id: u8,
+    ) -> Result<Pubkey, ConfigError> {
+        let mint = self.try_mint_by_pool_id(pool_id)?;
+        Ok(consts::get_associated_token_k(user_wallet_key, &mint))
+    }
+
+    // configs of the (left, right) component pools of an LP pool
+    pub fn lp_component_pools(&self, pool_id: u8) -> Option<(&PoolConfig, &PoolConfig)> {
+        let (left, right) = self.by_id(pool_id)?.lp_components?;
+        Some((self.by_id(left)?, self.by_id(right)?))
+    }
+
+    /**
+     * Pool ids, mints and names (case-insensitive, aliases included) must be unique, aliases must
+     * point at a pool, only LP pools can have a dex, LP accounts or LP components and components
+     * must be non-LP pools of the registry. decimals can't exceed 19 and liquidation_discount must
+     * be in [0, 1).
+     */
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |reason: String| Err(ConfigError::InvalidRegistry(reason));
+        let mut pool_ids = HashSet::new();
+        let mut mints = HashSet::new();
+        let mut names = HashSet::new();
+        for pool in self.iter() {
+            if !pool_ids.insert(pool.pool_id) {
+                return invalid(format!("duplicate pool id {}", pool.pool_id));
+            }
+            if !mints.insert(pool.mint) {
+                return invalid(format!("duplicate mint {}", pool.mint));
+            }
+            if !names.insert(pool.name.to_lowercase()) {
+                return invalid(format!("duplicate name {}", pool.name));
+            }
+            // 10^decimals has to fit the u64 native amounts
+            if pool.decimals > 19 {
+                return invalid(format!("{} has {} decimals", pool.name, pool.decimals));
+            }
+            if !(0.0..1.0).contains(&pool.liquidation_discount) {
+                let discount = pool.liquidation_discount;
+                return invalid(format!(
+                    "{} has liquidation discount {}",
+                    pool.name, discount
+                ));
+            }
+            let lp_only = pool.dex.is_some() || pool.lp_accounts.is_some();
+            if !pool.is_lp() && (lp_only || pool.lp_components.is_some()) {
+                return invalid(format!("{} is not an LP pool", pool.name));
+            }
+        }
+        for pool in self.iter() {
+            if let Some((left, right)) = pool.lp_components {
+                for component in [left, right].iter() {
+                    let reason = format!("invalid component {} of {}", component, pool.name);
+                    match self.by_id(*component) {
+                        Some(other) if !other.is_lp() => {}
+                        _ => return invalid(reason),
+                    }
+                }
+            }
+        }
+        for (alias, pool_id) in self.aliases.iter() {
+            if !names.insert(alias.to_lowercase()) {
+                return invalid(format!("duplicate name {}", alias));
+            }
+            if !pool_ids.contains(pool_id) {
+                return invalid(format!("alias {} of unknown pool id {}", alias, pool_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registry-file")]
+mod file {
+    use super::*;
+    use crate::config::{LpAccounts, OrcaDoubleDipAccounts, TokenCategory};
+    use crate::swap::SwapVenue;
+    use serde::Deserialize;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    /**
+     * File schema, one entry per pool. This is not the shape of configs.ts in sdk-ts, which keeps
+     * one map per field keyed by TokenID; an entry gathers the values of one TokenID instead:
+     * token_id is the TokenID, pool_id/mint/category/dex come from POOL_IDS/MINTS/CATEGORY/
+     * LP_TO_DEX, decimals is the log10 of DECIMAL_MULT and lp_to_lr the LP_TO_LR pair of
+     * component token ids, liquidation_discount the LIQUIDATION_DISCOUNT entry. symbol defaults
+     * to token_id, liquidation_discount to 0 and env, the deployment (ApricotEnv::new), to mainnet.
+     */
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct RegistryFile {
+        env: Option<EnvEntry>,
+        pools: Vec<PoolEntry>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct EnvEntry {
+        program_id: String,
+        admin: String,
+        lm_apt_vault: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct PoolEntry {
+        token_id: String,
+        symbol: Option<String>,
+        pool_id: u8,
+        mint: String,
+        decimals: u8,
+        category: CategoryEntry,
+        #[serde(default)]
+        aliases: Vec<String>,
+        dex: Option<DexEntry>,
+        lp_accounts: Option<LpAccountsEntry>,
+        lp_to_lr: Option<[String; 2]>,
+        #[serde(default)]
+        liquidation_discount: f64,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum CategoryEntry {
+        Volatile,
+        Stable,
+        Lp,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum DexEntry {
+        Raydium,
+        Saber,
+        Orca,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase", deny_unknown_fields)]
+    enum LpAccountsEntry {
+        Saber {
+            miner: String,
+        },
+        Orca {
+            user_farm_state: String,
+            reward_orca_account: String,
+            double_dip: Option<OrcaDoubleDipEntry>,
+        },
+        Raydium {
+            reward_ray_account: String,
+            reward_second_account: String,
+            user_ledger_account: String,
+        },
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct OrcaDoubleDipEntry {
+        user_farm_state: String,
+        reward_dd_account: String,
+        floating_lp: String,
+    }
+
+    fn pubkey(key: &str) -> Result<Pubkey, ConfigError> {
+        Pubkey::from_str(key)
+            .map_err(|_| ConfigError::InvalidRegistry(format!("invalid pubkey {}", key)))
+    }
+
+    impl LpAccountsEntry {
+        fn to_lp_accounts(&self) -> Result<LpAccounts, ConfigError> {
+            Ok(match self {
+                LpAccountsEntry::Saber { miner } => LpAccounts::Saber {
+                    miner: pubkey(miner)?,
+                },
+                LpAccountsEntry::Orca {
+                    user_farm_state,
+                    reward_orca_account,
+                    double_dip,
+                } => LpAccounts::Orca {
+                    user_farm_state: pubkey(user_farm_state)?,
+                    reward_orca_account: pubkey(reward_orca_account)?,
+                    double_dip: match double_dip {
+                        Some(entry) => Some(OrcaDoubleDipAccounts {
+                            user_farm_state: pubkey(&entry.user_farm_state)?,
+                            reward_dd_account: pubkey(&entry.reward_dd_account)?,
+                            floating_lp: pubkey(&entry.floating_lp)?,
+                        }),
+                        None => None,
+                    },
+                },
+                LpAccountsEntry::Raydium {
+                    reward_ray_account,
+                    reward_second_account,
+                    user_ledger_account,
+                } => LpAccounts::Raydium {
+                    reward_ray_account: pubkey(reward_ray_account)?,
+                    reward_second_account: pubkey(reward_second_account)?,
+                    user_ledger_account: pubkey(user_ledger_account)?,
+                },
+            })
+        }
+    }
+
+    impl RegistryFile {
+        fn to_registry(&self) -> Result<PoolRegistry, ConfigError> {
+            let env = match &self.env {
+                Some(env) => ApricotEnv::new(
+                    &pubkey(&env.program_id)?,
+                    &pubkey(&env.admin)?,
+                    &pubkey(&env.lm_apt_vault)?,
+                ),
+                None => ApricotEnv::mainnet(),
+            };
+            let mut pools = Vec::with_capacity(self.pools.len());
+            let mut aliases = Vec::new();
+            for entry in self.pools.iter() {
+                pools.push(PoolConfig {
+                    pool_id: entry.pool_id,
+                    name: Cow::Owned(entry.token_id.clone()),
+                    symbol: Cow::Owned(entry.symbol.as_ref().unwrap_or(&entry.token_id).clone()),
+                    mint: pubkey(&entry.mint)?,
+                    decimals: entry.decimals,
+                    category: match entry.category {
+                        CategoryEntry::Volatile => TokenCategory::Volatile,
+                        CategoryEntry::Stable => TokenCategory::Stable,
+                        CategoryEntry::Lp => TokenCategory::Lp,
+                    },
+                    dex: entry.dex.as_ref().map(|dex| match dex {
+                        DexEntry::Raydium => SwapVenue::Raydium,
+                        DexEntry::Saber => SwapVenue::Saber,
+                        DexEntry::Orca => SwapVenue::Orca,
+                    }),
+                    lp_accounts: match &entry.lp_accounts {
+                        Some(lp_accounts) => Some(lp_accounts.to_lp_accounts()?),
+                        None => None,
+                    },
+                    lp_components: None,
+                    liquidation_discount: entry.liquidation_discount,
+                });
+                aliases.extend(
+                    entry
+                        .aliases
+                        .iter()
+                        .map(|alias| (Cow::Owned(alias.clone()), entry.pool_id)),
+                );
+            }
+            let mut registry = PoolRegistry {
+                env,
+                pools,
+                aliases,
+            };
+            // components can be listed after the LP pool, so they are resolved once all are known
+            for (index, entry) in self.pools.iter().enumerate() {
+                if let Some([left, right]) = &entry.lp_to_lr {
+                    let components = (
+                        registry.try_pool_id_by_name(left)?,
+                        registry.try_pool_id_by_name(right)?,
+                    );
+                    registry.pools[index].lp_components = Some(components);
+                }
+            }
+            registry.validate()?;
+            Ok(registry)
+        }
+    }
+
+    impl PoolRegistry {
+        pub fn from_json_str(json: &str) -> Result<Self, ConfigError> {
+            serde_json::from_str::<RegistryFile>(json)
+                .map_err(|err| ConfigError::InvalidRegistry(err.to_string()))?
+                .to_registry()
+        }
+
+        pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+            toml::from_str::<RegistryFile>(toml)
+                .map_err(|err| ConfigError::InvalidRegistry(err.to_string()))?
+                .to_registry()
+        }
+
+        // TOML for a .toml extension, JSON otherwise
+        pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+            let path = path.as_ref();
+            let content = std::fs::read_to_string(path).map_err(|err| {
+                ConfigError::InvalidRegistry(format!("{}: {}", path.display(), err))
+            })?;
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("toml") => Self::from_toml_str(&content),
+                _ => Self::from_json_str(&content),
+            }
+        }
+
+        // the compiled-in registry unless a file is given
+        pub fn load<P: AsRef<Path>>(path: Option<P>) -> Result<Self, ConfigError> {
+            match path {
+                Some(path) => Self::from_file(path),
+                None => Ok(Self::builtin()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod registry_test {
+    use super::*;
+    use crate::config::{btc, msol, sol_usdc_orca};
+
+    #[test]
+    fn test_builtin() {
+        let registry = PoolRegistry::default();
+        assert_eq!(registry.validate(), Ok(()));
+        assert_eq!(registry.pools.len(), config::POOLS.len());
+        assert_eq!(registry.try_pool_id_by_name("msol"), Ok(msol::POOL_ID));
+        assert_eq!(registry.try_pool_id_by_name("soBTC"), Ok(btc::POOL_ID));
+        assert_eq!(registry.try_mint_by_pool_id(msol::POOL_ID), Ok(msol::ID));
+        let wallet = Pubkey::new_unique();
+        let user_spl = consts::get_user_spl_k(&wallet, msol::POOL_ID);
+        assert_eq!(
+            registry.try_get_user_spl_k(&wallet, msol::POOL_ID),
+            Ok(user_spl)
+        );
+        assert_eq!(
+            registry.try_pool_id_by_mint(&sol_usdc_orca::ID),
+            Ok(sol_usdc_orca::POOL_ID)
+        );
+        let asset_pool = registry.env.asset_pool_k(btc::POOL_ID);
+        assert_eq!(
+            registry.by_asset_pool_k(&asset_pool).unwrap().pool_id,
+            btc::POOL_ID
+        );
+
+        let (left, right) = registry.lp_component_pools(sol_usdc_orca::POOL_ID).unwrap();
+        assert_eq!((left.name.as_ref(), right.name.as_ref()), ("SOL", "USDC"));
+
+        let mut registry = PoolRegistry::builtin();
+        registry.pools[0].lp_components = Some((msol::POOL_ID, msol::POOL_ID));
+        assert!(registry.validate().is_err());
+
+        let mut registry = PoolRegistry::builtin();
+        registry.aliases.push((Cow::Borrowed("BTC"), msol::POOL_ID));
+        assert!(registry.validate().is_err());
+
+        let mut registry = PoolRegistry::builtin();
+        registry.pools[0].decimals = 20;
+        assert!(registry.validate().is_err());
+        registry.pools[0].decimals = 19;
+        assert_eq!(registry.validate(), Ok(()));
+        for discount in [-0.01, 1.0, f64::NAN].iter() {
+            registry.pools[0].liquidation_discount = *discount;
+            assert!(registry.validate().is_err());
+        }
+    }
+
+    #[cfg(feature = "registry-file")]
+    #[test]
+    fn test_from_str() {
+        use crate::config::{LpAccounts, TokenCategory};
+        use crate::consts;
+
+        let json = format!(
+            r#"{{
+                "env": {{
+                    "program_id": "{}",
+                    "admin": "{}",
+                    "lm_apt_vault": "{}"
+                }},
+                "pools": [
+                    {{ "token_id": "SOL", "pool_id": 4, "mint": "{}", "decimals": 9,
+                       "category": "volatile", "aliases": ["wSOL"] }},
+                    {{ "token_id": "SOL_USDC_ORCA", "symbol": "SOL-USDC", "pool_id": 15,
+                       "mint": "{}", "decimals": 6, "category": "lp", "dex": "orca",
+                       "lp_accounts": {{ "orca": {{
+                           "user_farm_state": "{}", "reward_orca_account": "{}" }} }},
+                       "lp_to_lr": ["SOL", "USDC"] }},
+                    {{ "token_id": "USDC", "pool_id": 3, "mint": "{}", "decimals": 6,
+                       "category": "stable" }}
+                ]
+            }}"#,
+            consts::alpha::program::ID,
+            consts::alpha::admin::ID,
+            consts::alpha::lm_apt_vault::ID,
+            config::sol::ID,
+            sol_usdc_orca::ID,
+            sol_usdc_orca::user_farm_state::ID,
+            sol_usdc_orca::reward_orca_account::ID,
+            config::usdc::ID,
+        );
+        let registry = PoolRegistry::from_json_str(&json).unwrap();
+        assert_eq!(registry.env, ApricotEnv::alpha());
+        assert_eq!(registry.try_pool_id_by_name("wsol"), Ok(4));
+        assert_eq!(registry.by_id(15), config::pool_by_id(15));
+        assert_eq!(registry.by_id(4).unwrap().category, TokenCategory::Volatile);
+        assert_eq!(registry.by_id(4).unwrap().symbol, "SOL");
+        let (left, right) = registry.lp_component_pools(15).unwrap();
+        assert_eq!((left.pool_id, right.pool_id), (4, 3));
+
+        let toml = format!(
+            r#"
+            [[pools]]
+            token_id = "BTC"
+            pool_id = 0
+            mint = "{}"
+            decimals = 6
+            category = "volatile"
+
+            [[pools]]
+            token_id = "USDT_USDC_SABER"
+            pool_id = 5
+            mint = "{}"
+            decimals = 6
+            category = "lp"
+            dex = "saber"
+            lp_accounts = {{ saber = {{ miner = "{}" }} }}
+            "#,
+            btc::ID,
+            config::usdt_usdc_saber::ID,
+            config::usdt_usdc_saber::miner::ID,
+        );
+        let registry = PoolRegistry::from_toml_str(&toml).unwrap();
+        assert_eq!(registry.env, ApricotEnv::mainnet());
+        assert_eq!(
+            registry.by_id(5).unwrap().lp_accounts,
+            Some(LpAccounts::Saber {
+                miner: config::usdt_usdc_saber::miner::ID
+            })
+        );
+
+        let duplicate = r#"{ "pools": [
+            { "token_id": "A", "pool_id": 1, "mint": "11111111111111111111111111111111",
+              "decimals": 6, "category": "stable" },
+            { "token_id": "B", "pool_id": 1, "mint": "SysvarRent111111111111111111111111111111111",
+              "decimals": 6, "category": "stable" }
+        ] }"#;
+        assert_eq!(
+            PoolRegistry::from_json_str(duplicate),
+            Err(ConfigError::InvalidRegistry(
+                "duplicate pool id 1".to_string()
+            ))
+        );
+        assert!(PoolRegistry::from_json_str(r#"{ "pools": [ { "pool_id": 1 } ] }"#).is_err());
+        let too_many_decimals = r#"{ "pools": [
+            { "token_id": "A", "pool_id": 1, "mint": "11111111111111111111111111111111",
+              "decimals": 30, "category": "stable" }
+        ] }"#;
+        assert_eq!(
+            PoolRegistry::from_json_str(too_many_decimals),
+            Err(ConfigError::InvalidRegistry(
+                "A has 30 decimals".to_string()
+            ))
+        );
+        let unknown_component = json.replace(r#""USDC"]"#, r#""USDT"]"#);
+        assert_eq!(
+            PoolRegistry::from_json_str(&unknown_component),
+            Err(ConfigError::UnknownPoolName("USDT".to_string()))
+        );
+        assert!(PoolRegistry::load(Some("/nonexistent/pools.json")).is_err());
+        assert_eq!(
+            PoolRegistry::load(None::<&str>),
+            Ok(PoolRegistry::builtin())
+        );
+    }
+}
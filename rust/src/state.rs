@@ -1,13 +1,20 @@
-use crate::utils;
-use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
-use std::cell::Ref;
+use crate::amount::{NativeAmount, Rounding};
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::layout::{LayoutReader, LayoutWriter};
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::{Pack, Sealed},
+    pubkey::Pubkey,
+};
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter, Result as FormatResult};
 use std::ops::{Add, Mul, Sub};
 
 pub const NATIVE_RAW_SHIFT: usize = 24;
 
-#[repr(packed)]
-#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
 /**
  * RawAmt to accrue interest with boosted precision
 */
@@ -15,16 +22,128 @@ pub struct RawAmt {
     amt: u128,
 }
 
+// fixed point precision factor is converted to in RawAmt::mul_f64
+const FACTOR_SHIFT: u32 = 40;
+
 impl RawAmt {
+    pub const ZERO: RawAmt = RawAmt { amt: 0 };
+
+    // raw is the on-chain value, native amount << NATIVE_RAW_SHIFT
+    pub fn from_raw(raw: u128) -> RawAmt {
+        RawAmt { amt: raw }
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.amt
+    }
+
+    pub fn from_native_amount(native_amount: u64) -> RawAmt {
+        RawAmt {
+            amt: (native_amount as u128) << NATIVE_RAW_SHIFT,
+        }
+    }
+
+    // rounds down, like the contract when it transfers tokens
     pub fn to_native_amount(&self) -> u64 {
         (self.amt >> NATIVE_RAW_SHIFT) as u64
     }
+
+    // None if the rounded amount doesn't fit a u64
+    pub fn to_native_amount_rounded(&self, rounding: Rounding) -> Option<NativeAmount> {
+        let one = 1u128 << NATIVE_RAW_SHIFT;
+        let fraction = self.amt & (one - 1);
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => fraction > 0,
+            Rounding::Nearest => fraction >= one / 2,
+        };
+        let native = (self.amt >> NATIVE_RAW_SHIFT) + round_up as u128;
+        u64::try_from(native).ok().map(NativeAmount)
+    }
+
+    pub fn checked_add(&self, other: RawAmt) -> Option<RawAmt> {
+        self.amt.checked_add(other.amt).map(RawAmt::from_raw)
+    }
+
+    pub fn checked_sub(&self, other: RawAmt) -> Option<RawAmt> {
+        self.amt.checked_sub(other.amt).map(RawAmt::from_raw)
+    }
+
+    pub fn saturating_add(&self, other: RawAmt) -> RawAmt {
+        RawAmt::from_raw(self.amt.saturating_add(other.amt))
+    }
+
+    pub fn saturating_sub(&self, other: RawAmt) -> RawAmt {
+        RawAmt::from_raw(self.amt.saturating_sub(other.amt))
+    }
+
+    /**
+     * Scales by a factor (e.g. an index ratio), saturating at u128::MAX instead of overflowing.
+     * A negative or NaN factor gives ZERO, use checked_mul_f64 to tell those apart. An infinite
+     * factor saturates unless the amount is zero.
+     */
+    pub fn mul_f64(&self, factor: f64) -> RawAmt {
+        self.checked_mul_f64(factor).unwrap_or(RawAmt::ZERO)
+    }
+
+    // None for a negative or NaN factor
+    pub fn checked_mul_f64(&self, factor: f64) -> Option<RawAmt> {
+        if factor.is_nan() || factor < 0.0 {
+            return None;
+        }
+        if self.amt == 0 || factor == 0.0 {
+            return Some(RawAmt::ZERO);
+        }
+        if factor.is_infinite() {
+            return Some(RawAmt::from_raw(u128::MAX));
+        }
+        let scaled = factor * (1u64 << FACTOR_SHIFT) as f64;
+        if scaled >= u128::MAX as f64 {
+            // factors of 2^88 and more, too large for the fixed point, precision doesn't matter
+            return Some(RawAmt::from_raw((self.amt as f64 * factor) as u128));
+        }
+        let factor = scaled as u128;
+        let mask = (1u128 << FACTOR_SHIFT) - 1;
+        // (amt * factor) >> FACTOR_SHIFT in parts that can't overflow except for the first one,
+        // amt_low * factor_high is below 2^40 * 2^88
+        let (amt_high, amt_low) = (self.amt >> FACTOR_SHIFT, self.amt & mask);
+        let (factor_high, factor_low) = (factor >> FACTOR_SHIFT, factor & mask);
+        let high = amt_high.saturating_mul(factor);
+        let low = (amt_low * factor_high).saturating_add((amt_low * factor_low) >> FACTOR_SHIFT);
+        Some(RawAmt {
+            amt: high.saturating_add(low),
+        })
+    }
+}
+
+// native amount with the raw fraction truncated to 8 decimals
+impl Display for RawAmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let fraction = self.amt & ((1u128 << NATIVE_RAW_SHIFT) - 1);
+        let fraction = (fraction * 100_000_000) >> NATIVE_RAW_SHIFT;
+        write!(f, "{}.{:08}", self.amt >> NATIVE_RAW_SHIFT, fraction)
+    }
+}
+
+impl Sealed for RawAmt {}
+
+impl Pack for RawAmt {
+    const LEN: usize = 16;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        LayoutWriter::new(dst).u128(self.amt);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        Ok(RawAmt {
+            amt: LayoutReader::new(src).u128()?,
+        })
+    }
 }
 
 pub const MAX_ASSETS_PER_USER: usize = 16;
 
-#[repr(packed)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct UserAssetInfo {
     pub pool_id: u8,
     pub use_as_collateral: u8,
@@ -42,8 +161,75 @@ pub struct UserAssetInfo {
     pub reward_borrow_index: f64,
 }
 
+impl UserAssetInfo {
+    /**
+     * Moves deposit_amount/borrow_amount from the indexes they were last updated at to the pool's
+     * current indexes, like sdk-ts fastForwardUserAssetInfo. Accrue the pool first with
+     * AssetPool::accrued to get balances as of now instead of the pool's last update.
+     */
+    pub fn rebased(&self, pool: &AssetPool) -> UserAssetInfo {
+        let mut rebased = *self;
+        if self.deposit_index > 0.0 {
+            rebased.deposit_amount = self
+                .deposit_amount
+                .mul_f64(pool.deposit_index / self.deposit_index);
+        }
+        if self.borrow_index > 0.0 {
+            rebased.borrow_amount = self
+                .borrow_amount
+                .mul_f64(pool.borrow_index / self.borrow_index);
+        }
+        rebased.deposit_index = pool.deposit_index;
+        rebased.borrow_index = pool.borrow_index;
+        rebased
+    }
+
+    pub fn fast_forwarded(&self, pool: &AssetPool, now: u64) -> UserAssetInfo {
+        self.rebased(&pool.accrued(now))
+    }
+}
+
+impl Sealed for UserAssetInfo {}
+
+impl Pack for UserAssetInfo {
+    const LEN: usize = 114;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut w = LayoutWriter::new(dst);
+        w.u8(self.pool_id);
+        w.u8(self.use_as_collateral);
+        self.deposit_amount.pack_into_slice(w.take(RawAmt::LEN));
+        w.u64(self.deposit_interests);
+        w.f64(self.deposit_index);
+        w.f64(self.reward_deposit_amount);
+        w.f64(self.reward_deposit_index);
+        self.borrow_amount.pack_into_slice(w.take(RawAmt::LEN));
+        w.u64(self.borrow_interests);
+        w.f64(self.borrow_index);
+        w.f64(self.reward_borrow_amount);
+        w.f64(self.reward_borrow_index);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = LayoutReader::new(src);
+        Ok(UserAssetInfo {
+            pool_id: r.u8()?,
+            use_as_collateral: r.u8()?,
+            deposit_amount: RawAmt::unpack_from_slice(r.take(RawAmt::LEN)?)?,
+            deposit_interests: r.u64()?,
+            deposit_index: r.f64()?,
+            reward_deposit_amount: r.f64()?,
+            reward_deposit_index: r.f64()?,
+            borrow_amount: RawAmt::unpack_from_slice(r.take(RawAmt::LEN)?)?,
+            borrow_interests: r.u64()?,
+            borrow_index: r.f64()?,
+            reward_borrow_amount: r.f64()?,
+            reward_borrow_index: r.f64()?,
+        })
+    }
+}
+
 impl Display for UserAssetInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(
             f,
@@ -64,8 +250,7 @@ impl Display for UserAssetInfo {
     }
 }
 
-#[repr(packed)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct RewardInfo {
     pub vesting: [f64; 4], // retro vesting
     pub prev_week_apt: f64,
@@ -78,8 +263,45 @@ pub struct RewardInfo {
     pub available_wluna: f64,    // wormhole luna
 }
 
+impl Sealed for RewardInfo {}
+
+impl Pack for RewardInfo {
+    const LEN: usize = 13 * 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut w = LayoutWriter::new(dst);
+        for vesting in self.vesting.iter() {
+            w.f64(*vesting);
+        }
+        w.f64(self.prev_week_apt);
+        for unused in self.unused.iter() {
+            w.f64(*unused);
+        }
+        w.f64(self.vesting_apt);
+        w.f64(self.available_apt);
+        w.f64(self.available_mnde);
+        w.f64(self.available_wldo);
+        w.f64(self.available_b180socn);
+        w.f64(self.available_wluna);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = LayoutReader::new(src);
+        Ok(RewardInfo {
+            vesting: [r.f64()?, r.f64()?, r.f64()?, r.f64()?],
+            prev_week_apt: r.f64()?,
+            unused: [r.f64()?, r.f64()?],
+            vesting_apt: r.f64()?,
+            available_apt: r.f64()?,
+            available_mnde: r.f64()?,
+            available_wldo: r.f64()?,
+            available_b180socn: r.f64()?,
+            available_wluna: r.f64()?,
+        })
+    }
+}
+
 impl Display for RewardInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(
             f,
@@ -99,8 +321,135 @@ impl Display for RewardInfo {
     }
 }
 
-#[repr(packed)]
-#[derive(Copy, Clone)]
+pub const ASSIST_SEQUENCE_SIZE: usize = 8;
+// price triggered actions, (10 + 30) * 8 bytes in sdk-ts AccountParser.parseAssist
+pub const ASSIST_PRICE_TRIGGER_ACTIONS_SIZE: usize = (10 + 30) * 8;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AssistStatus {
+    // assist_mode is ASSIST_MODE_DISABLED
+    Disabled,
+    // waiting for self_deleverage_factor or an action trigger to be reached
+    Armed,
+    // all num_actions actions have been executed
+    Executed,
+}
+
+/**
+ * User's assist (automatic deleverage) setup, see instructions::AssistConfig for the factors.
+ * sell_sequence/buy_sequence are pool ids in the order the assist bot sells collateral and
+ * buys back debt. sdk-ts skips the price triggered actions when parsing UserInfo and their
+ * layout isn't published, so price_trigger_actions is kept as raw bytes.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Assist {
+    pub assist_mode: u8,
+    pub self_deleverage_factor: f64,
+    pub post_deleverage_factor: f64,
+    pub sell_sequence: [u8; ASSIST_SEQUENCE_SIZE],
+    pub buy_sequence: [u8; ASSIST_SEQUENCE_SIZE],
+    pub price_trigger_actions: [u8; ASSIST_PRICE_TRIGGER_ACTIONS_SIZE],
+    pub num_actions: u8,
+    pub num_executed: u8,
+}
+
+impl Default for Assist {
+    fn default() -> Self {
+        Assist {
+            assist_mode: consts::ASSIST_MODE_DISABLED,
+            self_deleverage_factor: 0.0,
+            post_deleverage_factor: 0.0,
+            sell_sequence: [0; ASSIST_SEQUENCE_SIZE],
+            buy_sequence: [0; ASSIST_SEQUENCE_SIZE],
+            price_trigger_actions: [0; ASSIST_PRICE_TRIGGER_ACTIONS_SIZE],
+            num_actions: 0,
+            num_executed: 0,
+        }
+    }
+}
+
+impl Assist {
+    pub fn status(&self) -> AssistStatus {
+        if self.assist_mode == consts::ASSIST_MODE_DISABLED {
+            AssistStatus::Disabled
+        } else if self.num_actions > 0 && self.num_executed >= self.num_actions {
+            AssistStatus::Executed
+        } else {
+            AssistStatus::Armed
+        }
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.status() == AssistStatus::Armed
+    }
+
+    // the error the contract fails an assist action with, Ok if the assist can still run
+    pub fn check_armed(&self) -> Result<(), ProgramError> {
+        match self.status() {
+            AssistStatus::Disabled => Err(ProgramError::Custom(consts::ERR_ASSIST_NOT_ENABLED)),
+            AssistStatus::Executed => {
+                Err(ProgramError::Custom(consts::ERR_ASSIST_ALREADY_EXECUTED))
+            }
+            AssistStatus::Armed => Ok(()),
+        }
+    }
+}
+
+impl Sealed for Assist {}
+
+impl Pack for Assist {
+    const LEN: usize =
+        1 + 8 + 8 + 2 * ASSIST_SEQUENCE_SIZE + ASSIST_PRICE_TRIGGER_ACTIONS_SIZE + 1 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut w = LayoutWriter::new(dst);
+        w.u8(self.assist_mode);
+        w.f64(self.self_deleverage_factor);
+        w.f64(self.post_deleverage_factor);
+        w.bytes(&self.sell_sequence);
+        w.bytes(&self.buy_sequence);
+        w.bytes(&self.price_trigger_actions);
+        w.u8(self.num_actions);
+        w.u8(self.num_executed);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = LayoutReader::new(src);
+        let assist_mode = r.u8()?;
+        let self_deleverage_factor = r.f64()?;
+        let post_deleverage_factor = r.f64()?;
+        let sell_sequence = r.bytes()?;
+        let buy_sequence = r.bytes()?;
+        let price_trigger_actions = r.bytes()?;
+        Ok(Assist {
+            assist_mode,
+            self_deleverage_factor,
+            post_deleverage_factor,
+            sell_sequence,
+            buy_sequence,
+            price_trigger_actions,
+            num_actions: r.u8()?,
+            num_executed: r.u8()?,
+        })
+    }
+}
+
+impl Display for Assist {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "assist_mode: {}, self_deleverage_factor: {}, post_deleverage_factor: {}, num_actions: {}, num_executed: {}, status: {:?}",
+            self.assist_mode,
+            self.self_deleverage_factor,
+            self.post_deleverage_factor,
+            self.num_actions,
+            self.num_executed,
+            self.status()
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct UserInfo {
     pub page_id: u16,
     pub num_assets: u8,
@@ -109,23 +458,100 @@ pub struct UserInfo {
     pub pad: [u8; 8],
     pub last_vest_cutoff_timestamp: u64,
     pub last_update_timestamp: u64,
+    pub assist: Assist,
 }
 
 impl UserInfo {
-    pub fn from_account_info<'a>(acc_info: &'a AccountInfo) -> Result<Ref<'a, Self>, ProgramError> {
-        let data_ref = acc_info.try_borrow_data()?;
-        let bytes_ref = Ref::map(data_ref, |d| *d);
-        let user_info = Ref::map(bytes_ref, |b| utils::cast::<UserInfo>(b));
-        Ok(user_info)
+    pub fn from_account_info(acc_info: &AccountInfo) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(&acc_info.try_borrow_data()?)
+    }
+
+    // panics if data is shorter than UserInfo::LEN. Returns UserInfo by value; the &UserInfo this
+    // used to return relied on a #[repr(packed)] layout that UserInfo no longer has
+    pub fn from_bytes(data: &[u8]) -> Self {
+        Self::unpack_from_slice(data).expect("UserInfo data too short")
+    }
+
+    // fails with ERR_WRONG_DATA_SIZE unless data is exactly UserInfo::LEN long
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        check_len(data, Self::LEN)?;
+        Self::unpack_from_slice(data)
+    }
+
+    /**
+     * Checks that acc_info is owned by the Apricot program and, if user_wallet is given, that it is
+     * the user info of user_wallet (ERR_INCORRECT_USER_INFO otherwise) before parsing it.
+     */
+    pub fn try_from_account_info(
+        acc_info: &AccountInfo,
+        user_wallet: Option<&Pubkey>,
+    ) -> Result<Self, ProgramError> {
+        Self::try_from_account_info_with_env(acc_info, user_wallet, &ApricotEnv::mainnet())
+    }
+
+    pub fn try_from_account_info_with_env(
+        acc_info: &AccountInfo,
+        user_wallet: Option<&Pubkey>,
+        env: &ApricotEnv,
+    ) -> Result<Self, ProgramError> {
+        check_account(
+            acc_info,
+            &env.program_id,
+            user_wallet.map(|wallet| env.user_info_k(wallet)),
+            consts::ERR_INCORRECT_USER_INFO,
+        )?;
+        Self::try_from_bytes(&acc_info.try_borrow_data()?)
     }
+}
+
+impl Sealed for UserInfo {}
+
+impl Pack for UserInfo {
+    const LEN: usize = 2
+        + 1
+        + MAX_ASSETS_PER_USER * UserAssetInfo::LEN
+        + RewardInfo::LEN
+        + 8
+        + 8
+        + 8
+        + Assist::LEN;
 
-    pub fn from_bytes(data: &[u8]) -> &Self {
-        utils::cast::<Self>(data)
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut w = LayoutWriter::new(dst);
+        w.u16(self.page_id);
+        w.u8(self.num_assets);
+        for asset in self.user_asset_info.iter() {
+            asset.pack_into_slice(w.take(UserAssetInfo::LEN));
+        }
+        self.reward.pack_into_slice(w.take(RewardInfo::LEN));
+        w.bytes(&self.pad);
+        w.u64(self.last_vest_cutoff_timestamp);
+        w.u64(self.last_update_timestamp);
+        self.assist.pack_into_slice(w.take(Assist::LEN));
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = LayoutReader::new(src);
+        let page_id = r.u16()?;
+        let num_assets = r.u8()?;
+        let mut user_asset_info = [UserAssetInfo::default(); MAX_ASSETS_PER_USER];
+        for asset in user_asset_info.iter_mut() {
+            *asset = UserAssetInfo::unpack_from_slice(r.take(UserAssetInfo::LEN)?)?;
+        }
+        Ok(UserInfo {
+            page_id,
+            num_assets,
+            user_asset_info,
+            reward: RewardInfo::unpack_from_slice(r.take(RewardInfo::LEN)?)?,
+            pad: r.bytes()?,
+            last_vest_cutoff_timestamp: r.u64()?,
+            last_update_timestamp: r.u64()?,
+            assist: Assist::unpack_from_slice(r.take(Assist::LEN)?)?,
+        })
     }
 }
 
 impl Display for UserInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(
             f,
@@ -141,13 +567,36 @@ impl Display for UserInfo {
             "last_vest_cutoff_timestamp: {}, last_update_timestamp: {}",
             self.last_vest_cutoff_timestamp, self.last_update_timestamp
         )?;
+        writeln!(f, "assist: {}", self.assist)?;
         Ok(())
     }
 }
 
+// owner and key checks shared by the try_from_account_info parsers, the key is only checked if given
+pub(crate) fn check_account(
+    acc_info: &AccountInfo,
+    program_id: &Pubkey,
+    expected_key: Option<Pubkey>,
+    key_err: u32,
+) -> Result<(), ProgramError> {
+    if acc_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    match expected_key {
+        Some(key) if key != *acc_info.key => Err(ProgramError::Custom(key_err)),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn check_len(data: &[u8], len: usize) -> Result<(), ProgramError> {
+    if data.len() != len {
+        return Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE));
+    }
+    Ok(())
+}
+
 pub const TOKEN_NAME_SIZE: usize = 32;
-#[repr(packed)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct AssetPool {
     pub token_name: [u8; TOKEN_NAME_SIZE],
 
@@ -214,15 +663,132 @@ pub struct AssetPool {
 }
 
 impl AssetPool {
-    pub fn from_account_info<'a>(acc_info: &'a AccountInfo) -> Result<Ref<'a, Self>, ProgramError> {
-        let data_ref = acc_info.try_borrow_data()?;
-        let bytes_ref = Ref::map(data_ref, |d| *d);
-        let asset_pool = Ref::map(bytes_ref, |b| utils::cast::<AssetPool>(b));
-        Ok(asset_pool)
+    pub fn from_account_info(acc_info: &AccountInfo) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(&acc_info.try_borrow_data()?)
+    }
+
+    // panics if data is shorter than AssetPool::LEN. Returns AssetPool by value; the &AssetPool this
+    // used to return relied on a #[repr(packed)] layout that AssetPool no longer has
+    pub fn from_bytes(data: &[u8]) -> Self {
+        Self::unpack_from_slice(data).expect("AssetPool data too short")
+    }
+
+    // fails with ERR_WRONG_DATA_SIZE unless data is exactly AssetPool::LEN long
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        check_len(data, Self::LEN)?;
+        Self::unpack_from_slice(data)
+    }
+
+    /**
+     * Checks that acc_info is owned by the Apricot program and, if pool_id is given, that it is
+     * the asset pool of pool_id (ERR_INCORRECT_ASSET_POOL otherwise) before parsing it.
+     */
+    pub fn try_from_account_info(
+        acc_info: &AccountInfo,
+        pool_id: Option<u8>,
+    ) -> Result<Self, ProgramError> {
+        Self::try_from_account_info_with_env(acc_info, pool_id, &ApricotEnv::mainnet())
+    }
+
+    pub fn try_from_account_info_with_env(
+        acc_info: &AccountInfo,
+        pool_id: Option<u8>,
+        env: &ApricotEnv,
+    ) -> Result<Self, ProgramError> {
+        check_account(
+            acc_info,
+            &env.program_id,
+            pool_id.map(|pool_id| env.asset_pool_k(pool_id)),
+            consts::ERR_INCORRECT_ASSET_POOL,
+        )?;
+        Self::try_from_bytes(&acc_info.try_borrow_data()?)
+    }
+
+    /**
+     * An approximation of the pool after an update at now. current_deposit_rate and
+     * current_borrow_rate (APRs) are accrued linearly into the indexes and pool amounts for the
+     * seconds since last_update_time, fee_amount grows by borrow interest minus deposit interest,
+     * then the rates are recomputed for the new utilization. The contract's own update isn't
+     * published, so results can drift from on-chain values. A now before last_update_time leaves
+     * the pool unchanged.
+     */
+    pub fn accrued(&self, now: u64) -> AssetPool {
+        let mut pool = *self;
+        if now <= self.last_update_time {
+            return pool;
+        }
+        let years = (now - self.last_update_time) as f64 / consts::SECONDS_PER_YEAR as f64;
+        // rates from account data aren't validated, a negative one doesn't shrink the pool
+        let deposit_factor = (1.0 + self.current_deposit_rate * years).max(1.0);
+        let borrow_factor = (1.0 + self.current_borrow_rate * years).max(1.0);
+
+        pool.deposit_index = self.deposit_index * deposit_factor;
+        pool.borrow_index = self.borrow_index * borrow_factor;
+        pool.deposit_amount = self.deposit_amount.mul_f64(deposit_factor);
+        pool.borrow_amount = self.borrow_amount.mul_f64(borrow_factor);
+
+        let borrow_interest = pool
+            .borrow_amount
+            .amt
+            .saturating_sub(self.borrow_amount.amt);
+        let deposit_interest = pool
+            .deposit_amount
+            .amt
+            .saturating_sub(self.deposit_amount.amt);
+        pool.fee_amount = RawAmt {
+            amt: self
+                .fee_amount
+                .amt
+                .saturating_add(borrow_interest.saturating_sub(deposit_interest)),
+        };
+        pool.last_update_time = now;
+
+        let (deposit_rate, borrow_rate) = pool.calculate_new_interest_rate(0, 0);
+        pool.current_deposit_rate = deposit_rate;
+        pool.current_borrow_rate = borrow_rate;
+        pool
+    }
+
+    // token_name without the zero padding
+    pub fn token_name_str(&self) -> String {
+        let len = self
+            .token_name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(TOKEN_NAME_SIZE);
+        String::from_utf8_lossy(&self.token_name[..len]).into_owned()
     }
 
-    pub fn from_bytes(data: &[u8]) -> &Self {
-        utils::cast::<Self>(data)
+    pub fn allow_borrow(&self) -> bool {
+        self.flags & consts::POOL_FLAG_ALLOW_BORROW != 0
+    }
+
+    pub fn is_lp(&self) -> bool {
+        self.flags & consts::POOL_FLAG_IS_LP != 0
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.flags & consts::POOL_FLAG_IS_STABLE != 0
+    }
+
+    // native amount that can leave the pool at now, i.e. deposits not lent out
+    pub fn free_liquidity(&self, now: u64) -> u64 {
+        let pool = self.accrued(now);
+        pool.deposit_amount
+            .to_native_amount()
+            .saturating_sub(pool.borrow_amount.to_native_amount())
+    }
+
+    // native amount that can still be deposited at now, assuming a deposit_cap of 0 means no cap
+    pub fn deposit_room(&self, now: u64) -> u64 {
+        if self.is_disabled != 0 {
+            return 0;
+        }
+        if self.deposit_cap == 0 {
+            return u64::MAX;
+        }
+        self.deposit_cap
+            .saturating_sub(self.accrued(now).deposit_amount.to_native_amount())
     }
 
     pub fn calculate_new_interest_rate(
@@ -271,8 +837,127 @@ impl AssetPool {
     }
 }
 
+impl Sealed for AssetPool {}
+
+impl Pack for AssetPool {
+    const LEN: usize = 459;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut w = LayoutWriter::new(dst);
+        w.bytes(&self.token_name);
+        w.pubkey(&self.mint_key);
+        w.u64(self.mint_decimal_multiplier);
+        w.u8(self.pool_id);
+
+        self.deposit_amount.pack_into_slice(w.take(RawAmt::LEN));
+        w.f64(self.deposit_index);
+
+        self.borrow_amount.pack_into_slice(w.take(RawAmt::LEN));
+        w.f64(self.borrow_index);
+
+        w.f64(self.reserve_factor);
+        self.fee_amount.pack_into_slice(w.take(RawAmt::LEN));
+        w.u64(self.fee_withdrawn_amount);
+        w.f64(self.current_fee_rate);
+
+        w.u64(self.last_update_time);
+
+        w.pubkey(&self.spl_key);
+        w.pubkey(&self.atoken_mint_key);
+        w.pubkey(&self.asset_price_key);
+        w.pubkey(&self.pyth_price_key);
+
+        w.u64(self.serum_next_cl_id);
+
+        w.f64(self.ltv);
+        w.f64(self.safe_factor);
+        w.u8(self.flags);
+
+        w.f64(self.base_rate);
+        w.f64(self.multiplier);
+        w.f64(self.jump_multiplier);
+        w.f64(self.kink);
+        w.f64(self.current_borrow_rate);
+        w.f64(self.current_deposit_rate);
+
+        w.f64(self.reward_multiplier);
+        w.f64(self.reward_deposit_intra_share);
+
+        w.u64(self.reward_apr_per_year);
+        w.u64(self.deposit_apt_reward_amount_per_year);
+        w.u64(self.borrow_apt_reward_amount_per_year);
+        w.f64(self.apt_reward_per_year_per_deposit);
+        w.f64(self.apt_reward_per_year_per_borrow);
+
+        w.f64(self.reward_deposit_index);
+        w.f64(self.reward_borrow_index);
+
+        w.u64(self.deposit_cap);
+        w.u8(self.is_disabled);
+
+        w.f64(self.farm_yield);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut r = LayoutReader::new(src);
+        Ok(AssetPool {
+            token_name: r.bytes()?,
+            mint_key: r.pubkey()?,
+            mint_decimal_multiplier: r.u64()?,
+            pool_id: r.u8()?,
+
+            deposit_amount: RawAmt::unpack_from_slice(r.take(RawAmt::LEN)?)?,
+            deposit_index: r.f64()?,
+
+            borrow_amount: RawAmt::unpack_from_slice(r.take(RawAmt::LEN)?)?,
+            borrow_index: r.f64()?,
+
+            reserve_factor: r.f64()?,
+            fee_amount: RawAmt::unpack_from_slice(r.take(RawAmt::LEN)?)?,
+            fee_withdrawn_amount: r.u64()?,
+            current_fee_rate: r.f64()?,
+
+            last_update_time: r.u64()?,
+
+            spl_key: r.pubkey()?,
+            atoken_mint_key: r.pubkey()?,
+            asset_price_key: r.pubkey()?,
+            pyth_price_key: r.pubkey()?,
+
+            serum_next_cl_id: r.u64()?,
+
+            ltv: r.f64()?,
+            safe_factor: r.f64()?,
+            flags: r.u8()?,
+
+            base_rate: r.f64()?,
+            multiplier: r.f64()?,
+            jump_multiplier: r.f64()?,
+            kink: r.f64()?,
+            current_borrow_rate: r.f64()?,
+            current_deposit_rate: r.f64()?,
+
+            reward_multiplier: r.f64()?,
+            reward_deposit_intra_share: r.f64()?,
+
+            reward_apr_per_year: r.u64()?,
+            deposit_apt_reward_amount_per_year: r.u64()?,
+            borrow_apt_reward_amount_per_year: r.u64()?,
+            apt_reward_per_year_per_deposit: r.f64()?,
+            apt_reward_per_year_per_borrow: r.f64()?,
+
+            reward_deposit_index: r.f64()?,
+            reward_borrow_index: r.f64()?,
+
+            deposit_cap: r.u64()?,
+            is_disabled: r.u8()?,
+
+            farm_yield: r.f64()?,
+        })
+    }
+}
+
 impl Display for AssetPool {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(f, "pool_id: {}", self.pool_id)?;
         writeln!(f, "mint: {}", self.mint_key)?;
@@ -340,3 +1025,368 @@ pub mod asset_pool_test {
         assert!(0.063076 - borrow_rate < 1.0e-6, "borrow_rate:{} doesn't match", borrow_rate);
     }
 }
+
+#[cfg(test)]
+pub mod state_layout_test {
+    use super::*;
+
+    // offsets below are the ones sdk-ts AccountParser reads these accounts at
+    #[test]
+    fn test_user_info_layout() {
+        assert_eq!(UserAssetInfo::LEN, 114);
+        assert_eq!(RewardInfo::LEN, 104);
+        assert_eq!(Assist::LEN, 355);
+        assert_eq!(UserInfo::LEN, 2310);
+
+        let mut data = vec![0u8; UserInfo::LEN];
+        data[0..2].copy_from_slice(&7u16.to_le_bytes());
+        data[2] = 2;
+        // second asset
+        let asset = 3 + UserAssetInfo::LEN;
+        data[asset] = 5;
+        data[asset + 1] = 1;
+        data[asset + 2..asset + 18].copy_from_slice(&(3u128 << NATIVE_RAW_SHIFT).to_le_bytes());
+        data[asset + 50..asset + 66].copy_from_slice(&(2u128 << NATIVE_RAW_SHIFT).to_le_bytes());
+        let reward = 3 + MAX_ASSETS_PER_USER * UserAssetInfo::LEN;
+        data[reward + 64..reward + 72].copy_from_slice(&1.5f64.to_le_bytes());
+        let assist = UserInfo::LEN - Assist::LEN;
+        data[assist - 8..assist].copy_from_slice(&1_650_000_000u64.to_le_bytes());
+        data[assist] = consts::ASSIST_MODE_STABLE_ONLY;
+        data[assist + 1..assist + 9].copy_from_slice(&0.8f64.to_le_bytes());
+        data[assist + 17] = 5;
+        data[assist + 33] = 0xab;
+        data[assist + 353] = 2;
+        data[assist + 354] = 1;
+
+        let user_info = UserInfo::from_bytes(&data);
+        assert_eq!(user_info.page_id, 7);
+        assert_eq!(user_info.num_assets, 2);
+        assert_eq!(user_info.user_asset_info[1].pool_id, 5);
+        assert_eq!(user_info.user_asset_info[1].use_as_collateral, 1);
+        assert_eq!(
+            user_info.user_asset_info[1]
+                .deposit_amount
+                .to_native_amount(),
+            3
+        );
+        assert_eq!(
+            user_info.user_asset_info[1]
+                .borrow_amount
+                .to_native_amount(),
+            2
+        );
+        assert_eq!(user_info.reward.available_apt, 1.5);
+        assert_eq!(user_info.last_update_timestamp, 1_650_000_000);
+        assert_eq!(
+            user_info.assist.assist_mode,
+            consts::ASSIST_MODE_STABLE_ONLY
+        );
+        assert_eq!(user_info.assist.self_deleverage_factor, 0.8);
+        assert_eq!(user_info.assist.sell_sequence[0], 5);
+        assert_eq!(user_info.assist.num_actions, 2);
+        assert_eq!(user_info.assist.price_trigger_actions[0], 0xab);
+
+        let mut packed = vec![0u8; UserInfo::LEN];
+        user_info.pack_into_slice(&mut packed);
+        assert_eq!(packed, data);
+    }
+
+    #[test]
+    fn test_asset_pool_layout() {
+        assert_eq!(AssetPool::LEN, 459);
+
+        let mut data = vec![0u8; AssetPool::LEN];
+        data[0..3].copy_from_slice(b"SOL");
+        data[72] = 9;
+        data[73..89].copy_from_slice(&(100u128 << NATIVE_RAW_SHIFT).to_le_bytes());
+        data[305..313].copy_from_slice(&0.85f64.to_le_bytes());
+        data[321] = 1;
+        data[442..450].copy_from_slice(&5_000_000u64.to_le_bytes());
+        data[450] = 1;
+        data[451..459].copy_from_slice(&0.02f64.to_le_bytes());
+
+        let pool = AssetPool::from_bytes(&data);
+        assert_eq!(&pool.token_name[..3], b"SOL");
+        assert_eq!(pool.pool_id, 9);
+        assert_eq!(pool.deposit_amount.to_native_amount(), 100);
+        assert_eq!(pool.ltv, 0.85);
+        assert_eq!(pool.flags, 1);
+        assert_eq!(pool.token_name_str(), "SOL");
+        assert!(pool.allow_borrow() && !pool.is_lp() && !pool.is_stable());
+        assert_eq!(pool.deposit_cap, 5_000_000);
+        assert_eq!(pool.is_disabled, 1);
+        assert_eq!(pool.farm_yield, 0.02);
+
+        let mut packed = vec![0u8; AssetPool::LEN];
+        pool.pack_into_slice(&mut packed);
+        assert_eq!(packed, data);
+
+        assert_eq!(
+            AssetPool::unpack_from_slice(&data[..AssetPool::LEN - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod assist_test {
+    use super::*;
+
+    #[test]
+    fn test_assist_status() {
+        let mut assist = Assist::default();
+        assert_eq!(assist.status(), AssistStatus::Disabled);
+        assert_eq!(
+            assist.check_armed(),
+            Err(ProgramError::Custom(consts::ERR_ASSIST_NOT_ENABLED))
+        );
+
+        // stable-only assist without explicit actions stays armed
+        assist.assist_mode = consts::ASSIST_MODE_STABLE_ONLY;
+        assert!(assist.is_armed());
+
+        assist.num_actions = 2;
+        assist.num_executed = 1;
+        assert_eq!(assist.check_armed(), Ok(()));
+
+        assist.num_executed = 2;
+        assert_eq!(assist.status(), AssistStatus::Executed);
+        assert_eq!(
+            assist.check_armed(),
+            Err(ProgramError::Custom(consts::ERR_ASSIST_ALREADY_EXECUTED))
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod validated_parse_test {
+    use super::*;
+
+    fn check_user_info(
+        key: Pubkey,
+        owner: Pubkey,
+        mut data: Vec<u8>,
+        wallet: Option<&Pubkey>,
+    ) -> Result<UserInfo, ProgramError> {
+        let mut lamports = 0;
+        let acc_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        UserInfo::try_from_account_info(&acc_info, wallet)
+    }
+
+    #[test]
+    fn test_user_info_checks() {
+        let wallet = Pubkey::new_unique();
+        let key = consts::get_user_info_k(&wallet);
+        let data = vec![0u8; UserInfo::LEN];
+
+        assert!(check_user_info(key, consts::program::ID, data.clone(), Some(&wallet)).is_ok());
+        assert!(check_user_info(
+            Pubkey::new_unique(),
+            consts::program::ID,
+            data.clone(),
+            None
+        )
+        .is_ok());
+        assert_eq!(
+            check_user_info(key, Pubkey::new_unique(), data.clone(), Some(&wallet)),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            check_user_info(
+                Pubkey::new_unique(),
+                consts::program::ID,
+                data.clone(),
+                Some(&wallet)
+            ),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_USER_INFO))
+        );
+        assert_eq!(
+            check_user_info(
+                key,
+                consts::program::ID,
+                vec![0u8; UserInfo::LEN - 1],
+                Some(&wallet)
+            ),
+            Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE))
+        );
+    }
+
+    #[test]
+    fn test_asset_pool_checks() {
+        let key = consts::get_asset_pool_k(3);
+        let mut lamports = 0;
+        let mut data = vec![0u8; AssetPool::LEN];
+        let owner = consts::program::ID;
+        let acc_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+
+        assert!(AssetPool::try_from_account_info(&acc_info, Some(3)).is_ok());
+        assert_eq!(
+            AssetPool::try_from_account_info(&acc_info, Some(4)),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_ASSET_POOL))
+        );
+        assert_eq!(
+            AssetPool::try_from_account_info_with_env(&acc_info, Some(3), &ApricotEnv::alpha()),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            AssetPool::try_from_bytes(&[0u8; AssetPool::LEN + 1]),
+            Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE))
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod fast_forward_test {
+    use super::*;
+
+    fn raw(native: u64) -> RawAmt {
+        RawAmt {
+            amt: (native as u128) << NATIVE_RAW_SHIFT,
+        }
+    }
+
+    // f64 rates and indexes can land the amount just below the exact value
+    fn assert_native(amt: RawAmt, expected: u64) {
+        let native = amt.to_native_amount();
+        assert!(
+            native + 1 >= expected && native <= expected,
+            "{} != {}",
+            native,
+            expected
+        );
+    }
+
+    fn pool() -> AssetPool {
+        AssetPool {
+            deposit_amount: raw(1_000_000),
+            deposit_index: 1.0,
+            borrow_amount: raw(500_000),
+            borrow_index: 1.0,
+            reserve_factor: 0.2,
+            last_update_time: 1_000,
+            base_rate: 0.1,
+            kink: 0.8,
+            current_borrow_rate: 0.1,
+            current_deposit_rate: 0.04,
+            ..AssetPool::default()
+        }
+    }
+
+    #[test]
+    fn test_mul_f64() {
+        assert_native(raw(1_000).mul_f64(1.5), 1_500);
+        assert_eq!(raw(1_000).mul_f64(0.0), RawAmt::ZERO);
+
+        // the low 40 bits times a large factor used to overflow
+        let low = RawAmt::from_raw((1 << 40) - 1);
+        assert_eq!(
+            low.mul_f64(1e15).raw(),
+            ((1 << 40) - 1) * 1_000_000_000_000_000
+        );
+        assert_eq!(RawAmt::from_raw(1 << 100).mul_f64(2.0).raw(), 1 << 101);
+        assert_eq!(RawAmt::from_raw(1 << 100).mul_f64(1e15).raw(), u128::MAX);
+        assert_eq!(RawAmt::from_raw(u128::MAX).mul_f64(1e30).raw(), u128::MAX);
+        assert_eq!(low.mul_f64(1e40).raw(), u128::MAX);
+        assert_eq!(RawAmt::from_raw(1).mul_f64(1e30).raw(), 1e30 as u128);
+
+        assert_eq!(low.mul_f64(f64::INFINITY).raw(), u128::MAX);
+        assert_eq!(RawAmt::ZERO.mul_f64(f64::INFINITY), RawAmt::ZERO);
+        assert_eq!(low.checked_mul_f64(f64::NAN), None);
+        assert_eq!(low.checked_mul_f64(-1.0), None);
+        assert_eq!(low.mul_f64(f64::NAN), RawAmt::ZERO);
+        assert_eq!(low.mul_f64(-1.0), RawAmt::ZERO);
+    }
+
+    #[test]
+    fn test_deposit_room() {
+        let mut pool = AssetPool {
+            deposit_amount: raw(1_000),
+            ..AssetPool::default()
+        };
+        assert_eq!(pool.deposit_room(0), u64::MAX);
+        pool.deposit_cap = 1_500;
+        assert_eq!(pool.deposit_room(0), 500);
+        pool.deposit_cap = 800;
+        assert_eq!(pool.deposit_room(0), 0);
+        pool.is_disabled = 1;
+        pool.deposit_cap = 0;
+        assert_eq!(pool.deposit_room(0), 0);
+    }
+
+    #[test]
+    fn test_pool_accrual() {
+        let pool = pool();
+        assert_eq!(pool.accrued(500), pool);
+
+        let accrued = pool.accrued(1_000 + consts::SECONDS_PER_YEAR);
+        assert_eq!(accrued.last_update_time, 1_000 + consts::SECONDS_PER_YEAR);
+        assert!((accrued.borrow_index - 1.1).abs() < 1e-12);
+        assert!((accrued.deposit_index - 1.04).abs() < 1e-12);
+        assert_native(accrued.borrow_amount, 550_000);
+        assert_native(accrued.deposit_amount, 1_040_000);
+        // 50_000 borrow interest, 40_000 of it paid to depositors
+        assert_native(accrued.fee_amount, 10_000);
+        let utilization = 550_000.0 / 1_040_000.0;
+        assert!((accrued.current_borrow_rate - 0.1).abs() < 1e-12);
+        assert!((accrued.current_deposit_rate - 0.1 * utilization * 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pool_accrual_negative_rates() {
+        let mut pool = pool();
+        pool.current_deposit_rate = -0.5;
+        pool.current_borrow_rate = -3.0;
+        let accrued = pool.accrued(1_000 + consts::SECONDS_PER_YEAR);
+        assert_eq!(accrued.deposit_amount, pool.deposit_amount);
+        assert_eq!(accrued.borrow_amount, pool.borrow_amount);
+        assert_eq!(accrued.deposit_index, pool.deposit_index);
+        assert_eq!(accrued.borrow_index, pool.borrow_index);
+        assert_eq!(accrued.fee_amount, pool.fee_amount);
+    }
+
+    #[test]
+    fn test_user_fast_forward() {
+        let mut pool = pool();
+        pool.deposit_index = 1.02;
+        pool.borrow_index = 1.05;
+
+        let user = UserAssetInfo {
+            deposit_amount: raw(1_000),
+            deposit_index: 1.02,
+            borrow_amount: raw(2_000),
+            borrow_index: 1.0,
+            ..UserAssetInfo::default()
+        };
+        let rebased = user.rebased(&pool);
+        assert_eq!(rebased.deposit_amount, user.deposit_amount);
+        assert_native(rebased.borrow_amount, 2_100);
+        assert_eq!(rebased.borrow_index, 1.05);
+
+        let ff = user.fast_forwarded(&pool, 1_000 + consts::SECONDS_PER_YEAR / 2);
+        assert_native(ff.deposit_amount, 1_020);
+        assert_native(ff.borrow_amount, 2_205);
+
+        // a fresh asset without index is left as is
+        let empty = UserAssetInfo::default().rebased(&pool);
+        assert_eq!(empty.deposit_amount, RawAmt::default());
+        assert_eq!(empty.deposit_index, 1.02);
+    }
+}
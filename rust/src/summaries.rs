@@ -0,0 +1,227 @@
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::layout::LayoutReader;
+use crate::state::check_account;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+// PoolSummaries and PriceSummaries cache per-pool state for every instruction to read at once,
+// both are arrays indexed by pool_id. Their length grows with the number of pools, so the number of
+// entries is taken from the account size.
+//
+// UNVERIFIED: neither layout is published. The only reference is sdk-ts
+// AccountParser.parsePriceSummaries, which reads one u64 price per pool id from offset 0.
+// Everything else here is inferred from the AssetPool fields the summaries cache, and hasn't been
+// checked against a dump of the mainnet accounts.
+
+pub const POOL_SUMMARY_SIZE: usize = 5 * 8;
+
+/**
+ * Indexes and rates of one asset pool as of last_update_time, the same values the AssetPool
+ * account holds. Field order and entry size are unverified, see the note at the top of the file.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PoolSummary {
+    pub deposit_index: f64,
+    pub borrow_index: f64,
+    pub deposit_rate: f64,
+    pub borrow_rate: f64,
+    pub last_update_time: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolSummaries {
+    pub pools: Vec<PoolSummary>,
+}
+
+impl PoolSummaries {
+    pub fn unpack_from_slice(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() % POOL_SUMMARY_SIZE > 0 {
+            return Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE));
+        }
+        let mut r = LayoutReader::new(data);
+        let mut pools = Vec::with_capacity(data.len() / POOL_SUMMARY_SIZE);
+        for _ in 0..data.len() / POOL_SUMMARY_SIZE {
+            pools.push(PoolSummary {
+                deposit_index: r.f64()?,
+                borrow_index: r.f64()?,
+                deposit_rate: r.f64()?,
+                borrow_rate: r.f64()?,
+                last_update_time: r.u64()?,
+            });
+        }
+        Ok(PoolSummaries { pools })
+    }
+
+    // fails with ERR_INCORRECT_POOL_SUMMARIES unless acc_info is env's pool summaries
+    pub fn try_from_account_info(
+        acc_info: &AccountInfo,
+        env: &ApricotEnv,
+    ) -> Result<Self, ProgramError> {
+        check_account(
+            acc_info,
+            &env.program_id,
+            Some(env.pool_summaries),
+            consts::ERR_INCORRECT_POOL_SUMMARIES,
+        )?;
+        Self::unpack_from_slice(&acc_info.try_borrow_data()?)
+    }
+
+    pub fn get(&self, pool_id: u8) -> Option<&PoolSummary> {
+        self.pools.get(pool_id as usize)
+    }
+}
+
+pub const PRICE_SUMMARY_SIZE: usize = 2 * 8;
+
+// UNVERIFIED: decimals of the fixed-point price_in_usd, assumed to match the 9 decimals the
+// contract normalizes amounts to (see AssetPool::mint_decimal_multiplier)
+pub const PRICE_DECIMALS: u8 = 9;
+
+/**
+ * Oracle price of one pool's token, in the same fixed point as the AssetPrice account
+ * (see sdk-ts AccountParser.parseAssetPrice), and the unix time it was last updated.
+*/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PriceSummary {
+    pub price_in_usd: u64,
+    pub last_update_time: u64,
+}
+
+impl PriceSummary {
+    // price_in_usd as USD per whole token, see PRICE_DECIMALS
+    pub fn usd_price(&self) -> f64 {
+        self.price_in_usd as f64 / 10f64.powi(PRICE_DECIMALS as i32)
+    }
+
+    pub fn age(&self, now: u64) -> u64 {
+        now.saturating_sub(self.last_update_time)
+    }
+}
+
+/**
+ * Prices are stored first, one u64 per pool id, which is all sdk-ts parsePriceSummaries reads.
+ * The update times after them, and so the entry size used to count pools, are unverified.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PriceSummaries {
+    pub prices: Vec<PriceSummary>,
+}
+
+impl PriceSummaries {
+    pub fn unpack_from_slice(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() % PRICE_SUMMARY_SIZE > 0 {
+            return Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE));
+        }
+        let num_pools = data.len() / PRICE_SUMMARY_SIZE;
+        let (price_data, time_data) = data.split_at(num_pools * 8);
+        let mut prices_r = LayoutReader::new(price_data);
+        let mut times_r = LayoutReader::new(time_data);
+        let mut prices = Vec::with_capacity(num_pools);
+        for _ in 0..num_pools {
+            prices.push(PriceSummary {
+                price_in_usd: prices_r.u64()?,
+                last_update_time: times_r.u64()?,
+            });
+        }
+        Ok(PriceSummaries { prices })
+    }
+
+    // fails with ERR_INCORRECT_PRICE_SUMMARIES unless acc_info is env's price summaries
+    pub fn try_from_account_info(
+        acc_info: &AccountInfo,
+        env: &ApricotEnv,
+    ) -> Result<Self, ProgramError> {
+        check_account(
+            acc_info,
+            &env.program_id,
+            Some(env.price_summaries),
+            consts::ERR_INCORRECT_PRICE_SUMMARIES,
+        )?;
+        Self::unpack_from_slice(&acc_info.try_borrow_data()?)
+    }
+
+    pub fn get(&self, pool_id: u8) -> Option<&PriceSummary> {
+        self.prices.get(pool_id as usize)
+    }
+
+    // pools whose price hasn't been updated for more than max_age seconds
+    pub fn stale_pools(&self, now: u64, max_age: u64) -> Vec<u8> {
+        self.prices
+            .iter()
+            .enumerate()
+            .filter(|(_, price)| price.age(now) > max_age)
+            .map(|(pool_id, _)| pool_id as u8)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod summaries_test {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    // built by hand from the assumed layouts, there are no dumps of the mainnet accounts to test
+    // against offline
+
+    #[test]
+    fn test_pool_summaries() {
+        let mut data = vec![0u8; 3 * POOL_SUMMARY_SIZE];
+        let pool = 2 * POOL_SUMMARY_SIZE;
+        data[pool..pool + 8].copy_from_slice(&1.25f64.to_le_bytes());
+        data[pool + 24..pool + 32].copy_from_slice(&0.07f64.to_le_bytes());
+        data[pool + 32..pool + 40].copy_from_slice(&1_650_000_000u64.to_le_bytes());
+
+        let summaries = PoolSummaries::unpack_from_slice(&data).unwrap();
+        assert_eq!(summaries.pools.len(), 3);
+        let summary = summaries.get(2).unwrap();
+        assert_eq!(summary.deposit_index, 1.25);
+        assert_eq!(summary.borrow_rate, 0.07);
+        assert_eq!(summary.last_update_time, 1_650_000_000);
+        assert!(summaries.get(3).is_none());
+
+        assert_eq!(
+            PoolSummaries::unpack_from_slice(&data[1..]),
+            Err(ProgramError::Custom(consts::ERR_WRONG_DATA_SIZE))
+        );
+    }
+
+    #[test]
+    fn test_price_summaries() {
+        let mut data = vec![0u8; 4 * PRICE_SUMMARY_SIZE];
+        for pool_id in 0..4u64 {
+            let price = pool_id as usize * 8;
+            data[price..price + 8].copy_from_slice(&(100 + pool_id).to_le_bytes());
+            let time = 4 * 8 + price;
+            data[time..time + 8].copy_from_slice(&(1000 + pool_id * 10).to_le_bytes());
+        }
+
+        let summaries = PriceSummaries::unpack_from_slice(&data).unwrap();
+        assert_eq!(
+            summaries.get(1),
+            Some(&PriceSummary {
+                price_in_usd: 101,
+                last_update_time: 1010,
+            })
+        );
+        assert_eq!(summaries.stale_pools(1030, 15), vec![0, 1]);
+
+        let env = ApricotEnv::mainnet();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let owner = env.program_id;
+        let acc_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        assert_eq!(
+            PriceSummaries::try_from_account_info(&acc_info, &env),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_PRICE_SUMMARIES))
+        );
+    }
+}
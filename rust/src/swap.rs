@@ -0,0 +1,364 @@
+use solana_program::{
+    instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey, sysvar,
+};
+
+use crate::config;
+use crate::consts;
+use crate::lp::{LpMarket, LpRoute};
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SwapVenue {
+    Serum = consts::SWAP_SERUM,
+    Raydium = consts::SWAP_RAYDIUM,
+    Saber = consts::SWAP_SABER,
+    Orca = consts::SWAP_ORCA,
+}
+
+impl SwapVenue {
+    pub fn from_u8(target_swap: u8) -> Option<Self> {
+        match target_swap {
+            consts::SWAP_SERUM => Some(SwapVenue::Serum),
+            consts::SWAP_RAYDIUM => Some(SwapVenue::Raydium),
+            consts::SWAP_SABER => Some(SwapVenue::Saber),
+            consts::SWAP_ORCA => Some(SwapVenue::Orca),
+            _ => None,
+        }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            SwapVenue::Serum => config::serum_program::ID,
+            SwapVenue::Raydium => config::raydium_amm_program::ID,
+            SwapVenue::Saber => config::saber_swap_program::ID,
+            SwapVenue::Orca => config::orca_swap_program::ID,
+        }
+    }
+}
+
+// serum orderbook traded directly, open_orders is owned by base_pda
+#[derive(Copy, Clone, Debug)]
+pub struct SerumMarket {
+    pub market: Pubkey,
+    pub open_orders: Pubkey,
+    pub request_queue: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+// raydium amm, together with the serum market backing it
+#[derive(Copy, Clone, Debug)]
+pub struct RaydiumMarket {
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+
+    pub serum_program: Pubkey, // config::serum_program or openbook, depending on the market
+    pub serum_market: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+    pub serum_events: Pubkey,
+    pub serum_vault_a: Pubkey,
+    pub serum_vault_b: Pubkey,
+    pub serum_vault_signer: Pubkey,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SaberMarket {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub admin_fee_destination_a: Pubkey,
+    pub admin_fee_destination_b: Pubkey,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct OrcaMarket {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub pool_mint: Pubkey,
+    pub fees: Pubkey,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum SwapMarket {
+    Serum(SerumMarket),
+    Raydium(RaydiumMarket),
+    Saber(SaberMarket),
+    Orca(OrcaMarket),
+}
+
+/**
+ * A direct market between two Apricot pools. Token A (base) and token B (quote) follow the
+ * market's own vault order, a swap that buys token A is a "buy" for the contract.
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct SwapRoute {
+    pub pool_id_a: u8,
+    pub pool_id_b: u8,
+    pub market: SwapMarket,
+}
+
+/**
+ * LP pools whose DEX pool is the market DIRECT_SWAP_META in sdk-ts trades their tokens on. The
+ * other DIRECT_SWAP_META markets come from @apricot-lend/solana-swaps-js and have no accounts in
+ * this crate, neither do pools whose market isn't token A to USDC in vault order (USDT/USDC) or
+ * lacks the serum queues (SRM/USDC).
+*/
+pub static DIRECT_SWAP_LP_POOLS: &[u8] = &[
+    config::sol_usdc_raydium::POOL_ID,
+    config::ray_usdc_raydium::POOL_ID,
+    config::orca_usdc_orca::POOL_ID,
+    config::eth_usdc_orca::POOL_ID,
+    config::ust_usdc_saber::POOL_ID,
+];
+
+impl SwapRoute {
+    // market between the two pools from DIRECT_SWAP_LP_POOLS, the order of the pools doesn't matter
+    pub fn direct(pool_id_x: u8, pool_id_y: u8) -> Option<SwapRoute> {
+        DIRECT_SWAP_LP_POOLS
+            .iter()
+            .filter_map(|lp_pool_id| LpRoute::from_config(*lp_pool_id))
+            .find(|lp| {
+                (lp.left_pool_id, lp.right_pool_id) == (pool_id_x, pool_id_y)
+                    || (lp.left_pool_id, lp.right_pool_id) == (pool_id_y, pool_id_x)
+            })
+            .and_then(|lp| SwapRoute::from_lp_route(&lp))
+    }
+
+    // swaps through the DEX pool of an LP route, whose vault order is (left, right)
+    pub fn from_lp_route(route: &LpRoute) -> Option<SwapRoute> {
+        let market = match &route.market {
+            LpMarket::Orca(m) => SwapMarket::Orca(OrcaMarket {
+                swap: m.swap,
+                swap_authority: m.swap_authority,
+                vault_a: m.vault_a,
+                vault_b: m.vault_b,
+                pool_mint: m.lp_mint,
+                fees: m.fees,
+            }),
+            LpMarket::Raydium(m) => {
+                let queues = m.serum_queues.as_ref()?;
+                SwapMarket::Raydium(RaydiumMarket {
+                    amm: m.amm,
+                    amm_authority: m.amm_authority,
+                    open_orders: m.open_orders,
+                    target_orders: m.target_orders,
+                    vault_a: m.vault_coin,
+                    vault_b: m.vault_pc,
+                    serum_program: m.serum_program,
+                    serum_market: m.serum_market,
+                    serum_bids: queues.bids,
+                    serum_asks: queues.asks,
+                    serum_events: queues.event_queue,
+                    serum_vault_a: m.serum_vault_coin,
+                    serum_vault_b: m.serum_vault_pc,
+                    serum_vault_signer: m.serum_vault_signer,
+                })
+            }
+            LpMarket::Saber(m) => SwapMarket::Saber(SaberMarket {
+                swap: m.swap,
+                swap_authority: m.swap_authority,
+                vault_a: m.vault_a,
+                vault_b: m.vault_b,
+                admin_fee_destination_a: m.fees_a,
+                admin_fee_destination_b: m.fees_b,
+            }),
+        };
+        Some(SwapRoute {
+            pool_id_a: route.left_pool_id,
+            pool_id_b: route.right_pool_id,
+            market,
+        })
+    }
+
+    pub fn venue(&self) -> SwapVenue {
+        match self.market {
+            SwapMarket::Serum(_) => SwapVenue::Serum,
+            SwapMarket::Raydium(_) => SwapVenue::Raydium,
+            SwapMarket::Saber(_) => SwapVenue::Saber,
+            SwapMarket::Orca(_) => SwapVenue::Orca,
+        }
+    }
+
+    // checks the pools against this market and returns is_buy
+    pub fn is_buy(&self, sell_pool_id: u8, buy_pool_id: u8) -> Result<bool, ProgramError> {
+        let is_buy = if sell_pool_id == self.pool_id_b {
+            true
+        } else if sell_pool_id == self.pool_id_a {
+            false
+        } else {
+            return Err(ProgramError::Custom(consts::ERR_INCORRECT_SELL_MARKET));
+        };
+        let expected_buy_pool_id = if is_buy {
+            self.pool_id_a
+        } else {
+            self.pool_id_b
+        };
+        if buy_pool_id != expected_buy_pool_id {
+            return Err(ProgramError::Custom(consts::ERR_INCORRECT_BUY_MARKET));
+        }
+        Ok(is_buy)
+    }
+
+    // venue-specific accounts appended after the apricot accounts of a margin swap
+    pub fn swap_keys(&self, is_buy: bool) -> Vec<AccountMeta> {
+        let program_id = self.venue().program_id();
+        match &self.market {
+            SwapMarket::Serum(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(m.market, false),
+                AccountMeta::new(m.open_orders, false),
+                AccountMeta::new(m.request_queue, false),
+                AccountMeta::new(m.event_queue, false),
+                AccountMeta::new(m.bids, false),
+                AccountMeta::new(m.asks, false),
+                AccountMeta::new(m.base_vault, false),
+                AccountMeta::new(m.quote_vault, false),
+                AccountMeta::new_readonly(m.vault_signer, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+            ],
+            SwapMarket::Raydium(m) => vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(m.amm, false),
+                AccountMeta::new_readonly(m.amm_authority, false),
+                AccountMeta::new(m.open_orders, false),
+                AccountMeta::new(m.target_orders, false),
+                AccountMeta::new(m.vault_a, false),
+                AccountMeta::new(m.vault_b, false),
+                AccountMeta::new_readonly(m.serum_program, false),
+                AccountMeta::new(m.serum_market, false),
+                AccountMeta::new(m.serum_bids, false),
+                AccountMeta::new(m.serum_asks, false),
+                AccountMeta::new(m.serum_events, false),
+                AccountMeta::new(m.serum_vault_a, false),
+                AccountMeta::new(m.serum_vault_b, false),
+                AccountMeta::new_readonly(m.serum_vault_signer, false),
+            ],
+            SwapMarket::Saber(m) => {
+                let (source_vault, dest_vault, admin_fee_destination) = if is_buy {
+                    (m.vault_b, m.vault_a, m.admin_fee_destination_a)
+                } else {
+                    (m.vault_a, m.vault_b, m.admin_fee_destination_b)
+                };
+                vec![
+                    AccountMeta::new_readonly(program_id, false),
+                    AccountMeta::new_readonly(m.swap, false),
+                    AccountMeta::new_readonly(m.swap_authority, false),
+                    AccountMeta::new(source_vault, false),
+                    AccountMeta::new(dest_vault, false),
+                    AccountMeta::new(admin_fee_destination, false),
+                    AccountMeta::new_readonly(sysvar::clock::ID, false),
+                ]
+            }
+            SwapMarket::Orca(m) => {
+                let (source_vault, dest_vault) = if is_buy {
+                    (m.vault_b, m.vault_a)
+                } else {
+                    (m.vault_a, m.vault_b)
+                };
+                vec![
+                    AccountMeta::new_readonly(program_id, false),
+                    AccountMeta::new_readonly(m.swap, false),
+                    AccountMeta::new_readonly(m.swap_authority, false),
+                    AccountMeta::new(source_vault, false),
+                    AccountMeta::new(dest_vault, false),
+                    AccountMeta::new(m.pool_mint, false),
+                    AccountMeta::new(m.fees, false),
+                ]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod swap_route_test {
+    use super::*;
+
+    #[test]
+    fn test_is_buy() {
+        let route = SwapRoute {
+            pool_id_a: config::usdt::POOL_ID,
+            pool_id_b: config::usdc::POOL_ID,
+            market: SwapMarket::Orca(OrcaMarket {
+                swap: Pubkey::new_unique(),
+                swap_authority: Pubkey::new_unique(),
+                vault_a: Pubkey::new_unique(),
+                vault_b: Pubkey::new_unique(),
+                pool_mint: Pubkey::new_unique(),
+                fees: Pubkey::new_unique(),
+            }),
+        };
+
+        assert_eq!(
+            route.is_buy(config::usdc::POOL_ID, config::usdt::POOL_ID),
+            Ok(true)
+        );
+        assert_eq!(
+            route.is_buy(config::usdt::POOL_ID, config::usdc::POOL_ID),
+            Ok(false)
+        );
+        assert_eq!(
+            route.is_buy(config::sol::POOL_ID, config::usdc::POOL_ID),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_SELL_MARKET))
+        );
+        assert_eq!(
+            route.is_buy(config::usdc::POOL_ID, config::sol::POOL_ID),
+            Err(ProgramError::Custom(consts::ERR_INCORRECT_BUY_MARKET))
+        );
+
+        // selling USDC, so USDC vault (b) is the source
+        let keys = route.swap_keys(true);
+        assert_eq!(keys[0].pubkey, config::orca_swap_program::ID);
+        if let SwapMarket::Orca(m) = route.market {
+            assert_eq!(keys[3].pubkey, m.vault_b);
+            assert_eq!(keys[4].pubkey, m.vault_a);
+        }
+    }
+
+    #[test]
+    fn test_direct() {
+        // DIRECT_SWAP_META[SOL][USDC].getSwapKeys() in sdk-ts, after the amm program
+        let route = SwapRoute::direct(config::usdc::POOL_ID, config::sol::POOL_ID).unwrap();
+        assert_eq!(route.venue(), SwapVenue::Raydium);
+        assert_eq!(
+            route.is_buy(config::usdc::POOL_ID, config::sol::POOL_ID),
+            Ok(true)
+        );
+        let keys = route.swap_keys(true);
+        assert_eq!(keys.len(), 15);
+        assert_eq!(keys[1].pubkey, config::sol_usdc_raydium::amm::ID);
+        assert_eq!(keys[5].pubkey, config::sol_usdc_raydium::vault_coin::ID);
+        assert_eq!(keys[7].pubkey, config::sol_usdc_raydium::serum_program::ID);
+        assert_eq!(
+            keys[11].pubkey,
+            config::sol_usdc_raydium::serum_event_queue::ID
+        );
+
+        // selling USTv2 on saber, the fee goes to the USDC admin account
+        let route = SwapRoute::direct(config::ust::POOL_ID, config::usdc::POOL_ID).unwrap();
+        let keys = route.swap_keys(false);
+        assert_eq!(keys[3].pubkey, config::ust_usdc_saber::vault_a::ID);
+        assert_eq!(keys[5].pubkey, config::ust_usdc_saber::fees_b::ID);
+
+        assert_eq!(
+            SwapRoute::direct(config::orca::POOL_ID, config::usdc::POOL_ID)
+                .unwrap()
+                .venue(),
+            SwapVenue::Orca
+        );
+        assert!(SwapRoute::direct(config::srm::POOL_ID, config::usdc::POOL_ID).is_none());
+        assert!(SwapRoute::direct(config::sol::POOL_ID, config::ray::POOL_ID).is_none());
+        assert!(SwapRoute::direct(config::sol::POOL_ID, 7).is_none());
+    }
+}
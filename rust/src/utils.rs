@@ -1,34 +1,188 @@
+use crate::consts;
+use crate::layout::LayoutReader;
 use itertools::Itertools;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-#[repr(packed)]
 pub struct UserInfoHeader {
     pub page_id: u16,
 }
 
+impl UserInfoHeader {
+    pub fn unpack_from_slice(data: &[u8]) -> Result<Self, ProgramError> {
+        Ok(UserInfoHeader {
+            page_id: LayoutReader::new(data).u16()?,
+        })
+    }
+}
+
 pub const NUM_PAGES:usize = 5000;
 
-#[repr(packed)]
-pub struct UserPagesStats {
-    pub num_free_slots: [u16; NUM_PAGES],
+/**
+ * Free slots per users page, read in place from the user pages stats account. The account holds
+ * NUM_PAGES u16s, too much to copy onto the on-chain stack.
+*/
+pub struct UserPagesStats<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> UserPagesStats<'a> {
+    pub fn unpack_from_slice(data: &'a [u8]) -> Result<Self, ProgramError> {
+        if data.len() < NUM_PAGES * 2 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(UserPagesStats {
+            data: &data[..NUM_PAGES * 2],
+        })
+    }
+
+    // free slots of every page, indexed by page id
+    pub fn num_free_slots(&self) -> impl Iterator<Item = u16> + 'a {
+        let mut reader = LayoutReader::new(self.data);
+        std::iter::from_fn(move || reader.u16().ok())
+    }
+
+    /**
+     * Pages that still have free slots, most free slots first and higher page ids first among
+     * equals, like the position_max of earlier versions. Pages fill up between reading the stats and sending add_user_and_deposit, so callers
+     * can fall back to the next candidate when the contract rejects a page.
+     * Fails with ERR_NO_AVAILABLE_SLOTS when every page is full.
+     */
+    pub fn ranked_page_ids(&self) -> Result<Vec<u16>, ProgramError> {
+        let ranked: Vec<u16> = self
+            .num_free_slots()
+            .enumerate()
+            .filter(|(_, num_free)| *num_free > 0)
+            .sorted_by(|(a_id, a_free), (b_id, b_free)| b_free.cmp(a_free).then(b_id.cmp(a_id)))
+            .map(|(page_id, _)| page_id as u16)
+            .collect();
+        if ranked.is_empty() {
+            return Err(ProgramError::Custom(consts::ERR_NO_AVAILABLE_SLOTS));
+        }
+        Ok(ranked)
+    }
+}
+
+/**
+ * Wallets registered in one users page, indexed by slot. Free slots hold the default pubkey.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UsersPage {
+    pub slots: Vec<Pubkey>,
 }
 
-pub fn cast<T>(data: &[u8]) -> &T {
-    assert!(data.len() >= std::mem::size_of::<T>());
-    return unsafe{std::mem::transmute(data.as_ptr())};
+impl UsersPage {
+    pub fn unpack_from_slice(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = LayoutReader::new(data);
+        let mut slots = Vec::with_capacity(data.len() / 32);
+        for _ in 0..data.len() / 32 {
+            slots.push(reader.pubkey()?);
+        }
+        Ok(UsersPage { slots })
+    }
+
+    // (slot, wallet) of every registered user
+    pub fn wallets(&self) -> impl Iterator<Item = (usize, &Pubkey)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, wallet)| **wallet != Pubkey::default())
+    }
+
+    pub fn num_free_slots(&self) -> usize {
+        self.slots.len() - self.wallets().count()
+    }
+
+    pub fn slot_of(&self, user_wallet: &Pubkey) -> Option<usize> {
+        self.wallets()
+            .find(|(_, wallet)| *wallet == user_wallet)
+            .map(|(slot, _)| slot)
+    }
 }
 
 pub const INVALID_PAGE_ID:u16 = u16::MAX;
 
-pub fn is_user_active(data:&[u8]) -> bool {
-    let user_info_header = cast::<UserInfoHeader>(data);
-    return user_info_header.page_id != INVALID_PAGE_ID;
+// InvalidAccountData when data is too short to hold a UserInfo header
+pub fn is_user_active(data: &[u8]) -> Result<bool, ProgramError> {
+    let user_info_header = UserInfoHeader::unpack_from_slice(data)?;
+    Ok(user_info_header.page_id != INVALID_PAGE_ID)
+}
+
+// the page with the most free slots, highest page id among ties; see try_get_ranked_page_ids for
+// fallbacks when the contract rejects it
+pub fn get_best_page_id(data: &[u8]) -> Result<u16, ProgramError> {
+    Ok(try_get_ranked_page_ids(data)?[0])
+}
+
+// ERR_NO_AVAILABLE_SLOTS when every page is full, InvalidAccountData on a short account
+pub fn try_get_ranked_page_ids(data: &[u8]) -> Result<Vec<u16>, ProgramError> {
+    UserPagesStats::unpack_from_slice(data)?.ranked_page_ids()
 }
 
-pub fn get_best_page_id(data:&[u8]) -> u16 {
-    let user_pages_stats = cast::<UserPagesStats>(data);
-    #[allow(unaligned_references)] {
-        let max_page_id = user_pages_stats.num_free_slots.iter().position_max().unwrap();
-        assert!(max_page_id < NUM_PAGES);
-        return max_page_id as u16;
+#[cfg(test)]
+pub mod users_page_test {
+    use super::*;
+
+    #[test]
+    fn test_ranked_page_ids() {
+        let mut data = vec![0u8; NUM_PAGES * 2];
+        assert_eq!(
+            try_get_ranked_page_ids(&data),
+            Err(ProgramError::Custom(consts::ERR_NO_AVAILABLE_SLOTS))
+        );
+
+        data[2 * 7..2 * 7 + 2].copy_from_slice(&3u16.to_le_bytes());
+        data[2 * 4..2 * 4 + 2].copy_from_slice(&5u16.to_le_bytes());
+        data[2 * 2..2 * 2 + 2].copy_from_slice(&3u16.to_le_bytes());
+        assert_eq!(try_get_ranked_page_ids(&data), Ok(vec![4, 7, 2]));
+
+        assert_eq!(get_best_page_id(&data), Ok(4));
+
+        let stats = UserPagesStats::unpack_from_slice(&data).unwrap();
+        assert_eq!(stats.num_free_slots().count(), NUM_PAGES);
+        assert_eq!(stats.num_free_slots().nth(4), Some(5));
+
+        assert_eq!(
+            try_get_ranked_page_ids(&data[..10]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_best_page_id() {
+        let mut data = vec![0u8; NUM_PAGES * 2];
+        assert_eq!(
+            get_best_page_id(&data),
+            Err(ProgramError::Custom(consts::ERR_NO_AVAILABLE_SLOTS))
+        );
+        assert_eq!(
+            get_best_page_id(&data[..10]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // ties go to the highest page id, as position_max did
+        data[2 * 9..2 * 9 + 2].copy_from_slice(&6u16.to_le_bytes());
+        data[2 * 3..2 * 3 + 2].copy_from_slice(&6u16.to_le_bytes());
+        assert_eq!(get_best_page_id(&data), Ok(9));
+    }
+
+    #[test]
+    fn test_is_user_active() {
+        assert_eq!(is_user_active(&[7, 0, 1]), Ok(true));
+        assert_eq!(is_user_active(&INVALID_PAGE_ID.to_le_bytes()), Ok(false));
+        assert_eq!(is_user_active(&[7]), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_users_page() {
+        let wallet = Pubkey::new_unique();
+        let mut data = vec![0u8; 4 * 32];
+        data[64..96].copy_from_slice(wallet.as_ref());
+
+        let page = UsersPage::unpack_from_slice(&data).unwrap();
+        assert_eq!(page.slots.len(), 4);
+        assert_eq!(page.wallets().collect::<Vec<_>>(), vec![(2, &wallet)]);
+        assert_eq!(page.num_free_slots(), 3);
+        assert_eq!(page.slot_of(&wallet), Some(2));
+        assert_eq!(page.slot_of(&Pubkey::new_unique()), None);
     }
 }
@@ -41,7 +41,7 @@ fn main() {
         "pool" => {
             assert_eq!(1, args.len(), "Missing pool token symbol.");
             let token_name = args.nth(0).unwrap();
-            let pool_id = config::get_pool_id_by_name(token_name.as_str());
+            let pool_id = parse_pool_id(token_name.as_str());
             get_pool(&conn, pool_id)
         }
         "deposit" => {
@@ -61,7 +61,7 @@ fn main() {
                 token_name,
                 amount
             );
-            let pool_id = config::get_pool_id_by_name(token_name.as_str());
+            let pool_id = parse_pool_id(token_name.as_str());
             deposit(&conn, &user_keypair, pool_id, amount);
         }
         "withdraw-half" => {
@@ -69,7 +69,7 @@ fn main() {
             let user_wallet_keypair_str = args.nth(0).unwrap();
             let user_keypair = read_key_from_file(user_wallet_keypair_str.as_str());
             let token_name = args.nth(0).unwrap();
-            let pool_id = config::get_pool_id_by_name(token_name.as_str());
+            let pool_id = parse_pool_id(token_name.as_str());
 
             let balance = get_user_deposit_amount(&conn, &user_keypair.pubkey(), pool_id);
             let amount = (balance as f64 * 0.5) as u64;
@@ -87,7 +87,7 @@ fn main() {
             let user_wallet_keypair_str = args.nth(0).unwrap();
             let user_keypair = read_key_from_file(user_wallet_keypair_str.as_str());
             let token_name = args.nth(0).unwrap();
-            let pool_id = config::get_pool_id_by_name(token_name.as_str());
+            let pool_id = parse_pool_id(token_name.as_str());
 
             println!(
                 "User pubkey: {}, token: {}",
@@ -121,6 +121,16 @@ fn main() {
         _ => println!("Invalid command: {}", command),
     }
 
+    fn parse_pool_id(token_name: &str) -> u8 {
+        match config::try_get_pool_id_by_name(token_name) {
+            Ok(pool_id) => pool_id,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
     fn read_key_from_file(key_path: &str) -> Keypair {
         let mut file = File::open(key_path).unwrap();
         let mut key_str = String::from("");
@@ -262,7 +272,6 @@ fn main() {
 
 }
 
-#[allow(unaligned_references)]
 fn get_user_info(conn: &RpcClient, user_wallet_key: &Pubkey) {
     let user_info_key = consts::get_user_info_k(user_wallet_key);
     println!("UserInfo Key: {}", user_info_key);
@@ -306,7 +315,7 @@ fn is_user_active(conn: &RpcClient, user_wallet_key: &Pubkey) -> bool {
     if user_info_acc.is_err() {
         false
     } else {
-        utils::is_user_active(user_info_acc.unwrap().data())
+        utils::is_user_active(user_info_acc.unwrap().data()).unwrap()
     }
 }
 
@@ -314,5 +323,5 @@ fn get_best_page_id(conn: &RpcClient) -> u16 {
     let user_stats_key = consts::get_user_pages_stats_k();
     println!("user pages stats: {}", user_stats_key);
     let user_stats_data = conn.get_account_data(&user_stats_key).unwrap();
-    utils::get_best_page_id(user_stats_data.as_slice())
+    utils::get_best_page_id(user_stats_data.as_slice()).unwrap()
 }
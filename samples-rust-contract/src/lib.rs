@@ -5,6 +5,7 @@ use solana_program::{
     program,
     pubkey::Pubkey,
 };
+use apricot_client::accounts::{BorrowAccounts, DepositAccounts};
 use apricot_client::instructions;
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -39,18 +40,22 @@ pub fn process_instruction( _program_id: &Pubkey, accounts: &[AccountInfo], _dat
     let asset_pool_spl_1_a = next_account_info(account_iter)?;
 
     let base_pda_a = next_account_info(account_iter)?;
-    let system_program_a = next_account_info(account_iter)?;
+    let _system_program_a = next_account_info(account_iter)?;
     let token_program_a = next_account_info(account_iter)?;
     let apricot_program_a = next_account_info(account_iter)?;
 
+    let deposit_accounts = DepositAccounts {
+        user_wallet: *user_wallet_a.key,
+        user_spl: *user_spl_0_a.key,
+        user_info: *user_info_a.key,
+        asset_pool: *asset_pool_0_a.key,
+        asset_pool_spl: *asset_pool_spl_0_a.key,
+        pool_summaries: *pool_summaries_a.key,
+        price_summaries: *price_summaries_a.key,
+        token_program: *token_program_a.key,
+    };
     let deposit_ix = instructions::deposit_full(
-        user_wallet_a.key,
-        user_spl_0_a.key,
-        user_info_a.key,
-        asset_pool_0_a.key,
-        asset_pool_spl_0_a.key,
-        pool_summaries_a.key,
-        token_program_a.key,
+        &deposit_accounts,
         apricot_program_a.key,
 
         1000000000,
@@ -65,21 +70,24 @@ pub fn process_instruction( _program_id: &Pubkey, accounts: &[AccountInfo], _dat
         asset_pool_0_a.clone(),
         asset_pool_spl_0_a.clone(),
         pool_summaries_a.clone(),
-        system_program_a.clone(),
+        price_summaries_a.clone(),
         token_program_a.clone(),
         apricot_program_a.clone(),
     ])?;
 
+    let borrow_accounts = BorrowAccounts {
+        user_wallet: *user_wallet_a.key,
+        user_spl: *user_spl_1_a.key,
+        user_info: *user_info_a.key,
+        asset_pool: *asset_pool_1_a.key,
+        asset_pool_spl: *asset_pool_spl_1_a.key,
+        pool_summaries: *pool_summaries_a.key,
+        price_summaries: *price_summaries_a.key,
+        base_pda: *base_pda_a.key,
+        token_program: *token_program_a.key,
+    };
     let borrow_ix = instructions::borrow_full(
-        user_wallet_a.key,
-        user_spl_1_a.key,
-        user_info_a.key,
-        asset_pool_1_a.key,
-        asset_pool_spl_1_a.key,
-        pool_summaries_a.key,
-        price_summaries_a.key,
-        base_pda_a.key,
-        token_program_a.key,
+        &borrow_accounts,
         apricot_program_a.key,
 
         1000000,